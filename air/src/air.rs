@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use core::ops::{Add, Mul, Sub};
 
 use p3_field::{AbstractExtensionField, AbstractField, ExtensionField, Field};
@@ -12,6 +13,34 @@ pub trait BaseAir<F>: Sync {
     fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
         None
     }
+
+    /// The width of `preprocessed_trace`, or 0 if there is none. AIRs with a preprocessed trace
+    /// can override this to avoid generating the trace just to learn its width.
+    fn preprocessed_width(&self) -> usize {
+        self.preprocessed_trace().map_or(0, |trace| trace.width)
+    }
+
+    /// The witness stages committed after `main`, for AIRs with columns that depend on challenges
+    /// drawn from earlier commitments. Their traces are accessed via `MultiStageAirBuilder`.
+    fn later_stages(&self) -> Vec<StageShape> {
+        Vec::new()
+    }
+
+    /// The number of public values the AIR reads via `AirBuilderWithPublicValues`.
+    fn num_public_values(&self) -> usize {
+        0
+    }
+}
+
+/// The shape of a witness stage committed after `main`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StageShape {
+    /// The number of challenges drawn, after committing to all earlier stages, before this stage's
+    /// trace is generated.
+    pub num_challenges: usize,
+    /// The number of columns in this stage's trace. These are over the extension field, like its
+    /// challenges.
+    pub width: usize,
 }
 
 /// An AIR that works with a particular `AirBuilder`.
@@ -91,8 +120,62 @@ pub trait AirBuilder: Sized {
         self.when(self.is_transition_window(size))
     }
 
+    /// Returns a sub-builder whose constraints are enforced only on the rows where the main trace
+    /// column `selector_col` is nonzero.
+    ///
+    /// The column should be boolean, though this doesn't constrain it. Each constraint is
+    /// multiplied by the selector, so its degree grows by one.
+    fn when_rows(&mut self, selector_col: usize) -> FilteredAirBuilder<Self> {
+        let selector = self.main().row_slice(0)[selector_col];
+        self.when(selector)
+    }
+
+    /// Returns `a` if `cond` is one and `b` if it is zero, i.e. `cond * a + (1 - cond) * b`.
+    ///
+    /// This doesn't constrain `cond`, which should be asserted boolean separately.
+    fn select<IC, IA, IB>(&self, cond: IC, a: IA, b: IB) -> Self::Expr
+    where
+        IC: Into<Self::Expr>,
+        IA: Into<Self::Expr>,
+        IB: Into<Self::Expr>,
+    {
+        let b = b.into();
+        b.clone() + cond.into() * (a.into() - b)
+    }
+
+    /// Applies `select` to each pair of elements of `a` and `b`, which must have the same length.
+    fn select_slices<IC, I>(&self, cond: IC, a: &[I], b: &[I]) -> Vec<Self::Expr>
+    where
+        IC: Into<Self::Expr>,
+        I: Into<Self::Expr> + Clone,
+    {
+        assert_eq!(a.len(), b.len(), "selected slices have different lengths");
+        let cond = cond.into();
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| self.select(cond.clone(), a.clone(), b.clone()))
+            .collect()
+    }
+
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I);
 
+    /// Assert that each of `exprs` is zero, as a group.
+    ///
+    /// This is equivalent to calling `assert_zero` on each of them, and doesn't make the quotient
+    /// any smaller. The prover and verifier fold the batch with powers of their random challenge,
+    /// like all other constraints, so they are all zero iff the folded result is zero, with high
+    /// probability. Builders which check constraints directly can report which one of the batch
+    /// failed.
+    fn assert_zero_many<I, It>(&mut self, exprs: It)
+    where
+        I: Into<Self::Expr>,
+        It: IntoIterator<Item = I>,
+    {
+        for x in exprs {
+            self.assert_zero(x);
+        }
+    }
+
     fn assert_one<I: Into<Self::Expr>>(&mut self, x: I) {
         self.assert_zero(x.into() - Self::Expr::one());
     }
@@ -110,6 +193,35 @@ pub trait AirBuilder: Sized {
 
 pub trait PairBuilder: AirBuilder {
     fn preprocessed(&self) -> Self::M;
+
+    /// Like `when_rows`, but with the selector taken from the preprocessed trace column
+    /// `selector_col`, so that the prover can't choose which rows it applies to.
+    fn when_preprocessed_rows(&mut self, selector_col: usize) -> FilteredAirBuilder<Self> {
+        let selector = self.preprocessed().row_slice(0)[selector_col];
+        self.when(selector)
+    }
+}
+
+/// An `AirBuilder` for AIRs with constraints on every `period`-th row.
+pub trait PeriodicAirBuilder: AirBuilder {
+    /// A selector which is nonzero exactly on the rows `r` with `r % period == offset`.
+    ///
+    /// `period` must be a power of two no larger than the trace height, and `offset < period`.
+    fn is_periodic(&self, period: usize, offset: usize) -> Self::Expr;
+
+    /// Returns a sub-builder whose constraints are enforced only on the rows `r` with
+    /// `r % period == offset`. See `is_periodic` for the allowed parameters.
+    fn when_periodic(&mut self, period: usize, offset: usize) -> FilteredAirBuilder<Self> {
+        self.when(self.is_periodic(period, offset))
+    }
+}
+
+/// An `AirBuilder` for AIRs with constraints on the position of the current row.
+pub trait RowPointAirBuilder: AirBuilder {
+    /// The point `g^r` at the current row `r`, where `g` generates the subgroup whose order is the
+    /// trace height `n`. Unlike `r` itself, which is a function of the row of degree `n - 1`, it
+    /// has degree one, so constraints on the row index are written in terms of it.
+    fn row_point(&self) -> Self::Expr;
 }
 
 pub trait ExtensionBuilder: AirBuilder {
@@ -149,6 +261,39 @@ pub trait PermutationAirBuilder: ExtensionBuilder {
     fn permutation_randomness(&self) -> &[Self::EF];
 }
 
+/// An `AirBuilder` for AIRs whose witness is committed in stages, as described by
+/// `BaseAir::later_stages`. Stages are indexed from 0, starting with the first stage after `main`.
+pub trait MultiStageAirBuilder: ExtensionBuilder {
+    type MS: MatrixRowSlices<Self::VarEF>;
+
+    fn later_stage(&self, stage: usize) -> Self::MS;
+
+    /// The challenges drawn before the given stage's trace was generated. Like
+    /// `permutation_randomness`, these are drawn from the extension field.
+    fn later_stage_challenges(&self, stage: usize) -> &[Self::ExprEF];
+}
+
+/// An `AirBuilder` for AIRs which read the public values of the proof, such as a digest of the
+/// trace which is exposed for use by later proofs.
+pub trait AirBuilderWithPublicValues: AirBuilder {
+    fn public_values(&self) -> &[Self::Expr];
+
+    /// Assert that `values`, e.g. cells of the trace, equal the first public values, in order.
+    ///
+    /// # Panics
+    /// Panics if there are more `values` than public values.
+    fn assert_eq_public_values<I, It>(&mut self, values: It)
+    where
+        I: Into<Self::Expr>,
+        It: IntoIterator<Item = I>,
+    {
+        for (index, value) in values.into_iter().enumerate() {
+            let public_value = self.public_values()[index].clone();
+            self.assert_eq(value, public_value);
+        }
+    }
+}
+
 pub struct FilteredAirBuilder<'a, AB: AirBuilder> {
     pub inner: &'a mut AB,
     condition: AB::Expr,
@@ -181,6 +326,30 @@ impl<'a, AB: AirBuilder> AirBuilder for FilteredAirBuilder<'a, AB> {
     }
 }
 
+impl<'a, AB: PairBuilder> PairBuilder for FilteredAirBuilder<'a, AB> {
+    fn preprocessed(&self) -> Self::M {
+        self.inner.preprocessed()
+    }
+}
+
+impl<'a, AB: PeriodicAirBuilder> PeriodicAirBuilder for FilteredAirBuilder<'a, AB> {
+    fn is_periodic(&self, period: usize, offset: usize) -> Self::Expr {
+        self.inner.is_periodic(period, offset)
+    }
+}
+
+impl<'a, AB: RowPointAirBuilder> RowPointAirBuilder for FilteredAirBuilder<'a, AB> {
+    fn row_point(&self) -> Self::Expr {
+        self.inner.row_point()
+    }
+}
+
+impl<'a, AB: AirBuilderWithPublicValues> AirBuilderWithPublicValues for FilteredAirBuilder<'a, AB> {
+    fn public_values(&self) -> &[Self::Expr] {
+        self.inner.public_values()
+    }
+}
+
 impl<'a, AB: ExtensionBuilder> ExtensionBuilder for FilteredAirBuilder<'a, AB> {
     type EF = AB::EF;
     type VarEF = AB::VarEF;
@@ -207,6 +376,18 @@ impl<'a, AB: PermutationAirBuilder> PermutationAirBuilder for FilteredAirBuilder
     }
 }
 
+impl<'a, AB: MultiStageAirBuilder> MultiStageAirBuilder for FilteredAirBuilder<'a, AB> {
+    type MS = AB::MS;
+
+    fn later_stage(&self, stage: usize) -> Self::MS {
+        self.inner.later_stage(stage)
+    }
+
+    fn later_stage_challenges(&self, stage: usize) -> &[Self::ExprEF] {
+        self.inner.later_stage_challenges(stage)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use p3_matrix::MatrixRowSlices;
@@ -0,0 +1,158 @@
+/// Implements named column access for a `#[repr(C)]` struct of columns, such as
+///
+/// ```ignore
+/// #[repr(C)]
+/// pub struct CpuCols<T> {
+///     pub pc: T,
+///     pub opcode: T,
+///     pub registers: [T; 8],
+/// }
+///
+/// // SAFETY: `CpuCols` is `#[repr(C)]`, and each of its fields is a `T` or an array of `T`.
+/// impl_columns!(unsafe CpuCols);
+/// ```
+///
+/// # Safety
+/// The generated code reinterprets rows of `T`s as the struct and back, so the struct must be
+/// `#[repr(C)]`, and every field must be a `T` or a (possibly nested) array of `T`. The macro can't
+/// check the attribute, so callers acknowledge the contract by writing `unsafe` before the struct's
+/// name. A field of any other type is caught by compile-time checks on the struct's size and
+/// alignment.
+///
+/// This generates
+/// - `CpuCols::<T>::NUM_COLS`, the number of columns;
+/// - `CpuCols::from_row` and `CpuCols::from_row_mut`, which view a row as a `CpuCols`;
+/// - `Borrow<CpuCols<T>>` and `BorrowMut<CpuCols<T>>` for `[T]`;
+/// - `CpuCols::<usize>::COL_MAP`, a `CpuCols` holding the index of each column, so that e.g.
+///   `VirtualPairCol::single_main(CpuCols::<usize>::COL_MAP.pc)` refers to the `pc` column.
+#[macro_export]
+macro_rules! impl_columns {
+    (unsafe $cols:ident) => {
+        // A field other than `T` or an array of `T` would make the size stop scaling with `T`, or
+        // raise the alignment above that of `T`.
+        const _: () = {
+            assert!(core::mem::size_of::<$cols<u8>>() * 8 == core::mem::size_of::<$cols<u64>>());
+            assert!(core::mem::align_of::<$cols<u8>>() == 1);
+            assert!(core::mem::align_of::<$cols<u64>>() == core::mem::align_of::<u64>());
+        };
+
+        impl<T> $cols<T> {
+            pub const NUM_COLS: usize = core::mem::size_of::<$cols<u8>>();
+
+            pub fn from_row(row: &[T]) -> &Self {
+                core::borrow::Borrow::borrow(row)
+            }
+
+            pub fn from_row_mut(row: &mut [T]) -> &mut Self {
+                core::borrow::BorrowMut::borrow_mut(row)
+            }
+        }
+
+        impl $cols<usize> {
+            pub const COL_MAP: Self = {
+                let mut indices = [0; $cols::<u8>::NUM_COLS];
+                let mut i = 0;
+                while i < indices.len() {
+                    indices[i] = i;
+                    i += 1;
+                }
+                // SAFETY: The caller guarantees that `Self` is laid out like `[usize; NUM_COLS]`.
+                unsafe { core::mem::transmute::<[usize; $cols::<u8>::NUM_COLS], Self>(indices) }
+            };
+        }
+
+        impl<T> core::borrow::Borrow<$cols<T>> for [T] {
+            fn borrow(&self) -> &$cols<T> {
+                assert_eq!(self.len(), $cols::<T>::NUM_COLS);
+                // SAFETY: The caller guarantees that `$cols<T>` is laid out like `[T; NUM_COLS]`.
+                let (prefix, cols, suffix) = unsafe { self.align_to::<$cols<T>>() };
+                debug_assert!(prefix.is_empty(), "Alignment should match");
+                debug_assert!(suffix.is_empty(), "Alignment should match");
+                debug_assert_eq!(cols.len(), 1);
+                &cols[0]
+            }
+        }
+
+        impl<T> core::borrow::BorrowMut<$cols<T>> for [T] {
+            fn borrow_mut(&mut self) -> &mut $cols<T> {
+                assert_eq!(self.len(), $cols::<T>::NUM_COLS);
+                // SAFETY: As for `borrow`.
+                let (prefix, cols, suffix) = unsafe { self.align_to_mut::<$cols<T>>() };
+                debug_assert!(prefix.is_empty(), "Alignment should match");
+                debug_assert!(suffix.is_empty(), "Alignment should match");
+                debug_assert_eq!(cols.len(), 1);
+                &mut cols[0]
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use p3_field::AbstractField;
+    use p3_mersenne_31::Mersenne31;
+
+    use crate::VirtualPairCol;
+
+    type F = Mersenne31;
+
+    #[repr(C)]
+    struct TestCols<T> {
+        pc: T,
+        registers: [T; 3],
+        limbs: [[T; 2]; 2],
+        opcode: T,
+    }
+
+    // SAFETY: `TestCols` is `#[repr(C)]`, and its fields are all `T`s and arrays of `T`.
+    impl_columns!(unsafe TestCols);
+
+    fn row() -> Vec<F> {
+        (0..9).map(|i| F::new(100 + i)).collect()
+    }
+
+    #[test]
+    fn test_num_cols() {
+        assert_eq!(TestCols::<F>::NUM_COLS, 9);
+        assert_eq!(TestCols::<u8>::NUM_COLS, TestCols::<u64>::NUM_COLS);
+    }
+
+    #[test]
+    fn test_from_row_matches_indexing() {
+        let row = row();
+        let cols = TestCols::from_row(&row);
+        assert_eq!(cols.pc, row[0]);
+        assert_eq!(cols.registers, [row[1], row[2], row[3]]);
+        assert_eq!(cols.limbs, [[row[4], row[5]], [row[6], row[7]]]);
+        assert_eq!(cols.opcode, row[8]);
+    }
+
+    #[test]
+    fn test_from_row_mut() {
+        let mut row = row();
+        TestCols::from_row_mut(&mut row).registers[1] = F::zero();
+        assert_eq!(row[2], F::zero());
+    }
+
+    #[test]
+    fn test_col_map() {
+        let map = TestCols::<usize>::COL_MAP;
+        assert_eq!(map.pc, 0);
+        assert_eq!(map.registers, [1, 2, 3]);
+        assert_eq!(map.limbs, [[4, 5], [6, 7]]);
+        assert_eq!(map.opcode, 8);
+
+        let row = row();
+        let col = VirtualPairCol::single_main(map.opcode);
+        assert_eq!(col.apply::<F, F>(&[], &row), row[8]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_row_wrong_width() {
+        let row = row();
+        TestCols::from_row(&row[1..]);
+    }
+}
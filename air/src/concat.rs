@@ -0,0 +1,180 @@
+use core::ops::Range;
+
+use p3_field::Field;
+use p3_matrix::column_range::ColumnRangeView;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+use crate::{
+    Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, ExtensionBuilder, PairBuilder,
+    PeriodicAirBuilder, RowPointAirBuilder,
+};
+
+/// An AIR whose trace is `left`'s trace followed by `right`'s, and whose constraints are those of
+/// both. Each side sees only its own columns, in both the main and preprocessed traces.
+///
+/// Later stages aren't supported, since the two sides would draw their challenges separately.
+pub struct ConcatAir<A, B> {
+    pub left: A,
+    pub right: B,
+}
+
+impl<A, B> ConcatAir<A, B> {
+    pub fn new(left: A, right: B) -> Self {
+        Self { left, right }
+    }
+}
+
+impl<F: Field, A: BaseAir<F>, B: BaseAir<F>> BaseAir<F> for ConcatAir<A, B> {
+    fn width(&self) -> usize {
+        self.left.width() + self.right.width()
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        match (
+            self.left.preprocessed_trace(),
+            self.right.preprocessed_trace(),
+        ) {
+            (None, None) => None,
+            (Some(trace), None) | (None, Some(trace)) => Some(trace),
+            (Some(left), Some(right)) => {
+                assert_eq!(
+                    left.height(),
+                    right.height(),
+                    "preprocessed traces have different heights"
+                );
+                let values = left
+                    .rows()
+                    .zip(right.rows())
+                    .flat_map(|(l, r)| l.iter().chain(r).copied())
+                    .collect();
+                Some(RowMajorMatrix::new(values, left.width() + right.width()))
+            }
+        }
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        self.left.preprocessed_width() + self.right.preprocessed_width()
+    }
+
+    /// Both sides see the same public values.
+    fn num_public_values(&self) -> usize {
+        self.left
+            .num_public_values()
+            .max(self.right.num_public_values())
+    }
+}
+
+/// Evaluates `left` on the first `left.width()` columns and `right` on the rest. Each side's
+/// `preprocessed_width` is called for every evaluation, so AIRs with a preprocessed trace should
+/// override it rather than generate the trace to measure it.
+impl<AB, A, B> Air<AB> for ConcatAir<A, B>
+where
+    AB: AirBuilder,
+    A: BaseAir<AB::F> + for<'a> Air<SubAirBuilder<'a, AB>>,
+    B: BaseAir<AB::F> + for<'a> Air<SubAirBuilder<'a, AB>>,
+{
+    fn eval(&self, builder: &mut AB) {
+        let left_width = BaseAir::<AB::F>::width(&self.left);
+        let right_width = BaseAir::<AB::F>::width(&self.right);
+        let left_preprocessed_width = BaseAir::<AB::F>::preprocessed_width(&self.left);
+        let right_preprocessed_width = BaseAir::<AB::F>::preprocessed_width(&self.right);
+
+        self.left.eval(&mut SubAirBuilder::new(
+            builder,
+            0..left_width,
+            0..left_preprocessed_width,
+        ));
+        self.right.eval(&mut SubAirBuilder::new(
+            builder,
+            left_width..left_width + right_width,
+            left_preprocessed_width..left_preprocessed_width + right_preprocessed_width,
+        ));
+    }
+}
+
+/// A builder which exposes a range of the columns of `inner`'s traces, so that an AIR can be
+/// evaluated as one part of a wider AIR.
+pub struct SubAirBuilder<'a, AB: AirBuilder> {
+    inner: &'a mut AB,
+    main_columns: Range<usize>,
+    preprocessed_columns: Range<usize>,
+}
+
+impl<'a, AB: AirBuilder> SubAirBuilder<'a, AB> {
+    pub(crate) fn new(
+        inner: &'a mut AB,
+        main_columns: Range<usize>,
+        preprocessed_columns: Range<usize>,
+    ) -> Self {
+        Self {
+            inner,
+            main_columns,
+            preprocessed_columns,
+        }
+    }
+}
+
+impl<'a, AB: AirBuilder> AirBuilder for SubAirBuilder<'a, AB> {
+    type F = AB::F;
+    type Expr = AB::Expr;
+    type Var = AB::Var;
+    type M = ColumnRangeView<AB::M>;
+
+    fn main(&self) -> Self::M {
+        ColumnRangeView::new(self.inner.main(), self.main_columns.clone())
+    }
+
+    fn is_first_row(&self) -> Self::Expr {
+        self.inner.is_first_row()
+    }
+
+    fn is_last_row(&self) -> Self::Expr {
+        self.inner.is_last_row()
+    }
+
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        self.inner.is_transition_window(size)
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        self.inner.assert_zero(x);
+    }
+}
+
+impl<'a, AB: PairBuilder> PairBuilder for SubAirBuilder<'a, AB> {
+    fn preprocessed(&self) -> Self::M {
+        ColumnRangeView::new(self.inner.preprocessed(), self.preprocessed_columns.clone())
+    }
+}
+
+impl<'a, AB: PeriodicAirBuilder> PeriodicAirBuilder for SubAirBuilder<'a, AB> {
+    fn is_periodic(&self, period: usize, offset: usize) -> Self::Expr {
+        self.inner.is_periodic(period, offset)
+    }
+}
+
+impl<'a, AB: RowPointAirBuilder> RowPointAirBuilder for SubAirBuilder<'a, AB> {
+    fn row_point(&self) -> Self::Expr {
+        self.inner.row_point()
+    }
+}
+
+impl<'a, AB: AirBuilderWithPublicValues> AirBuilderWithPublicValues for SubAirBuilder<'a, AB> {
+    fn public_values(&self) -> &[Self::Expr] {
+        self.inner.public_values()
+    }
+}
+
+impl<'a, AB: ExtensionBuilder> ExtensionBuilder for SubAirBuilder<'a, AB> {
+    type EF = AB::EF;
+    type VarEF = AB::VarEF;
+    type ExprEF = AB::ExprEF;
+
+    fn assert_zero_ext<I>(&mut self, x: I)
+    where
+        I: Into<Self::ExprEF>,
+    {
+        self.inner.assert_zero_ext(x);
+    }
+}
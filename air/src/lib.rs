@@ -5,9 +5,14 @@
 extern crate alloc;
 
 mod air;
+mod columns;
+mod concat;
+mod row_context;
 mod two_row_matrix;
 mod virtual_column;
 
 pub use air::*;
+pub use concat::*;
+pub use row_context::*;
 pub use two_row_matrix::*;
 pub use virtual_column::*;
@@ -0,0 +1,59 @@
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::{Air, BaseAir, RowPointAirBuilder, SubAirBuilder};
+
+/// An AIR which evaluates `inner` with a `RowContextBuilder`, giving its constraints access to the
+/// current row alongside the boundary flags.
+///
+/// Nothing is committed for this. The row `r` is identified by `RowPointAirBuilder::row_point`, the
+/// point `g^r` of the trace's subgroup at which the builder already evaluates its selectors, so
+/// `inner` works with traces of any height. The index `r` itself has degree `n - 1` as a function
+/// of the row, so an AIR which needs it as a field element should keep it in a column, starting at
+/// zero and incremented on each transition.
+pub struct WithRowContext<A> {
+    pub inner: A,
+}
+
+impl<A> WithRowContext<A> {
+    pub fn new(inner: A) -> Self {
+        Self { inner }
+    }
+}
+
+impl<F: Field, A: BaseAir<F>> BaseAir<F> for WithRowContext<A> {
+    fn width(&self) -> usize {
+        self.inner.width()
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        self.inner.preprocessed_trace()
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        self.inner.preprocessed_width()
+    }
+
+    fn num_public_values(&self) -> usize {
+        self.inner.num_public_values()
+    }
+}
+
+impl<AB, A> Air<AB> for WithRowContext<A>
+where
+    AB: RowPointAirBuilder,
+    A: BaseAir<AB::F> + for<'a> Air<RowContextBuilder<'a, AB>>,
+{
+    fn eval(&self, builder: &mut AB) {
+        let width = BaseAir::<AB::F>::width(&self.inner);
+        let preprocessed_width = BaseAir::<AB::F>::preprocessed_width(&self.inner);
+        self.inner.eval(&mut SubAirBuilder::new(
+            builder,
+            0..width,
+            0..preprocessed_width,
+        ));
+    }
+}
+
+/// The builder `WithRowContext` evaluates its inner AIR with, which sees all of the columns.
+pub type RowContextBuilder<'a, AB> = SubAirBuilder<'a, AB>;
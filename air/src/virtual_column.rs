@@ -1,5 +1,6 @@
 use alloc::vec;
 use alloc::vec::Vec;
+use core::iter;
 use core::ops::Mul;
 
 use p3_field::{AbstractField, Field};
@@ -12,12 +13,16 @@ pub struct VirtualPairCol<F: Field> {
 }
 
 /// A column in a PAIR, i.e. either a preprocessed column or a main trace column.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PairCol {
     Preprocessed(usize),
     Main(usize),
 }
 
+/// The error returned by `VirtualPairCol::to_dense` when a column lies outside the given widths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColumnOutOfRange(pub PairCol);
+
 impl PairCol {
     fn get<T: Copy>(&self, preprocessed: &[T], main: &[T]) -> T {
         match self {
@@ -110,16 +115,168 @@ impl<F: Field> VirtualPairCol<F> {
         Self::new_main(vec![(a_col, F::one()), (b_col, F::neg_one())], F::zero())
     }
 
+    /// Multiply every weight, as well as the constant, by `factor`.
+    pub fn scale_in_place(&mut self, factor: F) {
+        for (_, weight) in &mut self.column_weights {
+            *weight *= factor;
+        }
+        self.constant *= factor;
+    }
+
+    /// Add `c` to the constant term.
+    pub fn add_constant(&mut self, c: F) {
+        self.constant += c;
+    }
+
+    /// Add the term `weight * col`.
+    pub fn push_term(&mut self, col: PairCol, weight: F) {
+        self.column_weights.push((col, weight));
+    }
+
     pub fn apply<Expr, Var>(&self, preprocessed: &[Var], main: &[Var]) -> Expr
     where
         F: Into<Expr>,
         Expr: AbstractField + Mul<F, Output = Expr>,
         Var: Into<Expr> + Copy,
     {
-        let mut result = self.constant.into();
-        for (column, weight) in &self.column_weights {
-            result += column.get(preprocessed, main).into() * *weight;
+        let terms = self
+            .column_weights
+            .iter()
+            .map(|&(column, weight)| column.get(preprocessed, main).into() * weight);
+        Expr::sum_iter(iter::once(self.constant.into()).chain(terms))
+    }
+
+    /// Like `apply`, for a row of field elements, e.g. while generating a trace. The weighted terms
+    /// are summed with `Field::sum_slice`, a packing's width at a time.
+    pub fn apply_to_row(&self, preprocessed: &[F], main: &[F]) -> F {
+        let terms = self
+            .column_weights
+            .iter()
+            .map(|&(column, weight)| column.get(preprocessed, main) * weight)
+            .collect::<Vec<_>>();
+        F::sum_slice(&terms) + self.constant
+    }
+
+    /// The weights of this column as a dense row over `[preprocessed | main | 1]`, i.e. a vector of
+    /// length `num_preprocessed + num_main + 1` ending in the constant, so that `apply` becomes a
+    /// dot product. Weights of repeated columns are summed.
+    pub fn to_dense(
+        &self,
+        num_preprocessed: usize,
+        num_main: usize,
+    ) -> Result<Vec<F>, ColumnOutOfRange> {
+        let mut dense = vec![F::zero(); num_preprocessed + num_main + 1];
+        for &(column, weight) in &self.column_weights {
+            let index = match column {
+                PairCol::Preprocessed(i) if i < num_preprocessed => i,
+                PairCol::Main(i) if i < num_main => num_preprocessed + i,
+                _ => return Err(ColumnOutOfRange(column)),
+            };
+            dense[index] += weight;
         }
-        result
+        dense[num_preprocessed + num_main] = self.constant;
+        Ok(dense)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::dot_product;
+    use p3_mersenne_31::Mersenne31;
+
+    use super::*;
+
+    type F = Mersenne31;
+
+    const PREPROCESSED: [F; 2] = [F::new(3), F::new(5)];
+    const MAIN: [F; 3] = [F::new(7), F::new(11), F::new(13)];
+
+    fn apply(col: &VirtualPairCol<F>) -> F {
+        col.apply::<F, F>(&PREPROCESSED, &MAIN)
+    }
+
+    #[test]
+    fn test_scale_in_place() {
+        let mut col = VirtualPairCol::new_main(vec![(0, F::two()), (2, F::one())], F::new(4));
+        col.scale_in_place(F::new(3));
+        let expected = VirtualPairCol::new_main(vec![(0, F::new(6)), (2, F::new(3))], F::new(12));
+        assert_eq!(apply(&col), apply(&expected));
+    }
+
+    #[test]
+    fn test_add_constant() {
+        let mut col = VirtualPairCol::diff_main(0, 1);
+        col.add_constant(F::new(9));
+        let expected = VirtualPairCol::new_main(vec![(0, F::one()), (1, F::neg_one())], F::new(9));
+        assert_eq!(apply(&col), apply(&expected));
+    }
+
+    #[test]
+    fn test_push_term() {
+        let mut col = VirtualPairCol::single_main(1);
+        col.push_term(PairCol::Preprocessed(1), F::new(8));
+        let expected = VirtualPairCol::new(
+            vec![
+                (PairCol::Main(1), F::one()),
+                (PairCol::Preprocessed(1), F::new(8)),
+            ],
+            F::zero(),
+        );
+        assert_eq!(apply(&col), apply(&expected));
+    }
+
+    #[test]
+    fn test_apply_to_row() {
+        // Enough terms to fill a few packings on any target, with some left over.
+        let column_weights = (0..37)
+            .map(|i| {
+                let column = if i % 4 == 0 {
+                    PairCol::Preprocessed(i % PREPROCESSED.len())
+                } else {
+                    PairCol::Main(i % MAIN.len())
+                };
+                (column, F::new(i as u32 + 1))
+            })
+            .collect();
+        let col = VirtualPairCol::new(column_weights, F::new(17));
+        assert_eq!(col.apply_to_row(&PREPROCESSED, &MAIN), apply(&col));
+    }
+
+    #[test]
+    fn test_to_dense() {
+        let mut col = VirtualPairCol::new(
+            vec![
+                (PairCol::Main(2), F::new(2)),
+                (PairCol::Preprocessed(0), F::new(6)),
+                (PairCol::Main(0), F::neg_one()),
+                (PairCol::Main(2), F::new(3)),
+            ],
+            F::new(10),
+        );
+        col.push_term(PairCol::Preprocessed(1), F::new(4));
+
+        let dense = col.to_dense(PREPROCESSED.len(), MAIN.len()).unwrap();
+        assert_eq!(dense.len(), PREPROCESSED.len() + MAIN.len() + 1);
+        let inputs: Vec<F> = PREPROCESSED
+            .iter()
+            .chain(&MAIN)
+            .copied()
+            .chain([F::one()])
+            .collect();
+        assert_eq!(dot_product(&dense, &inputs), apply(&col));
+    }
+
+    #[test]
+    fn test_to_dense_out_of_range() {
+        let col = VirtualPairCol::<F>::diff_main(0, 3);
+        assert_eq!(
+            col.to_dense(PREPROCESSED.len(), MAIN.len()),
+            Err(ColumnOutOfRange(PairCol::Main(3)))
+        );
+        let col = VirtualPairCol::<F>::single_preprocessed(2);
+        assert_eq!(
+            col.to_dense(PREPROCESSED.len(), MAIN.len()),
+            Err(ColumnOutOfRange(PairCol::Preprocessed(2)))
+        );
     }
 }
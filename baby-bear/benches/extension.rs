@@ -1,11 +1,21 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use p3_baby_bear::BabyBear;
 use p3_field::extension::BinomialExtensionField;
-use p3_field_testing::bench_func::{benchmark_inv, benchmark_mul, benchmark_square};
+use p3_field_testing::bench_func::{
+    benchmark_binomial_mul, benchmark_inv, benchmark_mul, benchmark_square,
+};
 
+type EF3 = BinomialExtensionField<BabyBear, 3>;
 type EF4 = BinomialExtensionField<BabyBear, 4>;
 type EF5 = BinomialExtensionField<BabyBear, 5>;
 
+fn bench_cubic_extension(c: &mut Criterion) {
+    let name = "BinomialExtensionField<BabyBear, 3>";
+    benchmark_square::<EF3>(c, name);
+    benchmark_inv::<EF3>(c, name);
+    benchmark_binomial_mul::<BabyBear, 3>(c, name);
+}
+
 fn bench_quartic_extension(c: &mut Criterion) {
     let name = "BinomialExtensionField<BabyBear, 4>";
     benchmark_square::<EF4>(c, name);
@@ -22,6 +32,7 @@ fn bench_qunitic_extension(c: &mut Criterion) {
 
 criterion_group!(
     bench_babybear_ef,
+    bench_cubic_extension,
     bench_quartic_extension,
     bench_qunitic_extension
 );
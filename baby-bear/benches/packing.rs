@@ -0,0 +1,49 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use p3_baby_bear::{BabyBear, PackedBabyBearScalar2};
+use p3_field::{dot_product_packed, PackedField};
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
+
+const N: usize = 1 << 12;
+
+/// Compares `PackedBabyBearScalar2` against plain `BabyBear`, over the same `N` elements.
+fn bench_packing(c: &mut Criterion) {
+    bench_add_mul::<BabyBear>(c, "BabyBear");
+    bench_add_mul::<PackedBabyBearScalar2>(c, "PackedBabyBearScalar2");
+
+    let mut rng = rand::thread_rng();
+    let a: Vec<BabyBear> = (0..N).map(|_| rng.gen()).collect();
+    let b: Vec<BabyBear> = (0..N).map(|_| rng.gen()).collect();
+    c.bench_function(&format!("BabyBear dot_product/{N}"), |bench| {
+        bench.iter(|| dot_product_packed::<BabyBear>(black_box(&a), black_box(&b)))
+    });
+    c.bench_function(&format!("PackedBabyBearScalar2 dot_product/{N}"), |bench| {
+        bench.iter(|| dot_product_packed::<PackedBabyBearScalar2>(black_box(&a), black_box(&b)))
+    });
+}
+
+/// Benchmark `x = x * y + z` over vectors holding `N` scalars in total.
+fn bench_add_mul<P: PackedField<Scalar = BabyBear>>(c: &mut Criterion, name: &str)
+where
+    Standard: Distribution<P>,
+{
+    let mut rng = rand::thread_rng();
+    let len = N / P::WIDTH;
+    let y: Vec<P> = (0..len).map(|_| rng.gen()).collect();
+    let z: Vec<P> = (0..len).map(|_| rng.gen()).collect();
+    c.bench_function(&format!("{name} add_mul/{N}"), |b| {
+        b.iter_batched(
+            || vec![P::one(); len],
+            |mut x| {
+                for ((x, &y), &z) in x.iter_mut().zip(&y).zip(&z) {
+                    *x = *x * y + z;
+                }
+                x
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, bench_packing);
+criterion_main!(benches);
@@ -2,13 +2,15 @@ use core::fmt::{self, Debug, Display, Formatter};
 use core::iter::{Product, Sum};
 use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
 
+use num_bigint::BigUint;
 use p3_field::{
-    exp_1725656503, exp_u64_by_squaring, AbstractField, Field, Packable, PrimeField, PrimeField32,
-    PrimeField64, TwoAdicField,
+    exp_1725656503, exp_u64_by_squaring, AbstractField, ConstantTimeField, Field, Packable,
+    PrimeField, PrimeField32, PrimeField64, TwoAdicField,
 };
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
 use serde::{Deserialize, Deserializer, Serialize};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 
 /// The Baby Bear prime
 const P: u32 = 0x78000001;
@@ -238,9 +240,32 @@ impl Field for BabyBear {
 
         Some(p1110111111111111111111111111111)
     }
+
+    fn characteristic() -> BigUint {
+        P.into()
+    }
+}
+
+impl PrimeField for BabyBear {
+    fn as_canonical_biguint(&self) -> BigUint {
+        self.as_canonical_u64().into()
+    }
 }
 
-impl PrimeField for BabyBear {}
+impl ConstantTimeField for BabyBear {
+    #[inline]
+    fn ct_eq(&self, other: &Self) -> Choice {
+        // Montgomery form is canonical, so there's no need to convert out of it.
+        self.value.ct_eq(&other.value)
+    }
+
+    #[inline]
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self {
+            value: u32::conditional_select(&a.value, &b.value, choice),
+        }
+    }
+}
 
 impl PrimeField64 for BabyBear {
     const ORDER_U64: u64 = <Self as PrimeField32>::ORDER_U32 as u64;
@@ -445,7 +470,9 @@ fn monty_reduce(x: u64) -> u32 {
 
 #[cfg(test)]
 mod tests {
-    use p3_field_testing::{test_field, test_two_adic_field};
+    use p3_field_testing::{
+        test_field, test_prime_field, test_prime_field_64, test_two_adic_field,
+    };
 
     use super::*;
 
@@ -547,6 +574,14 @@ mod tests {
         assert_eq!(m2, m2_deserialized);
     }
 
+    #[test]
+    fn test_characteristic() {
+        assert_eq!(F::characteristic(), BigUint::from(0x78000001u32));
+        assert_eq!(F::order(), F::characteristic());
+    }
+
     test_field!(crate::BabyBear);
+    test_prime_field!(crate::BabyBear);
+    test_prime_field_64!(crate::BabyBear);
     test_two_adic_field!(crate::BabyBear);
 }
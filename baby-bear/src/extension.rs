@@ -3,6 +3,31 @@ use p3_field::{field_to_array, AbstractField, TwoAdicField};
 
 use crate::BabyBear;
 
+impl BinomiallyExtendable<3> for BabyBear {
+    // Verifiable in Sage with
+    // `R.<x> = GF(p)[]; assert (x^3 - 2).is_irreducible()`.
+    fn w() -> Self {
+        Self::new(2)
+    }
+
+    // DTH_ROOT = W^((p - 1)/3)
+    fn dth_root() -> Self {
+        Self::new(1314723123)
+    }
+
+    fn ext_generator() -> [Self; 3] {
+        [Self::new(9), Self::one(), Self::zero()]
+    }
+}
+
+impl HasTwoAdicBionmialExtension<3> for BabyBear {
+    const EXT_TWO_ADICITY: usize = 27;
+
+    fn ext_two_adic_generator(bits: usize) -> [Self; 3] {
+        field_to_array::<Self, 3>(Self::two_adic_generator(bits))
+    }
+}
+
 impl BinomiallyExtendable<4> for BabyBear {
     // Verifiable in Sage with
     // `R.<x> = GF(p)[]; assert (x^4 - 11).is_irreducible()`.
@@ -80,6 +105,24 @@ impl HasTwoAdicBionmialExtension<5> for BabyBear {
     }
 }
 
+#[cfg(test)]
+mod test_cubic_extension {
+    use p3_field::extension::BinomialExtensionField;
+    use p3_field_testing::{
+        test_binomial_extension_mul, test_field, test_two_adic_extension_field,
+    };
+
+    use crate::BabyBear;
+
+    type F = BabyBear;
+    type EF = BinomialExtensionField<F, 3>;
+
+    test_field!(super::EF);
+    test_binomial_extension_mul!(super::F, 3);
+
+    test_two_adic_extension_field!(super::F, super::EF);
+}
+
 #[cfg(test)]
 mod test_quartic_extension {
     use alloc::format;
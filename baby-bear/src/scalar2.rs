@@ -0,0 +1,467 @@
+use core::iter::{Product, Sum};
+use core::mem::transmute;
+use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use p3_field::{AbstractField, Field, PackedField, PackedValue};
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
+
+use crate::BabyBear;
+
+const WIDTH: usize = 2;
+const P: u32 = 0x78000001;
+/// `P` in each 32-bit lane of a `u64`.
+const P_LANES: u64 = (P as u64) << 32 | P as u64;
+
+/// Portable packing of two `BabyBear` elements into the 32-bit lanes of a `u64`.
+///
+/// Since `P < 2^31`, the sum of two canonical lanes fits in its lane, so additions and
+/// subtractions are done on both elements at once with a single 64-bit operation, and reduced
+/// afterwards. Products don't fit in a lane, so they're computed and reduced per element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(transparent)] // This needed to make `transmute`s safe.
+pub struct PackedBabyBearScalar2(pub [BabyBear; WIDTH]);
+
+impl PackedBabyBearScalar2 {
+    #[inline]
+    #[must_use]
+    /// Get the packed values as the two lanes of a `u64`.
+    fn to_lanes(self) -> u64 {
+        unsafe {
+            // Safety: `BabyBear` is `repr(transparent)` so it can be transmuted to `u32`, so
+            // `[BabyBear; WIDTH]` can be transmuted to `[u32; 2]` and then to `u64`. Which lane
+            // holds which element depends on endianness, but every operation is lane-wise.
+            transmute(self)
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Reduce each lane of `lanes` from `0..2P` to `0..P`, and make a packed value from them.
+    fn from_unreduced_lanes(lanes: u64) -> Self {
+        let lo = reduce_lane(lanes as u32);
+        let hi = reduce_lane((lanes >> 32) as u32);
+        let lanes = (hi as u64) << 32 | lo as u64;
+        unsafe {
+            // Safety: each lane is now in `0..P`, so represents a valid `BabyBear`. The layout
+            // argument is as in `to_lanes`.
+            transmute(lanes)
+        }
+    }
+
+    /// Copy `value` to all positions in a packed vector. This is the same as
+    /// `From<BabyBear>::from`, but `const`.
+    #[inline]
+    #[must_use]
+    const fn broadcast(value: BabyBear) -> Self {
+        Self([value; WIDTH])
+    }
+}
+
+/// Map a lane in `0..2P` to `0..P`. If `x < P`, `x - P` wraps around to something larger than
+/// `x`, so the minimum is the reduced value in both cases.
+#[inline]
+#[must_use]
+fn reduce_lane(x: u32) -> u32 {
+    x.min(x.wrapping_sub(P))
+}
+
+impl Add for PackedBabyBearScalar2 {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        // Each lane of the sum is below `2P < 2^32`, so there's no carry between lanes.
+        Self::from_unreduced_lanes(self.to_lanes() + rhs.to_lanes())
+    }
+}
+
+impl Mul for PackedBabyBearScalar2 {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self([self.0[0] * rhs.0[0], self.0[1] * rhs.0[1]])
+    }
+}
+
+impl Neg for PackedBabyBearScalar2 {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        // Each lane of `P - x` is in `1..=P`, and no lane borrows since `x < P`.
+        Self::from_unreduced_lanes(P_LANES - self.to_lanes())
+    }
+}
+
+impl Sub for PackedBabyBearScalar2 {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        // Compute `x + (P - y)`, each lane of which is in `1..2P`.
+        Self::from_unreduced_lanes(self.to_lanes() + (P_LANES - rhs.to_lanes()))
+    }
+}
+
+impl From<BabyBear> for PackedBabyBearScalar2 {
+    #[inline]
+    fn from(value: BabyBear) -> Self {
+        Self::broadcast(value)
+    }
+}
+
+impl Default for PackedBabyBearScalar2 {
+    #[inline]
+    fn default() -> Self {
+        BabyBear::default().into()
+    }
+}
+
+impl AddAssign for PackedBabyBearScalar2 {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl MulAssign for PackedBabyBearScalar2 {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl SubAssign for PackedBabyBearScalar2 {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sum for PackedBabyBearScalar2 {
+    #[inline]
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = Self>,
+    {
+        iter.reduce(|lhs, rhs| lhs + rhs).unwrap_or(Self::zero())
+    }
+}
+
+impl Product for PackedBabyBearScalar2 {
+    #[inline]
+    fn product<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = Self>,
+    {
+        iter.reduce(|lhs, rhs| lhs * rhs).unwrap_or(Self::one())
+    }
+}
+
+impl AbstractField for PackedBabyBearScalar2 {
+    type F = BabyBear;
+
+    #[inline]
+    fn zero() -> Self {
+        BabyBear::zero().into()
+    }
+
+    #[inline]
+    fn one() -> Self {
+        BabyBear::one().into()
+    }
+
+    #[inline]
+    fn two() -> Self {
+        BabyBear::two().into()
+    }
+
+    #[inline]
+    fn neg_one() -> Self {
+        BabyBear::neg_one().into()
+    }
+
+    #[inline]
+    fn from_f(f: Self::F) -> Self {
+        f.into()
+    }
+
+    #[inline]
+    fn from_bool(b: bool) -> Self {
+        BabyBear::from_bool(b).into()
+    }
+    #[inline]
+    fn from_canonical_u8(n: u8) -> Self {
+        BabyBear::from_canonical_u8(n).into()
+    }
+    #[inline]
+    fn from_canonical_u16(n: u16) -> Self {
+        BabyBear::from_canonical_u16(n).into()
+    }
+    #[inline]
+    fn from_canonical_u32(n: u32) -> Self {
+        BabyBear::from_canonical_u32(n).into()
+    }
+    #[inline]
+    fn from_canonical_u64(n: u64) -> Self {
+        BabyBear::from_canonical_u64(n).into()
+    }
+    #[inline]
+    fn from_canonical_usize(n: usize) -> Self {
+        BabyBear::from_canonical_usize(n).into()
+    }
+
+    #[inline]
+    fn from_wrapped_u32(n: u32) -> Self {
+        BabyBear::from_wrapped_u32(n).into()
+    }
+    #[inline]
+    fn from_wrapped_u64(n: u64) -> Self {
+        BabyBear::from_wrapped_u64(n).into()
+    }
+
+    #[inline]
+    fn generator() -> Self {
+        BabyBear::generator().into()
+    }
+}
+
+impl Add<BabyBear> for PackedBabyBearScalar2 {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: BabyBear) -> Self {
+        self + Self::from(rhs)
+    }
+}
+
+impl Mul<BabyBear> for PackedBabyBearScalar2 {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: BabyBear) -> Self {
+        self * Self::from(rhs)
+    }
+}
+
+impl Sub<BabyBear> for PackedBabyBearScalar2 {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: BabyBear) -> Self {
+        self - Self::from(rhs)
+    }
+}
+
+impl AddAssign<BabyBear> for PackedBabyBearScalar2 {
+    #[inline]
+    fn add_assign(&mut self, rhs: BabyBear) {
+        *self += Self::from(rhs)
+    }
+}
+
+impl MulAssign<BabyBear> for PackedBabyBearScalar2 {
+    #[inline]
+    fn mul_assign(&mut self, rhs: BabyBear) {
+        *self *= Self::from(rhs)
+    }
+}
+
+impl SubAssign<BabyBear> for PackedBabyBearScalar2 {
+    #[inline]
+    fn sub_assign(&mut self, rhs: BabyBear) {
+        *self -= Self::from(rhs)
+    }
+}
+
+impl Sum<BabyBear> for PackedBabyBearScalar2 {
+    #[inline]
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = BabyBear>,
+    {
+        iter.sum::<BabyBear>().into()
+    }
+}
+
+impl Product<BabyBear> for PackedBabyBearScalar2 {
+    #[inline]
+    fn product<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = BabyBear>,
+    {
+        iter.product::<BabyBear>().into()
+    }
+}
+
+impl Div<BabyBear> for PackedBabyBearScalar2 {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    #[inline]
+    fn div(self, rhs: BabyBear) -> Self {
+        self * rhs.inverse()
+    }
+}
+
+impl Add<PackedBabyBearScalar2> for BabyBear {
+    type Output = PackedBabyBearScalar2;
+    #[inline]
+    fn add(self, rhs: PackedBabyBearScalar2) -> PackedBabyBearScalar2 {
+        PackedBabyBearScalar2::from(self) + rhs
+    }
+}
+
+impl Mul<PackedBabyBearScalar2> for BabyBear {
+    type Output = PackedBabyBearScalar2;
+    #[inline]
+    fn mul(self, rhs: PackedBabyBearScalar2) -> PackedBabyBearScalar2 {
+        PackedBabyBearScalar2::from(self) * rhs
+    }
+}
+
+impl Sub<PackedBabyBearScalar2> for BabyBear {
+    type Output = PackedBabyBearScalar2;
+    #[inline]
+    fn sub(self, rhs: PackedBabyBearScalar2) -> PackedBabyBearScalar2 {
+        PackedBabyBearScalar2::from(self) - rhs
+    }
+}
+
+impl Distribution<PackedBabyBearScalar2> for Standard {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> PackedBabyBearScalar2 {
+        PackedBabyBearScalar2(rng.gen())
+    }
+}
+
+unsafe impl PackedValue for PackedBabyBearScalar2 {
+    type Value = BabyBear;
+    const WIDTH: usize = WIDTH;
+
+    #[inline]
+    fn from_slice(slice: &[BabyBear]) -> &Self {
+        assert_eq!(slice.len(), Self::WIDTH);
+        unsafe {
+            // Safety: `[BabyBear; WIDTH]` can be transmuted to `PackedBabyBearScalar2` since the
+            // latter is `repr(transparent)`. They have the same alignment, so the reference cast
+            // is safe too.
+            &*slice.as_ptr().cast()
+        }
+    }
+    #[inline]
+    fn from_slice_mut(slice: &mut [BabyBear]) -> &mut Self {
+        assert_eq!(slice.len(), Self::WIDTH);
+        unsafe {
+            // Safety: as in `from_slice`.
+            &mut *slice.as_mut_ptr().cast()
+        }
+    }
+
+    #[inline]
+    fn from_fn<F: FnMut(usize) -> BabyBear>(f: F) -> Self {
+        let vals_arr: [_; WIDTH] = core::array::from_fn(f);
+        Self(vals_arr)
+    }
+
+    #[inline]
+    fn as_slice(&self) -> &[BabyBear] {
+        &self.0[..]
+    }
+    #[inline]
+    fn as_slice_mut(&mut self) -> &mut [BabyBear] {
+        &mut self.0[..]
+    }
+}
+
+unsafe impl PackedField for PackedBabyBearScalar2 {
+    type Scalar = BabyBear;
+
+    #[inline]
+    fn interleave(&self, other: Self, block_len: usize) -> (Self, Self) {
+        let ([a0, a1], [b0, b1]) = (self.0, other.0);
+        match block_len {
+            1 => (Self([a0, b0]), Self([a1, b1])),
+            2 => (*self, other),
+            _ => panic!("unsupported block_len"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    type F = BabyBear;
+
+    fn packed_from_canonical(vals: [u32; WIDTH]) -> PackedBabyBearScalar2 {
+        PackedBabyBearScalar2(vals.map(F::from_canonical_u32))
+    }
+
+    /// Values for which a missing or misplaced reduction would show up.
+    fn boundary_vals() -> [F; 6] {
+        [0, 1, 2, P - 2, P - 1, P / 2 + 1].map(F::from_canonical_u32)
+    }
+
+    fn assert_matches_scalar(x: PackedBabyBearScalar2, y: PackedBabyBearScalar2) {
+        for i in 0..WIDTH {
+            assert_eq!((x + y).0[i], x.0[i] + y.0[i]);
+            assert_eq!((x - y).0[i], x.0[i] - y.0[i]);
+            assert_eq!((x * y).0[i], x.0[i] * y.0[i]);
+            assert_eq!((-x).0[i], -x.0[i]);
+        }
+    }
+
+    #[test]
+    fn test_interleave_1() {
+        let vec0 = packed_from_canonical([1, 2]);
+        let vec1 = packed_from_canonical([3, 4]);
+
+        let (res0, res1) = vec0.interleave(vec1, 1);
+        assert_eq!(res0, packed_from_canonical([1, 3]));
+        assert_eq!(res1, packed_from_canonical([2, 4]));
+    }
+
+    #[test]
+    fn test_interleave_2() {
+        let vec0 = packed_from_canonical([1, 2]);
+        let vec1 = packed_from_canonical([3, 4]);
+
+        let (res0, res1) = vec0.interleave(vec1, 2);
+        assert_eq!(res0, vec0);
+        assert_eq!(res1, vec1);
+    }
+
+    #[test]
+    fn test_arithmetic_vs_scalar() {
+        let mut rng = thread_rng();
+        for _ in 0..1000 {
+            assert_matches_scalar(rng.gen(), rng.gen());
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_vs_scalar_boundary_vals() {
+        // Every pair of boundary values, in both lanes, so that e.g. one lane overflowing into
+        // `2P - 2` while the other wraps to zero is covered.
+        let vals = boundary_vals();
+        for &a in &vals {
+            for &b in &vals {
+                for &c in &vals {
+                    for &d in &vals {
+                        assert_matches_scalar(
+                            PackedBabyBearScalar2([a, b]),
+                            PackedBabyBearScalar2([c, d]),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lanes_are_independent() {
+        let max = F::neg_one();
+        let x = PackedBabyBearScalar2([max, F::zero()]);
+        let y = PackedBabyBearScalar2([max, F::zero()]);
+        assert_eq!((x + y).0, [max + max, F::zero()]);
+        assert_eq!((y - x).0, [F::zero(), F::zero()]);
+        assert_eq!((-y).0, [F::one(), F::zero()]);
+    }
+}
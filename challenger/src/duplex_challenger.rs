@@ -126,20 +126,36 @@ where
     F: PrimeField64,
     P: CryptographicPermutation<[F; WIDTH]>,
 {
+    /// Samples field elements until one falls below the largest multiple of `2^bits` not
+    /// exceeding the field order, and returns its low `bits` bits. Masking any field element
+    /// instead would slightly favor small values, as the order isn't a multiple of `2^bits`.
+    ///
+    /// Each attempt is rejected with probability less than `2^bits / p`, so this almost always
+    /// consumes a single field element. It is deterministic given the transcript, so the prover
+    /// and verifier stay in sync.
     fn sample_bits(&mut self, bits: usize) -> usize {
         debug_assert!(bits < (usize::BITS as usize));
         debug_assert!((1 << bits) < F::ORDER_U64);
-        let rand_f: F = self.sample();
-        let rand_usize = rand_f.as_canonical_u64() as usize;
-        rand_usize & ((1 << bits) - 1)
+        let bound = F::ORDER_U64 - F::ORDER_U64 % (1 << bits);
+        loop {
+            let rand_f: F = self.sample();
+            let rand_u64 = rand_f.as_canonical_u64();
+            if rand_u64 < bound {
+                return rand_u64 as usize & ((1 << bits) - 1);
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use p3_baby_bear::BabyBear;
     use p3_field::AbstractField;
     use p3_goldilocks::Goldilocks;
+    use p3_poseidon2::{DiffusionMatrixBabybear, Poseidon2};
     use p3_symmetric::Permutation;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
 
     use super::*;
 
@@ -212,6 +228,45 @@ mod tests {
         assert_eq!(duplex_challenger.sponge_state, should_be_sponge_state);
     }
 
+    #[test]
+    fn test_sample_bits_uniform() {
+        type F = BabyBear;
+        type Perm = Poseidon2<F, DiffusionMatrixBabybear, 16, 7>;
+        const BITS: usize = 10;
+        const SAMPLES_PER_BUCKET: usize = 100;
+
+        let perm = Perm::new_from_rng(
+            8,
+            22,
+            DiffusionMatrixBabybear,
+            &mut ChaCha20Rng::seed_from_u64(0),
+        );
+        let mut challenger = DuplexChallenger::<F, Perm, 16>::new(perm);
+        challenger.observe(F::from_canonical_u32(42));
+        let mut replay = challenger.clone();
+
+        let mut counts = [0usize; 1 << BITS];
+        for _ in 0..(SAMPLES_PER_BUCKET << BITS) {
+            let index = challenger.sample_bits(BITS);
+            assert_eq!(replay.sample_bits(BITS), index);
+            counts[index] += 1;
+        }
+
+        // Pearson's chi-squared statistic has 1023 degrees of freedom, so its mean is 1023 and its
+        // standard deviation is about 45. Allow six standard deviations.
+        let chi_squared: f64 = counts
+            .iter()
+            .map(|&count| {
+                let diff = count as f64 - SAMPLES_PER_BUCKET as f64;
+                diff * diff / SAMPLES_PER_BUCKET as f64
+            })
+            .sum();
+        assert!(
+            chi_squared < 1023.0 + 6.0 * 45.0,
+            "chi-squared statistic {chi_squared}"
+        );
+    }
+
     #[test]
     fn test_duplex_challenger_randomized() {
         let permutation = TestPermutation {};
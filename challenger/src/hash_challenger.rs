@@ -5,6 +5,10 @@ use p3_symmetric::CryptographicHasher;
 
 use crate::{CanObserve, CanSample};
 
+/// A challenger which keeps a running transcript and hashes it whenever output is needed, for
+/// compatibility with systems which use a conventional hash such as Blake3 or SHA-256 for
+/// Fiat-Shamir. It works over the hash's own items (typically bytes); wrap it in a
+/// `SerializingChallenger32` or `SerializingChallenger64` to observe and sample field elements.
 #[derive(Clone)]
 pub struct HashChallenger<T, H, const OUT_LEN: usize>
 where
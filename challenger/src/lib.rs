@@ -44,6 +44,9 @@ pub trait CanSample<T> {
 }
 
 pub trait CanSampleBits<T> {
+    /// Sample a value uniformly from `0..2^bits`. Implementations must not bias the result, such
+    /// as by reducing a field element modulo `2^bits`, since the prover could exploit a bias
+    /// towards some FRI query indices.
     fn sample_bits(&mut self, bits: usize) -> T;
 }
 
@@ -58,6 +61,18 @@ pub trait FieldChallenger<F: Field>:
         let vec = self.sample_vec(EF::D);
         EF::from_base_slice(&vec)
     }
+
+    /// Observe a fixed domain separation tag. Protocols should observe a distinct label before each
+    /// phase, so that phases which absorb structurally different data can't produce colliding
+    /// transcripts.
+    ///
+    /// The label's length is observed first, so that no label is a prefix of another.
+    fn observe_label(&mut self, label: &'static [u8]) {
+        self.observe(F::from_canonical_usize(label.len()));
+        for &byte in label {
+            self.observe(F::from_canonical_u8(byte));
+        }
+    }
 }
 
 impl<'a, C, T> CanObserve<T> for &'a mut C
@@ -121,4 +136,9 @@ where
     fn sample_ext_element<EF: AbstractExtensionField<F>>(&mut self) -> EF {
         (**self).sample_ext_element()
     }
+
+    #[inline(always)]
+    fn observe_label(&mut self, label: &'static [u8]) {
+        (**self).observe_label(label)
+    }
 }
@@ -97,7 +97,7 @@ where
     fn sample(&mut self) -> EF {
         let modulus = F::ORDER_U64 as u32;
         let log_size = log2_ceil_u64(F::ORDER_U64);
-        let pow_of_two_bound = (1 << log_size) - 1;
+        let pow_of_two_bound = u32::MAX >> (32 - log_size);
         // Perform rejection sampling over the uniform range (0..log2_ceil(p))
         let sample_base = |inner: &mut Inner| loop {
             let value = u32::from_le_bytes(inner.sample_array::<4>());
@@ -119,7 +119,7 @@ where
     fn sample(&mut self) -> EF {
         let modulus = F::ORDER_U64 as u32;
         let log_size = log2_ceil_u64(F::ORDER_U64);
-        let pow_of_two_bound = (1 << log_size) - 1;
+        let pow_of_two_bound = u32::MAX >> (32 - log_size);
         // Perform rejection sampling over the uniform range (0..log2_ceil(p))
         let sample_base = |inner: &mut Inner| loop {
             let value = inner.sample();
@@ -257,7 +257,8 @@ where
     fn sample(&mut self) -> EF {
         let modulus = F::ORDER_U64;
         let log_size = log2_ceil_u64(F::ORDER_U64);
-        let pow_of_two_bound = (1 << log_size) - 1;
+        // Written as a shift right, since `1 << 64` would overflow for fields such as Goldilocks.
+        let pow_of_two_bound = u64::MAX >> (64 - log_size);
         // Perform rejection sampling over the uniform range (0..log2_ceil(p))
         let sample_base = |inner: &mut Inner| loop {
             let value = u64::from_le_bytes(inner.sample_array::<8>());
@@ -309,3 +310,104 @@ where
     Inner: CanSample<u8> + CanObserve<u8> + Clone + Send + Sync,
 {
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use p3_baby_bear::BabyBear;
+    use p3_blake3::Blake3;
+    use p3_field::AbstractField;
+    use p3_goldilocks::Goldilocks;
+
+    use super::*;
+
+    type F = Goldilocks;
+    type Blake3Challenger = SerializingChallenger64<F, HashChallenger<u8, Blake3, 32>>;
+
+    fn challenger() -> Blake3Challenger {
+        Blake3Challenger::from_hasher(vec![], Blake3)
+    }
+
+    fn observe_and_sample(challenger: &mut Blake3Challenger, values: &[u64]) -> Vec<F> {
+        for &value in values {
+            challenger.observe(F::from_canonical_u64(value));
+        }
+        challenger.sample_vec(8)
+    }
+
+    #[test]
+    fn test_blake3_deterministic() {
+        let mut a = challenger();
+        let mut b = challenger();
+        assert_eq!(
+            observe_and_sample(&mut a, &[1, 2, 3]),
+            observe_and_sample(&mut b, &[1, 2, 3])
+        );
+        assert_eq!(a.sample_bits(20), b.sample_bits(20));
+        assert_eq!(
+            observe_and_sample(&mut a, &[4]),
+            observe_and_sample(&mut b, &[4])
+        );
+    }
+
+    #[test]
+    fn test_blake3_transcripts_diverge() {
+        let mut a = challenger();
+        let mut b = challenger();
+        assert_ne!(
+            observe_and_sample(&mut a, &[1, 2, 3]),
+            observe_and_sample(&mut b, &[1, 2, 4])
+        );
+
+        // Once transcripts differ, later samples differ too, even if the same values are observed.
+        assert_ne!(
+            observe_and_sample(&mut a, &[5]),
+            observe_and_sample(&mut b, &[5])
+        );
+    }
+
+    /// Returns the given bytes, in order, as samples.
+    #[derive(Clone)]
+    struct ScriptedBytes(Vec<u8>);
+
+    impl CanObserve<u8> for ScriptedBytes {
+        fn observe(&mut self, _value: u8) {}
+    }
+
+    impl CanSample<u8> for ScriptedBytes {
+        fn sample(&mut self) -> u8 {
+            self.0.remove(0)
+        }
+    }
+
+    #[test]
+    fn test_sample_rejection_boundary() {
+        // The order and values above it aren't canonical, so they're rejected, and the largest
+        // canonical value is returned as is.
+        let order = F::ORDER_U64;
+        let bytes = [order, order + 1, u64::MAX, order - 1]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let mut challenger = SerializingChallenger64::<F, _>::new(ScriptedBytes(bytes));
+        let sample: F = challenger.sample();
+        assert_eq!(sample, F::from_canonical_u64(order - 1));
+        assert!(challenger.inner.0.is_empty());
+    }
+
+    #[test]
+    fn test_sample_rejection_boundary_32() {
+        type F32 = BabyBear;
+        let order = F32::ORDER_U32;
+        // BabyBear's order is just below 2^31, so the top bit is masked off before comparing.
+        let bytes = [order, order + 1, order | 1 << 31, (order - 1) | 1 << 31]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let mut challenger = SerializingChallenger32::<F32, u8, _>::new(ScriptedBytes(bytes));
+        let sample: F32 = challenger.sample();
+        assert_eq!(sample, F32::from_canonical_u32(order - 1));
+        assert!(challenger.inner.0.is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_field::extension::{Complex, ComplexExtendable};
+use p3_field::{AbstractField, ExtensionField, Field};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+use crate::CircleDomain;
+
+/// A batch of columns evaluated over a `CircleDomain`, in the domain's natural order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircleEvaluations<F> {
+    pub domain: CircleDomain<F>,
+    pub values: RowMajorMatrix<F>,
+}
+
+impl<F: ComplexExtendable> CircleEvaluations<F> {
+    pub fn new(domain: CircleDomain<F>, values: RowMajorMatrix<F>) -> Self {
+        assert_eq!(values.height(), domain.size());
+        Self { domain, values }
+    }
+
+    /// Evaluate the given coefficients (in the CFFT basis) over `domain`.
+    pub fn evaluate(domain: CircleDomain<F>, coeffs: RowMajorMatrix<F>) -> Self {
+        assert_eq!(coeffs.height(), domain.size());
+        let width = coeffs.width();
+        let values = cfft(coeffs.values, width, domain.half_generator(), true);
+        Self::new(domain, RowMajorMatrix::new(values, width))
+    }
+
+    /// Recover the coefficients of each column in the CFFT basis.
+    ///
+    /// Coefficient `k` multiplies `y^(k_0) * x^(k_1) * π(x)^(k_2) * π^2(x)^(k_3) * ...`, where `k_j`
+    /// is the `j`th bit of `k` and `π(x) = 2x^2 - 1` is the circle squaring map on `x`.
+    pub fn interpolate(self) -> RowMajorMatrix<F> {
+        let width = self.values.width();
+        let coeffs = icfft(
+            self.values.values,
+            width,
+            self.domain.half_generator(),
+            true,
+        );
+        RowMajorMatrix::new(coeffs, width)
+    }
+
+    /// Compute the low-degree extension of each column onto the standard domain which is
+    /// `2^log_blowup` times larger.
+    pub fn extrapolate(self, log_blowup: usize) -> Self {
+        let lde_domain = CircleDomain::standard(self.domain.log_n + log_blowup);
+        let width = self.values.width();
+        let mut coeffs = self.interpolate();
+        coeffs.values.resize(lde_domain.size() * width, F::zero());
+        Self::evaluate(lde_domain, coeffs)
+    }
+
+    /// Evaluate each column at an arbitrary point `(x, y)` on the circle.
+    pub fn evaluate_at_point<EF: ExtensionField<F>>(&self, point: (EF, EF)) -> Vec<EF> {
+        evaluate_coeffs_at_point(&self.clone().interpolate(), point)
+    }
+}
+
+/// Evaluate CFFT-basis coefficients at an arbitrary point `(x, y)` on the circle.
+pub fn evaluate_coeffs_at_point<F: Field, EF: ExtensionField<F>>(
+    coeffs: &RowMajorMatrix<F>,
+    (x, y): (EF, EF),
+) -> Vec<EF> {
+    let width = coeffs.width();
+    let mut folded: Vec<EF> = coeffs.values.iter().map(|&c| EF::from_base(c)).collect();
+    let mut t = y;
+    let mut first = true;
+    while folded.len() > width {
+        folded = folded
+            .chunks_exact(2 * width)
+            .flat_map(|pair| {
+                let (lo, hi) = pair.split_at(width);
+                lo.iter()
+                    .zip(hi)
+                    .map(|(&l, &h)| l + t * h)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        t = if first {
+            x
+        } else {
+            t.square().double() - EF::one()
+        };
+        first = false;
+    }
+    folded
+}
+
+/// The twiddle of a layer: the `y` coordinate for the first layer, and `x` afterwards.
+fn twiddle<F: ComplexExtendable>(point: Complex<F>, y_layer: bool) -> F {
+    if y_layer {
+        point.imag()
+    } else {
+        point.real()
+    }
+}
+
+/// Interpolate `values` (a row-major matrix of the given width) over the points
+/// `[generator^(2i + 1) for i in 0..height]`, projected to `x` unless this is the `y` layer.
+fn icfft<F: ComplexExtendable>(
+    values: Vec<F>,
+    width: usize,
+    generator: Complex<F>,
+    y_layer: bool,
+) -> Vec<F> {
+    let height = values.len() / width;
+    if height <= 1 {
+        return values;
+    }
+    let half = height / 2;
+    let half_inv = F::two().inverse();
+    let step = generator.square();
+
+    let mut lo = Vec::with_capacity(half * width);
+    let mut hi = Vec::with_capacity(half * width);
+    let mut point = generator;
+    for i in 0..half {
+        let inv_twiddle = twiddle(point, y_layer).double().inverse();
+        let a = &values[i * width..(i + 1) * width];
+        let b = &values[(height - 1 - i) * width..(height - i) * width];
+        for (&a, &b) in a.iter().zip(b) {
+            lo.push((a + b) * half_inv);
+            hi.push((a - b) * inv_twiddle);
+        }
+        point *= step;
+    }
+
+    let next_generator = if y_layer { generator } else { step };
+    let lo = icfft(lo, width, next_generator, false);
+    let hi = icfft(hi, width, next_generator, false);
+
+    let mut coeffs = Vec::with_capacity(values.len());
+    for (lo_row, hi_row) in lo.chunks_exact(width).zip(hi.chunks_exact(width)) {
+        coeffs.extend_from_slice(lo_row);
+        coeffs.extend_from_slice(hi_row);
+    }
+    coeffs
+}
+
+/// The inverse of `icfft`.
+fn cfft<F: ComplexExtendable>(
+    coeffs: Vec<F>,
+    width: usize,
+    generator: Complex<F>,
+    y_layer: bool,
+) -> Vec<F> {
+    let height = coeffs.len() / width;
+    if height <= 1 {
+        return coeffs;
+    }
+    let half = height / 2;
+    let step = generator.square();
+
+    let mut lo = Vec::with_capacity(half * width);
+    let mut hi = Vec::with_capacity(half * width);
+    for pair in coeffs.chunks_exact(2 * width) {
+        let (lo_row, hi_row) = pair.split_at(width);
+        lo.extend_from_slice(lo_row);
+        hi.extend_from_slice(hi_row);
+    }
+
+    let next_generator = if y_layer { generator } else { step };
+    let lo = cfft(lo, width, next_generator, false);
+    let hi = cfft(hi, width, next_generator, false);
+
+    let mut values = vec![F::zero(); coeffs.len()];
+    let mut point = generator;
+    for i in 0..half {
+        let tw = twiddle(point, y_layer);
+        for col in 0..width {
+            let l = lo[i * width + col];
+            let h = hi[i * width + col] * tw;
+            values[i * width + col] = l + h;
+            values[(height - 1 - i) * width + col] = l - h;
+        }
+        point *= step;
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_matrix::MatrixRowSlices;
+    use p3_mersenne_31::Mersenne31;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    type F = Mersenne31;
+
+    #[test]
+    fn test_cfft_icfft_round_trip() {
+        let mut rng = thread_rng();
+        for log_n in 0..6 {
+            let domain = CircleDomain::<F>::standard(log_n);
+            let values = RowMajorMatrix::<F>::rand(&mut rng, domain.size(), 3);
+            let evals = CircleEvaluations::new(domain, values.clone());
+            let coeffs = evals.interpolate();
+            assert_eq!(CircleEvaluations::evaluate(domain, coeffs).values, values);
+        }
+    }
+
+    #[test]
+    fn test_evaluate_at_domain_points() {
+        let mut rng = thread_rng();
+        let domain = CircleDomain::<F>::standard(4);
+        let values = RowMajorMatrix::<F>::rand(&mut rng, domain.size(), 2);
+        let coeffs = CircleEvaluations::new(domain, values.clone()).interpolate();
+        for i in 0..domain.size() {
+            let p = domain.nth_point(i);
+            let opened = evaluate_coeffs_at_point::<F, F>(&coeffs, (p.real(), p.imag()));
+            assert_eq!(opened, values.row_slice(i).to_vec());
+        }
+    }
+
+    #[test]
+    fn test_extrapolate_agrees_with_original() {
+        let mut rng = thread_rng();
+        let domain = CircleDomain::<F>::standard(3);
+        let values = RowMajorMatrix::<F>::rand(&mut rng, domain.size(), 2);
+        let evals = CircleEvaluations::new(domain, values);
+        let lde = evals.clone().extrapolate(2);
+        let i = rng.gen_range(0..lde.domain.size());
+        let p = lde.domain.nth_point(i);
+        assert_eq!(
+            evals.evaluate_at_point::<F>((p.real(), p.imag())),
+            lde.values.row_slice(i).to_vec()
+        );
+    }
+}
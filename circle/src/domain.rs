@@ -0,0 +1,83 @@
+use core::marker::PhantomData;
+
+use p3_field::extension::{Complex, ComplexExtendable};
+use p3_field::{AbstractField, ExtensionField, Field};
+
+/// A "standard position" twin-coset of the circle group, of size `2^log_n`.
+///
+/// Writing `h` for a generator of the circle subgroup of order `2^(log_n + 1)`, the `i`th point of
+/// the domain is `h^(2i + 1)`. With this ordering, points `i` and `n - 1 - i` are conjugates, which
+/// is the structure the CFFT folds along.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CircleDomain<F> {
+    pub log_n: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: ComplexExtendable> CircleDomain<F> {
+    pub fn standard(log_n: usize) -> Self {
+        assert!(log_n < F::CIRCLE_TWO_ADICITY);
+        Self {
+            log_n,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        1 << self.log_n
+    }
+
+    /// A generator of the circle subgroup of order `2 * self.size()`.
+    pub(crate) fn half_generator(&self) -> Complex<F> {
+        F::circle_two_adic_generator(self.log_n + 1)
+    }
+
+    /// The `i`th point of the domain, as a complex number `x + iy` of norm one.
+    pub fn nth_point(&self, i: usize) -> Complex<F> {
+        assert!(i < self.size());
+        self.half_generator().exp_u64(2 * i as u64 + 1)
+    }
+}
+
+/// Maps a point `t` on the projective line to the point `((1 - t^2) / (1 + t^2), 2t / (1 + t^2))`
+/// on the circle. This lets univariate out-of-domain challenges be used as circle points.
+///
+/// Returns `None` if `1 + t^2 = 0`, i.e. `t = ±i`. This can't happen for `t` in the base field,
+/// where `-1` isn't a square, but can in extensions such as `Complex<F>`.
+pub fn univariate_to_point<F: Field, EF: ExtensionField<F>>(t: EF) -> Option<(EF, EF)> {
+    let t2 = t.square();
+    let denom_inv = (EF::one() + t2).try_inverse()?;
+    Some(((EF::one() - t2) * denom_inv, t.double() * denom_inv))
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_mersenne_31::Mersenne31;
+
+    use super::*;
+
+    type F = Mersenne31;
+
+    #[test]
+    fn test_points_lie_on_circle_and_pair_with_conjugates() {
+        let domain = CircleDomain::<F>::standard(4);
+        for i in 0..domain.size() {
+            let p = domain.nth_point(i);
+            assert_eq!(p.norm(), F::one());
+            assert_eq!(domain.nth_point(domain.size() - 1 - i), p.conjugate());
+        }
+    }
+
+    #[test]
+    fn test_univariate_to_point() {
+        let (x, y) = univariate_to_point::<F, F>(F::from_canonical_u32(12345)).unwrap();
+        assert_eq!(x.square() + y.square(), F::one());
+    }
+
+    #[test]
+    fn test_univariate_to_point_undefined_at_i() {
+        let i = Complex::<F>::new_imag(F::one());
+        assert_eq!(univariate_to_point::<F, _>(i), None);
+        assert_eq!(univariate_to_point::<F, _>(-i), None);
+    }
+}
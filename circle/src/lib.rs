@@ -0,0 +1,16 @@
+//! A scaffold for circle STARKs over fields with `p = 3 (mod 4)`, such as Mersenne31, based on
+//! HLP24 <https://eprint.iacr.org/2024/278>.
+//!
+//! There's no circle FRI yet, so the only PCS is the test-only `NaiveCirclePcs`.
+
+#![no_std]
+
+extern crate alloc;
+
+mod cfft;
+mod domain;
+mod naive_pcs;
+
+pub use cfft::*;
+pub use domain::*;
+pub use naive_pcs::*;
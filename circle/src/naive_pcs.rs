@@ -0,0 +1,334 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use p3_challenger::FieldChallenger;
+use p3_commit::{DirectMmcs, OpenedValues, Pcs, UnivariatePcs};
+use p3_field::extension::ComplexExtendable;
+use p3_field::ExtensionField;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Dimensions, Matrix, MatrixRows};
+use p3_util::log2_strict_usize;
+use serde::{Deserialize, Serialize};
+
+use crate::{evaluate_coeffs_at_point, univariate_to_point, CircleDomain, CircleEvaluations};
+
+/// A PCS which commits to the LDEs of polynomials over standard circle domains, for testing the
+/// circle domain, CFFT and commitment layout before circle FRI exists.
+///
+/// It must not be used in production. An opening reveals every committed evaluation, which the
+/// verifier checks against the commitment in full, so proofs are as large as the committed data
+/// and hide nothing about it. Circle FRI will replace the opening argument, as a separate PCS with
+/// the same commitments.
+///
+/// Opening points are given as univariate `t`, and are mapped onto the circle with
+/// `univariate_to_point`.
+pub struct NaiveCirclePcs<Val, InputMmcs> {
+    log_blowup: usize,
+    mmcs: InputMmcs,
+    _phantom: PhantomData<Val>,
+}
+
+impl<Val, InputMmcs> NaiveCirclePcs<Val, InputMmcs> {
+    pub fn new(log_blowup: usize, mmcs: InputMmcs) -> Self {
+        Self {
+            log_blowup,
+            mmcs,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Val: ComplexExtendable, InputMmcs: DirectMmcs<Val>> NaiveCirclePcs<Val, InputMmcs> {
+    fn commit_evaluations(
+        &self,
+        evaluations: &[RowMajorMatrix<Val>],
+    ) -> (InputMmcs::Commitment, InputMmcs::ProverData) {
+        let ldes = evaluations
+            .iter()
+            .map(|evals| {
+                let domain = CircleDomain::standard(log2_strict_usize(evals.height()));
+                CircleEvaluations::new(domain, evals.clone())
+                    .extrapolate(self.log_blowup)
+                    .values
+            })
+            .collect();
+        self.mmcs.commit(ldes)
+    }
+}
+
+pub struct CircleProverData<Val, MmcsData> {
+    /// The evaluations of each committed matrix over its (non-extended) standard domain.
+    evaluations: Vec<RowMajorMatrix<Val>>,
+    /// The MMCS data for the committed LDEs. Unused until the opening argument is succinct.
+    _mmcs_data: MmcsData,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct NaiveCirclePcsProof<Val: ComplexExtendable> {
+    /// For each committed batch, the evaluations of each matrix over its standard domain.
+    pub(crate) evaluations: Vec<Vec<RowMajorMatrix<Val>>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum NaiveCirclePcsError {
+    InvalidProofShape,
+    CommitmentMismatch,
+    OpenedValueMismatch,
+    /// An opening point `t` with `1 + t^2 = 0`, which doesn't correspond to a point of the circle.
+    InvalidOpeningPoint,
+}
+
+impl<Val, InputMmcs, In> Pcs<Val, In> for NaiveCirclePcs<Val, InputMmcs>
+where
+    Val: ComplexExtendable,
+    InputMmcs: DirectMmcs<Val>,
+    In: MatrixRows<Val>,
+{
+    type Commitment = InputMmcs::Commitment;
+    type ProverData = CircleProverData<Val, InputMmcs::ProverData>;
+    type Proof = NaiveCirclePcsProof<Val>;
+    type Error = NaiveCirclePcsError;
+
+    fn commit_batches(&self, polynomials: Vec<In>) -> (Self::Commitment, Self::ProverData) {
+        let evaluations: Vec<_> = polynomials
+            .into_iter()
+            .map(|poly| poly.to_row_major_matrix())
+            .collect();
+        let (commit, mmcs_data) = self.commit_evaluations(&evaluations);
+        let data = CircleProverData {
+            evaluations,
+            _mmcs_data: mmcs_data,
+        };
+        (commit, data)
+    }
+}
+
+impl<Val, EF, InputMmcs, In, Challenger> UnivariatePcs<Val, EF, In, Challenger>
+    for NaiveCirclePcs<Val, InputMmcs>
+where
+    Val: ComplexExtendable,
+    EF: ExtensionField<Val>,
+    InputMmcs: DirectMmcs<Val>,
+    InputMmcs::Commitment: PartialEq,
+    In: MatrixRows<Val>,
+    Challenger: FieldChallenger<Val>,
+{
+    fn open_multi_batches(
+        &self,
+        prover_data_and_points: &[(&Self::ProverData, &[Vec<EF>])],
+        _challenger: &mut Challenger,
+    ) -> (OpenedValues<EF>, Self::Proof) {
+        let opened_values = prover_data_and_points
+            .iter()
+            .map(|(data, points_per_matrix)| {
+                data.evaluations
+                    .iter()
+                    .zip(points_per_matrix.iter())
+                    .map(|(evals, points)| {
+                        open_matrix(evals, points)
+                            .expect("opening points must not be roots of 1 + t^2")
+                    })
+                    .collect()
+            })
+            .collect();
+        let evaluations = prover_data_and_points
+            .iter()
+            .map(|(data, _)| data.evaluations.clone())
+            .collect();
+        (opened_values, NaiveCirclePcsProof { evaluations })
+    }
+
+    fn verify_multi_batches(
+        &self,
+        commits_and_points: &[(Self::Commitment, &[Vec<EF>])],
+        dims: &[Vec<Dimensions>],
+        values: OpenedValues<EF>,
+        proof: &Self::Proof,
+        _challenger: &mut Challenger,
+    ) -> Result<(), Self::Error> {
+        if proof.evaluations.len() != commits_and_points.len()
+            || dims.len() != commits_and_points.len()
+            || values.len() != commits_and_points.len()
+        {
+            return Err(NaiveCirclePcsError::InvalidProofShape);
+        }
+
+        for (((commit, points_per_matrix), batch_dims), (batch_values, batch_evals)) in
+            commits_and_points
+                .iter()
+                .zip(dims)
+                .zip(values.iter().zip(&proof.evaluations))
+        {
+            if batch_evals.len() != batch_dims.len()
+                || points_per_matrix.len() != batch_dims.len()
+                || batch_values.len() != batch_dims.len()
+            {
+                return Err(NaiveCirclePcsError::InvalidProofShape);
+            }
+            for (evals, dim) in batch_evals.iter().zip(batch_dims) {
+                if evals.width() != dim.width
+                    || evals.height() != dim.height
+                    || evals.values.len() != dim.width * dim.height
+                    || !dim.height.is_power_of_two()
+                {
+                    return Err(NaiveCirclePcsError::InvalidProofShape);
+                }
+            }
+
+            let (recomputed_commit, _) = self.commit_evaluations(batch_evals);
+            if recomputed_commit != *commit {
+                return Err(NaiveCirclePcsError::CommitmentMismatch);
+            }
+
+            for ((evals, points), claimed) in batch_evals
+                .iter()
+                .zip(points_per_matrix.iter())
+                .zip(batch_values)
+            {
+                let opened =
+                    open_matrix(evals, points).ok_or(NaiveCirclePcsError::InvalidOpeningPoint)?;
+                if opened != *claimed {
+                    return Err(NaiveCirclePcsError::OpenedValueMismatch);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Evaluate every column of `evals` at each of the given univariate points, or return `None` if
+/// one of them has no corresponding circle point.
+fn open_matrix<Val: ComplexExtendable, EF: ExtensionField<Val>>(
+    evals: &RowMajorMatrix<Val>,
+    points: &[EF],
+) -> Option<Vec<Vec<EF>>> {
+    let domain = CircleDomain::standard(log2_strict_usize(evals.height()));
+    let coeffs = CircleEvaluations::new(domain, evals.clone()).interpolate();
+    points
+        .iter()
+        .map(|&t| {
+            let point = univariate_to_point::<Val, EF>(t)?;
+            Some(evaluate_coeffs_at_point(&coeffs, point))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use p3_challenger::{HashChallenger, SerializingChallenger32};
+    use p3_field::extension::Complex;
+    use p3_field::AbstractField;
+    use p3_keccak::Keccak256Hash;
+    use p3_merkle_tree::FieldMerkleTreeMmcs;
+    use p3_mersenne_31::Mersenne31;
+    use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    type Val = Mersenne31;
+    type Challenge = Complex<Mersenne31>;
+
+    type ByteHash = Keccak256Hash;
+    type FieldHash = SerializingHasher32<ByteHash>;
+    type MyCompress = CompressionFunctionFromHasher<u8, ByteHash, 2, 32>;
+    type ValMmcs = FieldMerkleTreeMmcs<Val, u8, FieldHash, MyCompress, 32>;
+    type Challenger = SerializingChallenger32<Val, u8, HashChallenger<u8, ByteHash, 32>>;
+    type MyPcs = NaiveCirclePcs<Val, ValMmcs>;
+
+    fn pcs() -> MyPcs {
+        let mmcs = ValMmcs::new(FieldHash::new(ByteHash {}), MyCompress::new(ByteHash {}));
+        MyPcs::new(1, mmcs)
+    }
+
+    fn challenger() -> Challenger {
+        Challenger::from_hasher(vec![], ByteHash {})
+    }
+
+    type Open = (
+        <MyPcs as Pcs<Val, RowMajorMatrix<Val>>>::Commitment,
+        Vec<Vec<Challenge>>,
+        Vec<Dimensions>,
+        OpenedValues<Challenge>,
+        NaiveCirclePcsProof<Val>,
+    );
+
+    fn commit_and_open(pcs: &MyPcs) -> Open {
+        let mut rng = thread_rng();
+        let polys = vec![
+            RowMajorMatrix::<Val>::rand(&mut rng, 8, 3),
+            RowMajorMatrix::<Val>::rand(&mut rng, 16, 2),
+        ];
+        let dims = polys.iter().map(|m| m.dimensions()).collect();
+        let points = vec![vec![rng.gen::<Challenge>()], vec![rng.gen::<Challenge>()]];
+
+        let (commit, data) = <MyPcs as Pcs<Val, RowMajorMatrix<Val>>>::commit_batches(pcs, polys);
+        let (values, proof) = <MyPcs as UnivariatePcs<
+            Val,
+            Challenge,
+            RowMajorMatrix<Val>,
+            Challenger,
+        >>::open_multi_batches(
+            pcs, &[(&data, points.as_slice())], &mut challenger()
+        );
+        (commit, points, dims, values, proof)
+    }
+
+    fn verify(
+        pcs: &MyPcs,
+        (commit, points, dims, values, proof): Open,
+    ) -> Result<(), NaiveCirclePcsError> {
+        <MyPcs as UnivariatePcs<Val, Challenge, RowMajorMatrix<Val>, Challenger>>::verify_multi_batches(
+            pcs,
+            &[(commit, points.as_slice())],
+            &[dims],
+            values,
+            &proof,
+            &mut challenger(),
+        )
+    }
+
+    #[test]
+    fn test_open_verify_round_trip() {
+        let pcs = pcs();
+        let opening = commit_and_open(&pcs);
+        assert_eq!(verify(&pcs, opening), Ok(()));
+    }
+
+    #[test]
+    fn test_tampered_opened_value_rejected() {
+        let pcs = pcs();
+        let mut opening = commit_and_open(&pcs);
+        opening.3[0][1][0][0] += Challenge::one();
+        assert_eq!(
+            verify(&pcs, opening),
+            Err(NaiveCirclePcsError::OpenedValueMismatch)
+        );
+    }
+
+    #[test]
+    fn test_invalid_opening_point_rejected() {
+        let pcs = pcs();
+        let mut opening = commit_and_open(&pcs);
+        opening.1[0][0] = Challenge::new_imag(Val::one());
+        assert_eq!(
+            verify(&pcs, opening),
+            Err(NaiveCirclePcsError::InvalidOpeningPoint)
+        );
+    }
+
+    #[test]
+    fn test_tampered_proof_rejected() {
+        let pcs = pcs();
+        let mut opening = commit_and_open(&pcs);
+        opening.4.evaluations[0][0].values[0] += Val::one();
+        assert_eq!(
+            verify(&pcs, opening),
+            Err(NaiveCirclePcsError::CommitmentMismatch)
+        );
+    }
+}
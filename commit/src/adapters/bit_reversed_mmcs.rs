@@ -0,0 +1,97 @@
+use alloc::vec::Vec;
+
+use p3_matrix::bitrev::BitReversedMatrixView;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::util::reverse_matrix_index_bits;
+use p3_matrix::Dimensions;
+use p3_util::{log2_strict_usize, reverse_bits_len};
+
+use crate::{DirectMmcs, Mmcs};
+
+/// An MMCS which commits to matrices with their rows in bit-reversed order, as FRI expects, while
+/// accepting inputs and row indices in natural order. The permutation is applied internally, so
+/// committing here is interchangeable with bit-reversing each input and committing with `inner`.
+///
+/// Note that row indices for shorter matrices wrap around, rather than dropping low bits: opening
+/// row `i` yields row `i mod height` of each matrix.
+#[derive(Clone)]
+pub struct BitReversedMmcs<InnerMmcs> {
+    inner: InnerMmcs,
+}
+
+impl<InnerMmcs> BitReversedMmcs<InnerMmcs> {
+    pub fn new(inner: InnerMmcs) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T, InnerMmcs> Mmcs<T> for BitReversedMmcs<InnerMmcs>
+where
+    InnerMmcs: Mmcs<T>,
+{
+    type ProverData = InnerMmcs::ProverData;
+    type Commitment = InnerMmcs::Commitment;
+    type Proof = InnerMmcs::Proof;
+    type Error = InnerMmcs::Error;
+    type Mat<'a> = BitReversedMatrixView<InnerMmcs::Mat<'a>> where Self: 'a;
+
+    fn open_batch(
+        &self,
+        index: usize,
+        prover_data: &Self::ProverData,
+    ) -> (Vec<Vec<T>>, Self::Proof) {
+        let max_height = self
+            .inner
+            .get_matrix_heights(prover_data)
+            .into_iter()
+            .max()
+            .unwrap_or(1);
+        let log_max_height = log2_strict_usize(max_height);
+        self.inner
+            .open_batch(reverse_bits_len(index, log_max_height), prover_data)
+    }
+
+    fn get_matrices<'a>(&'a self, prover_data: &'a Self::ProverData) -> Vec<Self::Mat<'a>> {
+        self.inner
+            .get_matrices(prover_data)
+            .into_iter()
+            .map(BitReversedMatrixView::new)
+            .collect()
+    }
+
+    fn verify_batch(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        index: usize,
+        opened_values: &[Vec<T>],
+        proof: &Self::Proof,
+    ) -> Result<(), Self::Error> {
+        let max_height = dimensions.iter().map(|dims| dims.height).max().unwrap_or(1);
+        let log_max_height = log2_strict_usize(max_height);
+        self.inner.verify_batch(
+            commit,
+            dimensions,
+            reverse_bits_len(index, log_max_height),
+            opened_values,
+            proof,
+        )
+    }
+}
+
+impl<T, InnerMmcs> DirectMmcs<T> for BitReversedMmcs<InnerMmcs>
+where
+    InnerMmcs: DirectMmcs<T>,
+{
+    fn commit(&self, inputs: Vec<RowMajorMatrix<T>>) -> (Self::Commitment, Self::ProverData) {
+        self.inner.commit(
+            inputs
+                .into_iter()
+                .map(|mut mat| {
+                    reverse_matrix_index_bits(&mut mat);
+                    mat
+                })
+                .collect(),
+        )
+    }
+}
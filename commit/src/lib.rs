@@ -7,7 +7,9 @@ extern crate alloc;
 mod adapters;
 mod mmcs;
 mod pcs;
+mod spans;
 
 pub use adapters::*;
 pub use mmcs::*;
 pub use pcs::*;
+pub use spans::*;
@@ -31,7 +31,8 @@ pub trait Mmcs<T>: Clone {
     /// Opens a batch of rows from committed matrices
     /// returns `(openings, proof)`
     /// where `openings` is a vector whose `i`th element is the `j`th row of the ith matrix `M[i]`,
-    /// and `j = index >> (log2_ceil(max_height) - log2_ceil(M[i].height))`.
+    /// and `j = index >> (log2_ceil(max_height) - log2_ceil(M[i].height))`, as computed by
+    /// `query_index_for_height`.
     fn open_batch(
         &self,
         index: usize,
@@ -82,4 +83,43 @@ pub trait DirectMmcs<T>: Mmcs<T> {
     fn commit_vec(&self, input: Vec<T>) -> (Self::Commitment, Self::ProverData) {
         self.commit_matrix(RowMajorMatrix::new_col(input))
     }
+
+    /// Commit to a single matrix given as its columns, each of which must have the same length.
+    ///
+    /// By default this transposes the columns and calls `commit_matrix`. Implementations which can
+    /// build their leaves straight from the columns should override it.
+    fn commit_col_major(&self, cols: Vec<Vec<T>>) -> (Self::Commitment, Self::ProverData)
+    where
+        T: Clone,
+    {
+        let width = cols.len();
+        assert!(width > 0, "cannot commit to a matrix with no columns");
+        let height = cols[0].len();
+        assert!(
+            cols.iter().all(|col| col.len() == height),
+            "all columns must have the same length"
+        );
+        let values = (0..height)
+            .flat_map(|r| cols.iter().map(move |col| col[r].clone()))
+            .collect();
+        self.commit_matrix(RowMajorMatrix::new(values, width))
+    }
+}
+
+/// The row of a matrix of height `2^matrix_log_height` which is opened along with row
+/// `global_index` of the tallest matrices in a batch, of height `2^max_log_height`.
+///
+/// The low bits of the index are dropped, so each row of a shorter matrix is opened for a run of
+/// consecutive rows of the tallest ones. Folding in FRI halves the height of a codeword in the same
+/// way, so one query index selects corresponding rows in every matrix and every FRI layer.
+pub fn query_index_for_height(
+    global_index: usize,
+    matrix_log_height: usize,
+    max_log_height: usize,
+) -> usize {
+    debug_assert!(
+        matrix_log_height <= max_log_height,
+        "matrix of log height {matrix_log_height} is taller than the maximum {max_log_height}"
+    );
+    global_index >> (max_log_height - matrix_log_height)
 }
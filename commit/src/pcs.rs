@@ -3,10 +3,12 @@
 use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::Debug;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 use p3_challenger::FieldChallenger;
 use p3_field::{ExtensionField, Field};
 use p3_matrix::{Dimensions, MatrixGet, MatrixRows};
+use p3_util::allocator::TraceAllocator;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
@@ -47,12 +49,32 @@ where
     In: MatrixRows<Val>,
     Challenger: FieldChallenger<Val>,
 {
+    /// Open each committed batch, with each matrix in a batch opened at its own set of points, so
+    /// that `prover_data_and_points[r].1[m]` lists the points for matrix `m` of round `r`. The
+    /// opened values are indexed by round, then matrix, then point, then column.
     fn open_multi_batches(
         &self,
         prover_data_and_points: &[(&Self::ProverData, &[Vec<EF>])],
         challenger: &mut Challenger,
     ) -> (OpenedValues<EF>, Self::Proof);
 
+    /// Like `open_multi_batches`, but gives up and returns `None` if `cancel` is set before the
+    /// opening is finished. By default `cancel` is only checked before starting, but
+    /// implementations can check it as they go.
+    fn open_multi_batches_cancellable(
+        &self,
+        prover_data_and_points: &[(&Self::ProverData, &[Vec<EF>])],
+        challenger: &mut Challenger,
+        cancel: &AtomicBool,
+    ) -> Option<(OpenedValues<EF>, Self::Proof)> {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        Some(self.open_multi_batches(prover_data_and_points, challenger))
+    }
+
+    /// Verify openings produced by `open_multi_batches`, where the points and `dims` are structured
+    /// by round and then matrix, as in `open_multi_batches`.
     fn verify_multi_batches(
         &self,
         commits_and_points: &[(Self::Commitment, &[Vec<EF>])],
@@ -77,6 +99,12 @@ where
     where
         Self: 'a;
 
+    /// The allocator for the LDEs, which provers should also use for their other trace-sized
+    /// buffers.
+    type Allocator: TraceAllocator;
+
+    fn allocator(&self) -> &Self::Allocator;
+
     fn coset_shift(&self) -> Val;
 
     fn log_blowup(&self) -> usize;
@@ -99,6 +127,30 @@ where
     ) -> (Self::Commitment, Self::ProverData) {
         self.commit_shifted_batches(vec![polynomials], &[coset_shift])
     }
+
+    /// Like `commit_shifted_batches`, but gives up and returns `None` if `cancel` is set before
+    /// the commitment is finished. By default `cancel` is only checked before starting, but
+    /// implementations can check it as they go, e.g. between LDEs.
+    fn commit_shifted_batches_cancellable(
+        &self,
+        polynomials: Vec<In>,
+        coset_shift: &[Val],
+        cancel: &AtomicBool,
+    ) -> Option<(Self::Commitment, Self::ProverData)> {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        Some(self.commit_shifted_batches(polynomials, coset_shift))
+    }
+
+    fn commit_shifted_batch_cancellable(
+        &self,
+        polynomials: In,
+        coset_shift: Val,
+        cancel: &AtomicBool,
+    ) -> Option<(Self::Commitment, Self::ProverData)> {
+        self.commit_shifted_batches_cancellable(vec![polynomials], &[coset_shift], cancel)
+    }
 }
 
 pub trait MultivariatePcs<Val, EF, In, Challenger>: Pcs<Val, In>
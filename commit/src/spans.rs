@@ -0,0 +1,11 @@
+//! The names of the spans which a PCS enters for each phase of proving, so that subscribers can
+//! tell the phases apart, e.g. to time them, whichever PCS is in use.
+
+/// Computing the LDEs of the matrices being committed to.
+pub const LDE_SPAN: &str = "compute all coset LDEs";
+
+/// Committing to each layer of a low-degree test, e.g. FRI's folding.
+pub const COMMIT_PHASE_SPAN: &str = "commit phase";
+
+/// Answering the low-degree test's queries.
+pub const QUERY_PHASE_SPAN: &str = "query phase";
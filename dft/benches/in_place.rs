@@ -0,0 +1,163 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::any::type_name;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::measurement::{Measurement, ValueFormatter};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use p3_baby_bear::BabyBear;
+use p3_dft::{Radix2Bowers, Radix2Dit, TwoAdicSubgroupDft};
+use p3_field::TwoAdicField;
+use p3_matrix::dense::RowMajorMatrix;
+use rand::distributions::{Distribution, Standard};
+use rand::thread_rng;
+
+/// Counts allocations, so each benchmark can measure how many bytes an iteration allocates.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// A criterion measurement of the bytes allocated, rather than the time taken. Criterion's warm up
+/// runs first, so memoized twiddles aren't counted.
+struct BytesAllocated;
+
+impl Measurement for BytesAllocated {
+    type Intermediate = usize;
+    type Value = usize;
+
+    fn start(&self) -> usize {
+        ALLOCATED.load(Ordering::Relaxed)
+    }
+
+    fn end(&self, start: usize) -> usize {
+        ALLOCATED.load(Ordering::Relaxed) - start
+    }
+
+    fn add(&self, v1: &usize, v2: &usize) -> usize {
+        v1 + v2
+    }
+
+    fn zero(&self) -> usize {
+        0
+    }
+
+    fn to_f64(&self, value: &usize) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &BytesFormatter
+    }
+}
+
+struct BytesFormatter;
+
+impl ValueFormatter for BytesFormatter {
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        let (factor, unit) = if typical_value < 1024.0 {
+            (1.0, "B")
+        } else if typical_value < 1024.0 * 1024.0 {
+            (1024.0, "KiB")
+        } else {
+            (1024.0 * 1024.0, "MiB")
+        };
+        values.iter_mut().for_each(|v| *v /= factor);
+        unit
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let (n, unit) = match *throughput {
+            Throughput::Bytes(n) | Throughput::BytesDecimal(n) => (n, "B/byte"),
+            Throughput::Elements(n) => (n, "B/element"),
+        };
+        values.iter_mut().for_each(|v| *v /= n as f64);
+        unit
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "B"
+    }
+}
+
+fn bench_in_place_time(c: &mut Criterion) {
+    bench_in_place(c, "dft_idft_round_trip");
+}
+
+fn bench_in_place_bytes_allocated(c: &mut Criterion<BytesAllocated>) {
+    bench_in_place(c, "dft_idft_round_trip_bytes_allocated");
+}
+
+fn bench_in_place<M: Measurement>(c: &mut Criterion<M>, name: &str) {
+    const BATCH_SIZE: usize = 100;
+
+    round_trip::<BabyBear, Radix2Dit<_>, M, BATCH_SIZE>(c, name);
+    round_trip::<BabyBear, Radix2Bowers, M, BATCH_SIZE>(c, name);
+}
+
+/// Repeatedly transforms a matrix and transforms it back, keeping the input, as a pipeline which
+/// processes evaluations between the two would. With the allocating methods, the input has to be
+/// cloned each time, while the in-place ones can reuse a single buffer.
+fn round_trip<F, Dft, M, const BATCH_SIZE: usize>(c: &mut Criterion<M>, name: &str)
+where
+    F: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<F>,
+    M: Measurement,
+    Standard: Distribution<F>,
+{
+    let mut group = c.benchmark_group(&format!(
+        "{name}::<{}, {}, {}>",
+        type_name::<F>(),
+        type_name::<Dft>(),
+        BATCH_SIZE
+    ));
+    group.sample_size(10);
+
+    let mut rng = thread_rng();
+    for n_log in [14, 16] {
+        let n = 1 << n_log;
+        let dft = Dft::default();
+        let mut mat = RowMajorMatrix::<F>::rand(&mut rng, n, BATCH_SIZE);
+
+        let allocating = |mat: &RowMajorMatrix<F>| {
+            let evals = dft.dft_batch(mat.clone()).to_row_major_matrix();
+            dft.idft_batch(evals)
+        };
+        let in_place = |mat: &mut RowMajorMatrix<F>| {
+            dft.dft_batch_in_place(mat);
+            dft.idft_batch_in_place(mat);
+        };
+
+        group.bench_with_input(BenchmarkId::new("allocating", n), &mat, |b, mat| {
+            b.iter(|| allocating(mat));
+        });
+        group.bench_function(BenchmarkId::new("in_place", n), |b| {
+            b.iter(|| in_place(&mut mat));
+        });
+    }
+}
+
+criterion_group!(time, bench_in_place_time);
+criterion_group! {
+    name = bytes_allocated;
+    config = Criterion::default().with_measurement(BytesAllocated);
+    targets = bench_in_place_bytes_allocated
+}
+criterion_main!(time, bytes_allocated);
@@ -5,6 +5,7 @@ use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
 use p3_matrix::util::reverse_matrix_index_bits;
 use p3_matrix::Matrix;
 use p3_maybe_rayon::prelude::*;
+use p3_util::allocator::{GlobalTraceAllocator, TraceAllocator};
 use p3_util::{log2_strict_usize, reverse_bits, reverse_slice_index_bits};
 
 use crate::butterflies::{
@@ -22,32 +23,45 @@ impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for Radix2Bowers {
     type Evaluations = RowMajorMatrix<F>;
 
     fn dft_batch(&self, mut mat: RowMajorMatrix<F>) -> RowMajorMatrix<F> {
-        reverse_matrix_index_bits(&mut mat);
-        bowers_g(&mut mat.as_view_mut());
+        self.dft_batch_in_place(&mut mat);
         mat
     }
 
-    /// Compute the inverse DFT of each column in `mat`.
-    fn idft_batch(&self, mut mat: RowMajorMatrix<F>) -> RowMajorMatrix<F> {
+    fn dft_batch_in_place(&self, mat: &mut RowMajorMatrix<F>) {
+        reverse_matrix_index_bits(mat);
+        bowers_g(&mut mat.as_view_mut());
+    }
+
+    /// Compute the inverse DFT of each column in `mat`, overwriting it with the coefficients.
+    fn idft_batch_in_place(&self, mat: &mut RowMajorMatrix<F>) {
         bowers_g_t(&mut mat.as_view_mut());
-        divide_by_height(&mut mat);
-        reverse_matrix_index_bits(&mut mat);
-        mat
+        divide_by_height(mat);
+        reverse_matrix_index_bits(mat);
     }
 
     fn lde_batch(&self, mut mat: RowMajorMatrix<F>, added_bits: usize) -> RowMajorMatrix<F> {
         bowers_g_t(&mut mat.as_view_mut());
         divide_by_height(&mut mat);
-        bit_reversed_zero_pad(&mut mat, added_bits);
+        bit_reversed_zero_pad(&mut mat, added_bits, &GlobalTraceAllocator);
         bowers_g(&mut mat.as_view_mut());
         mat
     }
 
     fn coset_lde_batch(
+        &self,
+        mat: RowMajorMatrix<F>,
+        added_bits: usize,
+        shift: F,
+    ) -> RowMajorMatrix<F> {
+        self.coset_lde_batch_with_allocator(mat, added_bits, shift, &GlobalTraceAllocator)
+    }
+
+    fn coset_lde_batch_with_allocator<A: TraceAllocator>(
         &self,
         mut mat: RowMajorMatrix<F>,
         added_bits: usize,
         shift: F,
+        allocator: &A,
     ) -> RowMajorMatrix<F> {
         let h = mat.height();
         let h_inv = F::from_canonical_usize(h).inverse();
@@ -67,7 +81,7 @@ impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for Radix2Bowers {
             mat.scale_row(reverse_bits(row, h), weight);
         }
 
-        bit_reversed_zero_pad(&mut mat, added_bits);
+        bit_reversed_zero_pad(&mut mat, added_bits, allocator);
 
         bowers_g(&mut mat.as_view_mut());
 
@@ -199,4 +213,9 @@ mod tests {
     fn dft_idft_consistency() {
         test_dft_idft_consistency::<BabyBear, Radix2Bowers>();
     }
+
+    #[test]
+    fn in_place_matches_allocating() {
+        test_in_place_matches_allocating::<BabyBear, Radix2Bowers>();
+    }
 }
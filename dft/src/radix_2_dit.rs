@@ -23,6 +23,11 @@ impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for Radix2Dit<F> {
     type Evaluations = RowMajorMatrix<F>;
 
     fn dft_batch(&self, mut mat: RowMajorMatrix<F>) -> RowMajorMatrix<F> {
+        self.dft_batch_in_place(&mut mat);
+        mat
+    }
+
+    fn dft_batch_in_place(&self, mat: &mut RowMajorMatrix<F>) {
         let h = mat.height();
         let log_h = log2_strict_usize(h);
 
@@ -34,11 +39,10 @@ impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for Radix2Dit<F> {
         });
 
         // DIT butterfly
-        reverse_matrix_index_bits(&mut mat);
+        reverse_matrix_index_bits(mat);
         for layer in 0..log_h {
             dit_layer(&mut mat.as_view_mut(), layer, twiddles);
         }
-        mat
     }
 }
 
@@ -113,4 +117,9 @@ mod tests {
     fn dft_idft_consistency() {
         test_dft_idft_consistency::<BabyBear, Radix2Dit<_>>();
     }
+
+    #[test]
+    fn in_place_matches_allocating() {
+        test_in_place_matches_allocating::<BabyBear, Radix2Dit<_>>();
+    }
 }
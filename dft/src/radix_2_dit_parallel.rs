@@ -6,6 +6,7 @@ use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
 use p3_matrix::util::reverse_matrix_index_bits;
 use p3_matrix::Matrix;
 use p3_maybe_rayon::prelude::*;
+use p3_util::allocator::{GlobalTraceAllocator, TraceAllocator};
 use p3_util::{log2_strict_usize, reverse_bits, reverse_slice_index_bits};
 
 use crate::butterflies::dit_butterfly;
@@ -47,10 +48,20 @@ impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for Radix2DitParallel {
     }
 
     fn coset_lde_batch(
+        &self,
+        mat: RowMajorMatrix<F>,
+        added_bits: usize,
+        shift: F,
+    ) -> Self::Evaluations {
+        self.coset_lde_batch_with_allocator(mat, added_bits, shift, &GlobalTraceAllocator)
+    }
+
+    fn coset_lde_batch_with_allocator<A: TraceAllocator>(
         &self,
         mut mat: RowMajorMatrix<F>,
         added_bits: usize,
         shift: F,
+        allocator: &A,
     ) -> Self::Evaluations {
         let h = mat.height();
         let log_h = log2_strict_usize(h);
@@ -85,7 +96,7 @@ impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for Radix2DitParallel {
             mat.scale_row(reverse_bits(row, h), weight);
         }
 
-        bit_reversed_zero_pad(&mut mat, added_bits);
+        bit_reversed_zero_pad(&mut mat, added_bits, allocator);
 
         let h = mat.height();
         let log_h = log2_strict_usize(h);
@@ -224,4 +235,9 @@ mod tests {
     fn dft_idft_consistency() {
         test_dft_idft_consistency::<BabyBear, Radix2DitParallel>();
     }
+
+    #[test]
+    fn in_place_matches_allocating() {
+        test_in_place_matches_allocating::<BabyBear, Radix2DitParallel>();
+    }
 }
@@ -1,6 +1,7 @@
 use p3_field::TwoAdicField;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::MatrixRows;
+use p3_util::allocator::GlobalTraceAllocator;
 use rand::distributions::{Distribution, Standard};
 use rand::thread_rng;
 
@@ -106,8 +107,11 @@ where
         let mat = RowMajorMatrix::<F>::rand(&mut rng, h, 3);
         let shift = F::generator();
         let coset_lde_naive = NaiveDft.coset_lde_batch(mat.clone(), 1, shift);
-        let coset_lde_result = dft.coset_lde_batch(mat, 1, shift);
+        let coset_lde_result = dft.coset_lde_batch(mat.clone(), 1, shift);
         assert_eq!(coset_lde_naive, coset_lde_result.to_row_major_matrix());
+        let coset_lde_allocated =
+            dft.coset_lde_batch_with_allocator(mat, 1, shift, &GlobalTraceAllocator);
+        assert_eq!(coset_lde_naive, coset_lde_allocated.to_row_major_matrix());
     }
 }
 
@@ -127,3 +131,26 @@ where
         assert_eq!(original, idft_output);
     }
 }
+
+pub(crate) fn test_in_place_matches_allocating<F, Dft>()
+where
+    F: TwoAdicField,
+    Standard: Distribution<F>,
+    Dft: TwoAdicSubgroupDft<F>,
+{
+    let dft = Dft::default();
+    let mut rng = thread_rng();
+    for log_h in 0..5 {
+        let h = 1 << log_h;
+        let original = RowMajorMatrix::<F>::rand(&mut rng, h, 3);
+
+        let mut mat = original.clone();
+        dft.dft_batch_in_place(&mut mat);
+        let dft_output = dft.dft_batch(original.clone()).to_row_major_matrix();
+        assert_eq!(mat, dft_output);
+
+        dft.idft_batch_in_place(&mut mat);
+        assert_eq!(mat, dft.idft_batch(dft_output));
+        assert_eq!(mat, original);
+    }
+}
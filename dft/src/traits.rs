@@ -1,10 +1,12 @@
 use alloc::vec::Vec;
+use core::mem;
 
 use p3_field::TwoAdicField;
 use p3_matrix::bitrev::BitReversableMatrix;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::util::swap_rows;
 use p3_matrix::{Matrix, MatrixRows};
+use p3_util::allocator::TraceAllocator;
 
 use crate::util::divide_by_height;
 
@@ -24,6 +26,18 @@ pub trait TwoAdicSubgroupDft<F: TwoAdicField>: Clone + Default {
     /// methods can be derived from this one.
     fn dft_batch(&self, mat: RowMajorMatrix<F>) -> Self::Evaluations;
 
+    /// Compute the DFT of each column in `mat`, overwriting it with the evaluations in their
+    /// natural order.
+    ///
+    /// By default this moves `mat` into `dft_batch`, so it only avoids allocating if `dft_batch`
+    /// reuses its input and its `Evaluations` can be put back in natural order without copying,
+    /// as for the radix-2 implementations.
+    fn dft_batch_in_place(&self, mat: &mut RowMajorMatrix<F>) {
+        let width = mat.width();
+        let input = mem::replace(mat, RowMajorMatrix::new(Vec::new(), width));
+        *mat = self.dft_batch(input).to_row_major_matrix();
+    }
+
     /// Compute the "coset DFT" of `vec`. This can be viewed as interpolation onto a coset of a
     /// multiplicative subgroup, rather than the subgroup itself.
     fn coset_dft(&self, vec: Vec<F>, shift: F) -> Vec<F> {
@@ -56,17 +70,21 @@ pub trait TwoAdicSubgroupDft<F: TwoAdicField>: Clone + Default {
     }
 
     /// Compute the inverse DFT of each column in `mat`.
-    fn idft_batch(&self, mat: RowMajorMatrix<F>) -> RowMajorMatrix<F> {
-        let mut dft = self.dft_batch(mat).to_row_major_matrix();
-        let h = dft.height();
+    fn idft_batch(&self, mut mat: RowMajorMatrix<F>) -> RowMajorMatrix<F> {
+        self.idft_batch_in_place(&mut mat);
+        mat
+    }
 
-        divide_by_height(&mut dft);
+    /// Compute the inverse DFT of each column in `mat`, overwriting it with the coefficients.
+    fn idft_batch_in_place(&self, mat: &mut RowMajorMatrix<F>) {
+        self.dft_batch_in_place(mat);
+        let h = mat.height();
+
+        divide_by_height(mat);
 
         for row in 1..h / 2 {
-            swap_rows(&mut dft, row, h - row);
+            swap_rows(mat, row, h - row);
         }
-
-        dft
     }
 
     /// Compute the "coset iDFT" of `vec`. This can be viewed as an inverse operation of
@@ -136,4 +154,22 @@ pub trait TwoAdicSubgroupDft<F: TwoAdicField>: Clone + Default {
         );
         self.coset_dft_batch(coeffs, shift)
     }
+
+    /// Like `coset_lde_batch`, but with the buffer holding the LDE taken from `allocator`.
+    fn coset_lde_batch_with_allocator<A: TraceAllocator>(
+        &self,
+        mat: RowMajorMatrix<F>,
+        added_bits: usize,
+        shift: F,
+        allocator: &A,
+    ) -> Self::Evaluations {
+        let coeffs = self.idft_batch(mat);
+        // Zero-pad the coefficients in a buffer of the LDE's size, which the DFT then transforms in
+        // place.
+        let lde_len = coeffs.values.len() << added_bits;
+        let mut values = allocator.allocate(lde_len);
+        values.extend_from_slice(&coeffs.values);
+        values.resize(lde_len, F::zero());
+        self.coset_dft_batch(RowMajorMatrix::new(values, coeffs.width()), shift)
+    }
 }
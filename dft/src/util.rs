@@ -1,8 +1,7 @@
-use alloc::vec;
-
 use p3_field::Field;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
+use p3_util::allocator::TraceAllocator;
 
 /// Divide each coefficient of the given matrix by its height.
 pub(crate) fn divide_by_height<F: Field>(mat: &mut RowMajorMatrix<F>) {
@@ -15,9 +14,14 @@ pub(crate) fn divide_by_height<F: Field>(mat: &mut RowMajorMatrix<F>) {
 }
 
 /// Append zeros to the "end" of the given matrix, except that the matrix is in bit-reversed order,
-/// so in actuality we're interleaving zero rows.
+/// so in actuality we're interleaving zero rows. The padded matrix is built in a buffer taken from
+/// `allocator`.
 #[inline]
-pub(crate) fn bit_reversed_zero_pad<F: Field>(mat: &mut RowMajorMatrix<F>, added_bits: usize) {
+pub(crate) fn bit_reversed_zero_pad<F: Field, A: TraceAllocator>(
+    mat: &mut RowMajorMatrix<F>,
+    added_bits: usize,
+    allocator: &A,
+) {
     if added_bits == 0 {
         return;
     }
@@ -32,7 +36,9 @@ pub(crate) fn bit_reversed_zero_pad<F: Field>(mat: &mut RowMajorMatrix<F>, added
     // whose rows are zero except for rows whose low `added_bits` bits are zero.
 
     let w = mat.width;
-    let mut values = vec![F::zero(); mat.values.len() << added_bits];
+    let len = mat.values.len() << added_bits;
+    let mut values = allocator.allocate(len);
+    values.resize(len, F::zero());
     for i in (0..mat.values.len()).step_by(w) {
         values[(i << added_bits)..((i << added_bits) + w)].copy_from_slice(&mat.values[i..i + w]);
     }
@@ -2,11 +2,14 @@ use alloc::format;
 use alloc::vec::Vec;
 
 use criterion::{black_box, BatchSize, Criterion};
-use p3_field::Field;
+use p3_field::extension::{BinomialExtensionField, BinomiallyExtendable};
+use p3_field::{dot_product_packed, Field};
 use rand::distributions::Standard;
 use rand::prelude::Distribution;
 use rand::Rng;
 
+use crate::binomial_mul_schoolbook;
+
 pub fn benchmark_square<F: Field>(c: &mut Criterion, name: &str)
 where
     Standard: Distribution<F>,
@@ -41,6 +44,62 @@ where
     });
 }
 
+/// Benchmark the optimized binomial extension multiplication against the schoolbook method.
+pub fn benchmark_binomial_mul<F: BinomiallyExtendable<D>, const D: usize>(
+    c: &mut Criterion,
+    name: &str,
+) where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    let x = rng.gen::<BinomialExtensionField<F, D>>();
+    let y = rng.gen::<BinomialExtensionField<F, D>>();
+    c.bench_function(&format!("{} mul", name), |b| {
+        b.iter(|| black_box(black_box(x) * black_box(y)))
+    });
+    c.bench_function(&format!("{} schoolbook mul", name), |b| {
+        b.iter(|| black_box(binomial_mul_schoolbook(black_box(x), black_box(y))))
+    });
+}
+
+/// Benchmark the dot product of two slices of length N, on both the packed and scalar paths.
+pub fn benchmark_dot_product<F: Field, const N: usize>(c: &mut Criterion, name: &str)
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    let a: Vec<F> = (0..N).map(|_| rng.gen()).collect();
+    let b: Vec<F> = (0..N).map(|_| rng.gen()).collect();
+    c.bench_function(&format!("{} dot_product/{}", name, N), |bench| {
+        bench.iter(|| dot_product_packed::<F::Packing>(black_box(&a), black_box(&b)))
+    });
+    c.bench_function(&format!("{} dot_product scalar/{}", name, N), |bench| {
+        bench.iter(|| dot_product_packed::<F>(black_box(&a), black_box(&b)))
+    });
+}
+
+/// Benchmark `Field::sum_slice` and `Field::product_slice` on a slice of length N, against a
+/// scalar fold.
+pub fn benchmark_sum_product_slice<F: Field, const N: usize>(c: &mut Criterion, name: &str)
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    let values: Vec<F> = (0..N).map(|_| rng.gen()).collect();
+    c.bench_function(&format!("{} sum_slice/{}", name, N), |b| {
+        b.iter(|| F::sum_slice(black_box(&values)))
+    });
+    c.bench_function(&format!("{} sum fold/{}", name, N), |b| {
+        b.iter(|| black_box(&values).iter().fold(F::zero(), |acc, &x| acc + x))
+    });
+    c.bench_function(&format!("{} product_slice/{}", name, N), |b| {
+        b.iter(|| F::product_slice(black_box(&values)))
+    });
+    c.bench_function(&format!("{} product fold/{}", name, N), |b| {
+        b.iter(|| black_box(&values).iter().fold(F::one(), |acc, &x| acc * x))
+    });
+}
+
 /// Benchmark the time taken to sum an array [F; N] using .sum() method.
 /// Repeat the summation REPS times.
 pub fn benchmark_iter_sum<F: Field, const N: usize, const REPS: usize>(
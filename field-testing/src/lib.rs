@@ -6,13 +6,19 @@ extern crate alloc;
 
 pub mod bench_func;
 
+use alloc::vec::Vec;
+
 pub use bench_func::*;
+use p3_field::extension::{BinomialExtensionField, BinomiallyExtendable};
 use p3_field::{
-    cyclic_subgroup_coset_known_order, cyclic_subgroup_known_order, two_adic_coset_zerofier,
-    two_adic_subgroup_zerofier, ExtensionField, Field, TwoAdicField,
+    cyclic_subgroup_coset_known_order, cyclic_subgroup_known_order, dot_product,
+    dot_product_packed, two_adic_coset_zerofier, two_adic_subgroup_zerofier,
+    AbstractExtensionField, AbstractField, ConstantTimeField, ExtensionField, Field, FieldError,
+    PrimeField64, TwoAdicField,
 };
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
+use subtle::Choice;
 
 #[allow(clippy::eq_op)]
 pub fn test_add_neg_sub_mul<F: Field>()
@@ -74,6 +80,39 @@ where
     }
 }
 
+pub fn test_dot_product<F: Field>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    // Include lengths which leave a partial packing, as well as ones which fill them exactly.
+    for len in [0, 1, 3, 8, 17, 64, 1023] {
+        let a: Vec<F> = (0..len).map(|_| rng.gen()).collect();
+        let b: Vec<F> = (0..len).map(|_| rng.gen()).collect();
+        let expected: F = a.iter().zip(&b).map(|(&x, &y)| x * y).sum();
+        assert_eq!(dot_product_packed::<F>(&a, &b), expected);
+        assert_eq!(dot_product_packed::<F::Packing>(&a, &b), expected);
+        assert_eq!(dot_product(&a, &b), expected);
+    }
+}
+
+pub fn test_sum_product<F: Field>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    // As in `test_dot_product`, include lengths which leave a partial packing.
+    for len in [0, 1, 3, 8, 17, 64, 1023] {
+        let values: Vec<F> = (0..len).map(|_| rng.gen()).collect();
+        let sum = values.iter().fold(F::zero(), |acc, &x| acc + x);
+        let product = values.iter().fold(F::one(), |acc, &x| acc * x);
+        assert_eq!(F::sum_iter(values.iter().copied()), sum);
+        assert_eq!(F::product_iter(values.iter().copied()), product);
+        assert_eq!(F::sum_slice(&values), sum);
+        assert_eq!(F::product_slice(&values), product);
+    }
+}
+
 pub fn test_two_adic_subgroup_zerofier<F: TwoAdicField>() {
     for log_n in 0..5 {
         let g = F::two_adic_generator(log_n);
@@ -113,6 +152,114 @@ pub fn test_ef_two_adic_generator_consistency<
     );
 }
 
+pub fn test_ef_order<F: Field, EF: ExtensionField<F>>() {
+    assert_eq!(EF::characteristic(), F::characteristic());
+    assert_eq!(
+        EF::order(),
+        F::order().pow(<EF as AbstractExtensionField<F>>::D as u32)
+    );
+}
+
+/// Multiply in a binomial extension by the schoolbook method, as a reference for the optimized
+/// multiplication.
+pub fn binomial_mul_schoolbook<F: BinomiallyExtendable<D>, const D: usize>(
+    x: BinomialExtensionField<F, D>,
+    y: BinomialExtensionField<F, D>,
+) -> BinomialExtensionField<F, D> {
+    let (a, b) = (x.as_base_slice(), y.as_base_slice());
+    let mut res = [F::zero(); D];
+    #[allow(clippy::needless_range_loop)]
+    for i in 0..D {
+        for j in 0..D {
+            if i + j >= D {
+                res[i + j - D] += F::w() * a[i] * b[j];
+            } else {
+                res[i + j] += a[i] * b[j];
+            }
+        }
+    }
+    BinomialExtensionField::from_base_slice(&res)
+}
+
+pub fn test_binomial_mul_matches_schoolbook<F: BinomiallyExtendable<D>, const D: usize>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    for _ in 0..100 {
+        let x = rng.gen::<BinomialExtensionField<F, D>>();
+        let y = rng.gen::<BinomialExtensionField<F, D>>();
+        assert_eq!(x * y, binomial_mul_schoolbook(x, y));
+        assert_eq!(x.square(), binomial_mul_schoolbook(x, x));
+    }
+    let neg_one = BinomialExtensionField::<F, D>::neg_one();
+    assert_eq!(neg_one * neg_one, binomial_mul_schoolbook(neg_one, neg_one));
+}
+
+pub fn test_ct_eq_conditional_select<F: ConstantTimeField>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    let x = rng.gen::<F>();
+    let y = rng.gen::<F>();
+    for (a, b) in [(x, x), (x, y), (x, x + F::one()), (F::zero(), -F::zero())] {
+        assert_eq!(bool::from(a.ct_eq(&b)), a == b);
+        assert_eq!(bool::from(b.ct_eq(&a)), a == b);
+    }
+    assert_eq!(F::conditional_select(&x, &y, Choice::from(0)), x);
+    assert_eq!(F::conditional_select(&x, &y, Choice::from(1)), y);
+}
+
+pub fn test_try_from_canonical<F: PrimeField64>() {
+    let order = F::ORDER_U64;
+    assert_eq!(
+        F::try_from_canonical_u64(order - 1),
+        Ok(F::zero() - F::one())
+    );
+    assert_eq!(F::try_from_canonical_u64(0), Ok(F::zero()));
+    assert_eq!(
+        F::try_from_canonical_u64(order),
+        Err(FieldError::NotCanonical(order))
+    );
+    assert_eq!(
+        F::try_from_canonical_u64(order + 1),
+        Err(FieldError::NotCanonical(order + 1))
+    );
+    assert!(F::try_from_canonical_u64(u64::MAX).is_err());
+
+    match u32::try_from(order) {
+        Ok(order) => {
+            assert_eq!(
+                F::try_from_canonical_u32(order - 1),
+                Ok(F::zero() - F::one())
+            );
+            assert!(F::try_from_canonical_u32(order).is_err());
+            assert!(F::try_from_canonical_u32(order + 1).is_err());
+        }
+        // Every `u32` is canonical.
+        Err(_) => assert!(F::try_from_canonical_u32(u32::MAX).is_ok()),
+    }
+}
+
+pub fn test_bits_le_round_trip<F: PrimeField64>() {
+    let f = F::from_canonical_u32(0b1011);
+    assert_eq!(f.to_bits_le(6), [true, true, false, true, false, false]);
+    assert_eq!(F::from_bits_le(&f.to_bits_le(6)), f);
+
+    let f = F::neg_one();
+    assert_eq!(f.as_canonical_biguint(), F::order() - 1u32);
+    assert_eq!(F::from_bits_le(&f.to_bits_le(F::bits())), f);
+    assert_eq!(F::from_bits_le(&f.to_bits_le(70)), f);
+
+    assert!(F::zero().to_bits_le(0).is_empty());
+}
+
+/// Panics, since `-1` needs every bit of the field's order.
+pub fn test_to_bits_le_too_few_bits<F: PrimeField64>() {
+    F::neg_one().to_bits_le(F::bits() - 1);
+}
+
 #[macro_export]
 macro_rules! test_field {
     ($field:ty) => {
@@ -129,6 +276,59 @@ macro_rules! test_field {
             fn test_inverse() {
                 $crate::test_inverse::<$field>();
             }
+            #[test]
+            fn test_dot_product() {
+                $crate::test_dot_product::<$field>();
+            }
+            #[test]
+            fn test_sum_product() {
+                $crate::test_sum_product::<$field>();
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_prime_field {
+    ($field:ty) => {
+        mod prime_field_tests {
+            #[test]
+            fn test_ct_eq_conditional_select() {
+                $crate::test_ct_eq_conditional_select::<$field>();
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_prime_field_64 {
+    ($field:ty) => {
+        mod prime_field_64_tests {
+            #[test]
+            fn test_try_from_canonical() {
+                $crate::test_try_from_canonical::<$field>();
+            }
+            #[test]
+            fn test_bits_le_round_trip() {
+                $crate::test_bits_le_round_trip::<$field>();
+            }
+            #[test]
+            #[should_panic]
+            fn test_to_bits_le_too_few_bits() {
+                $crate::test_to_bits_le_too_few_bits::<$field>();
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_binomial_extension_mul {
+    ($field:ty, $d:literal) => {
+        mod binomial_extension_mul_tests {
+            #[test]
+            fn test_mul_matches_schoolbook() {
+                $crate::test_binomial_mul_matches_schoolbook::<$field, $d>();
+            }
         }
     };
 }
@@ -166,6 +366,11 @@ macro_rules! test_two_adic_extension_field {
             fn test_ef_two_adic_generator_consistency() {
                 $crate::test_ef_two_adic_generator_consistency::<$field, $ef>();
             }
+
+            #[test]
+            fn test_ef_order() {
+                $crate::test_ef_order::<$field, $ef>();
+            }
         }
     };
 }
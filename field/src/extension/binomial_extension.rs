@@ -6,6 +6,7 @@ use core::iter::{Product, Sum};
 use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 use itertools::Itertools;
+use num_bigint::BigUint;
 use rand::distributions::Standard;
 use rand::prelude::Distribution;
 use serde::{Deserialize, Serialize};
@@ -218,6 +219,14 @@ impl<F: BinomiallyExtendable<D>, const D: usize> Field for BinomialExtensionFiel
             _ => Some(self.frobenius_inv()),
         }
     }
+
+    fn order() -> BigUint {
+        F::order().pow(D as u32)
+    }
+
+    fn characteristic() -> BigUint {
+        F::characteristic()
+    }
 }
 
 impl<F, const D: usize> Display for BinomialExtensionField<F, D>
@@ -396,9 +405,10 @@ where
 
         match D {
             2 => {
+                let [c0, c1] = quadratic_mul(&a, &b, w);
                 let mut res = Self::default();
-                res.value[0] = a[0].clone() * b[0].clone() + a[1].clone() * w_af * b[1].clone();
-                res.value[1] = a[0].clone() * b[1].clone() + a[1].clone() * b[0].clone();
+                res.value[0] = c0;
+                res.value[1] = c1;
                 res
             }
             3 => Self {
@@ -577,7 +587,21 @@ fn cubic_inv<F: Field>(a: &[F], w: F) -> [F; 3] {
     ]
 }
 
-/// karatsuba multiplication for cubic extension field
+/// Karatsuba multiplication for a quadratic extension field, using three base field
+/// multiplications, plus one by `w`, rather than schoolbook's four.
+#[inline]
+fn quadratic_mul<AF: AbstractField>(a: &[AF], b: &[AF], w: AF::F) -> [AF; 2] {
+    let a0_b0 = a[0].clone() * b[0].clone();
+    let a1_b1 = a[1].clone() * b[1].clone();
+
+    let c0 = a0_b0.clone() + a1_b1.clone() * AF::from_f(w);
+    let c1 = (a[0].clone() + a[1].clone()) * (b[0].clone() + b[1].clone()) - a0_b0 - a1_b1;
+
+    [c0, c1]
+}
+
+/// Karatsuba multiplication for a cubic extension field, using six base field multiplications,
+/// plus two by `w`, rather than schoolbook's nine.
 #[inline]
 fn cubic_mul<AF: AbstractField>(a: &[AF], b: &[AF], w: AF::F) -> [AF; 3] {
     let a0_b0 = a[0].clone() * b[0].clone();
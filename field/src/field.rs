@@ -1,13 +1,16 @@
 use alloc::vec;
+use alloc::vec::Vec;
 use core::fmt::{Debug, Display};
 use core::hash::Hash;
 use core::iter::{Product, Sum};
 use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
 use core::slice;
 
+use num_bigint::BigUint;
 use p3_util::log2_ceil_u64;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use subtle::Choice;
 
 use crate::exponentiation::exp_u64_by_squaring;
 use crate::packed::PackedField;
@@ -145,6 +148,20 @@ pub trait AbstractField:
     fn dot_product<const N: usize>(u: &[Self; N], v: &[Self; N]) -> Self {
         u.iter().zip(v).map(|(x, y)| x.clone() * y.clone()).sum()
     }
+
+    /// The sum of the elements of `iter`, or zero if it's empty.
+    ///
+    /// Concrete fields should prefer `Field::sum_slice` where the elements are in a slice.
+    fn sum_iter<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, x| acc + x)
+    }
+
+    /// The product of the elements of `iter`, or one if it's empty.
+    ///
+    /// Concrete fields should prefer `Field::product_slice` where the elements are in a slice.
+    fn product_iter<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |acc, x| acc * x)
+    }
 }
 
 /// An element of a finite field.
@@ -207,14 +224,102 @@ pub trait Field:
     fn inverse(&self) -> Self {
         self.try_inverse().expect("Tried to invert zero")
     }
+
+    /// The characteristic of the field, i.e. the order of its prime subfield.
+    fn characteristic() -> BigUint;
+
+    /// The number of elements in the field. The default is only correct for prime fields, whose
+    /// order equals their characteristic, so extensions must override it with
+    /// `characteristic^degree`.
+    fn order() -> BigUint {
+        Self::characteristic()
+    }
+
+    /// The sum of the elements of `slice`, accumulated `Self::Packing::WIDTH` at a time into a
+    /// packed sum which is only reduced to a scalar at the end.
+    fn sum_slice(slice: &[Self]) -> Self {
+        let (packed, suffix) = Self::Packing::pack_slice_with_suffix(slice);
+        let packed_sum = packed.iter().fold(Self::Packing::zero(), |acc, &x| acc + x);
+        Self::sum_iter(packed_sum.as_slice().iter().chain(suffix).copied())
+    }
+
+    /// The product of the elements of `slice`, computed with `Self::Packing` as in `sum_slice`.
+    fn product_slice(slice: &[Self]) -> Self {
+        let (packed, suffix) = Self::Packing::pack_slice_with_suffix(slice);
+        let packed_product = packed.iter().fold(Self::Packing::one(), |acc, &x| acc * x);
+        Self::product_iter(packed_product.as_slice().iter().chain(suffix).copied())
+    }
 }
 
-pub trait PrimeField: Field + Ord {}
+pub trait PrimeField: Field + Ord {
+    /// Return the representative of `value` that is less than the field's order.
+    fn as_canonical_biguint(&self) -> BigUint;
+
+    /// Return the low `num_bits` bits of the canonical representative, least significant first.
+    ///
+    /// Panics if the canonical representative does not fit in `num_bits` bits.
+    fn to_bits_le(&self, num_bits: usize) -> Vec<bool> {
+        let value = self.as_canonical_biguint();
+        assert!(
+            value.bits() <= num_bits as u64,
+            "{value} does not fit in {num_bits} bits"
+        );
+        let mut bits: Vec<bool> = value
+            .to_radix_le(2)
+            .into_iter()
+            .map(|digit| digit == 1)
+            .collect();
+        bits.resize(num_bits, false);
+        bits
+    }
+
+    /// The inverse of `to_bits_le`. The result is reduced modulo the field order, so bits beyond
+    /// its bit length wrap around.
+    fn from_bits_le(bits: &[bool]) -> Self {
+        bits.iter().rev().fold(Self::zero(), |acc, &bit| {
+            acc.double() + Self::from_bool(bit)
+        })
+    }
+}
+
+/// A `PrimeField` with constant-time comparison and selection, for fields used with secret values.
+pub trait ConstantTimeField: PrimeField {
+    /// Compare two elements by their canonical representatives, in time independent of both.
+    ///
+    /// Unlike `==`, this doesn't branch on the inputs, so it's suitable for comparing secret
+    /// values.
+    fn ct_eq(&self, other: &Self) -> Choice;
+
+    /// Return `a` if `choice` is 0 and `b` if it is 1, in time independent of all three inputs.
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self;
+}
+
+/// An error from constructing a field element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldError {
+    /// The given value was not less than the order of the field.
+    NotCanonical(u64),
+}
 
 /// A prime field of order less than `2^64`.
 pub trait PrimeField64: PrimeField {
     const ORDER_U64: u64;
 
+    /// Like `from_canonical_u64`, but checks that `n` is less than `ORDER_U64` in all builds,
+    /// rather than only in debug builds, so that it's safe to use on untrusted input.
+    fn try_from_canonical_u64(n: u64) -> Result<Self, FieldError> {
+        if n < Self::ORDER_U64 {
+            Ok(Self::from_canonical_u64(n))
+        } else {
+            Err(FieldError::NotCanonical(n))
+        }
+    }
+
+    /// The `u32` version of `try_from_canonical_u64`.
+    fn try_from_canonical_u32(n: u32) -> Result<Self, FieldError> {
+        Self::try_from_canonical_u64(n.into())
+    }
+
     // TODO: Move to Field itself? Limiting it to `PrimeField64` seems unusual.
     #[must_use]
     #[allow(clippy::cast_possible_truncation)]
@@ -3,7 +3,7 @@ use alloc::vec::Vec;
 use core::array;
 
 use crate::field::Field;
-use crate::{AbstractField, TwoAdicField};
+use crate::{AbstractField, PackedField, PackedValue, TwoAdicField};
 
 /// Computes `Z_H(x)`, where `Z_H` is the zerofier of a multiplicative subgroup of order `2^log_n`.
 pub fn two_adic_subgroup_zerofier<F: TwoAdicField>(log_n: usize, x: F) -> F {
@@ -48,6 +48,29 @@ pub fn scale_vec<F: Field>(s: F, vec: Vec<F>) -> Vec<F> {
     vec.into_iter().map(|x| s * x).collect()
 }
 
+/// Computes `sum_i a_i b_i`. Panics if `a` and `b` have different lengths.
+pub fn dot_product<F: Field>(a: &[F], b: &[F]) -> F {
+    dot_product_packed::<F::Packing>(a, b)
+}
+
+/// Computes `sum_i a_i b_i`, multiplying `P::WIDTH` pairs at a time and accumulating into a single
+/// packed sum, which is only reduced to a scalar at the end. Elements beyond the last full packing
+/// are handled one at a time. Panics if `a` and `b` have different lengths.
+///
+/// With `P = F`, this is the plain scalar loop.
+pub fn dot_product_packed<P: PackedField>(a: &[P::Scalar], b: &[P::Scalar]) -> P::Scalar {
+    assert_eq!(a.len(), b.len(), "dot_product: length mismatch");
+    let (a_packed, a_suffix) = P::pack_slice_with_suffix(a);
+    let (b_packed, b_suffix) = P::pack_slice_with_suffix(b);
+
+    let mut packed_sum = P::zero();
+    for (&x, &y) in a_packed.iter().zip(b_packed) {
+        packed_sum += x * y;
+    }
+    let suffix_sum: P::Scalar = a_suffix.iter().zip(b_suffix).map(|(&x, &y)| x * y).sum();
+    packed_sum.as_slice().iter().copied().sum::<P::Scalar>() + suffix_sum
+}
+
 /// `x += y * s`, where `s` is a scalar.
 pub fn add_scaled_slice_in_place<F, Y>(x: &mut [F], y: Y, s: F)
 where
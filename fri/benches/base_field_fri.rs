@@ -0,0 +1,212 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::measurement::{Measurement, ValueFormatter};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use p3_baby_bear::BabyBear;
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractExtensionField, Field};
+use p3_fri::{prover, FriConfig};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{DiffusionMatrixBabybear, Poseidon2};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_util::reverse_slice_index_bits;
+use rand::thread_rng;
+
+type Val = BabyBear;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Perm = Poseidon2<Val, DiffusionMatrixBabybear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16>;
+
+/// Tracks the bytes currently allocated, and the most ever allocated at once since the last reset.
+struct PeakAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+impl PeakAllocator {
+    fn reset_peak() {
+        PEAK.store(CURRENT.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+
+    /// The peak since the last reset, above what was allocated at the reset.
+    fn peak_since(base: usize) -> usize {
+        PEAK.load(Ordering::Relaxed) - base
+    }
+
+    fn grow(size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::grow(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if new_size > layout.size() {
+            Self::grow(new_size - layout.size());
+        } else {
+            CURRENT.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+        }
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: PeakAllocator = PeakAllocator;
+
+/// A criterion measurement of the most bytes allocated at once, above what was allocated at the
+/// start, rather than the time taken. It's only meaningful for one iteration per batch.
+struct PeakMemory;
+
+impl Measurement for PeakMemory {
+    type Intermediate = usize;
+    type Value = usize;
+
+    fn start(&self) -> usize {
+        PeakAllocator::reset_peak();
+        CURRENT.load(Ordering::Relaxed)
+    }
+
+    fn end(&self, base: usize) -> usize {
+        PeakAllocator::peak_since(base)
+    }
+
+    fn add(&self, v1: &usize, v2: &usize) -> usize {
+        v1 + v2
+    }
+
+    fn zero(&self) -> usize {
+        0
+    }
+
+    fn to_f64(&self, value: &usize) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &BytesFormatter
+    }
+}
+
+struct BytesFormatter;
+
+impl ValueFormatter for BytesFormatter {
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        let (factor, unit) = if typical_value < 1024.0 {
+            (1.0, "B")
+        } else if typical_value < 1024.0 * 1024.0 {
+            (1024.0, "KiB")
+        } else {
+            (1024.0 * 1024.0, "MiB")
+        };
+        values.iter_mut().for_each(|v| *v /= factor);
+        unit
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let (n, unit) = match *throughput {
+            Throughput::Bytes(n) | Throughput::BytesDecimal(n) => (n, "B/byte"),
+            Throughput::Elements(n) => (n, "B/element"),
+        };
+        values.iter_mut().for_each(|v| *v /= n as f64);
+        unit
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "B"
+    }
+}
+
+fn bench_base_field_fri_time(c: &mut Criterion) {
+    bench_base_field_fri(c, "base_field_fri", BatchSize::LargeInput);
+}
+
+fn bench_base_field_fri_peak_memory(c: &mut Criterion<PeakMemory>) {
+    bench_base_field_fri(c, "base_field_fri_peak_memory", BatchSize::PerIteration);
+}
+
+/// Compares proving the low degree of a base field codeword with `prove_base_field` against
+/// lifting it to the extension and calling `prove`, by whichever measurement `c` uses. The input
+/// is made outside the measurement, so it isn't counted in the peak memory.
+fn bench_base_field_fri<M: Measurement>(c: &mut Criterion<M>, name: &str, batch_size: BatchSize) {
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+
+    let mut rng = thread_rng();
+    let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng);
+    let base_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let config = FriConfig {
+        log_blowup: 1,
+        num_queries: 100,
+        proof_of_work_bits: 16,
+        mmcs: ChallengeMmcs::new(base_mmcs.clone()),
+    };
+
+    for log_size in [16, 18] {
+        let evals = RowMajorMatrix::<Val>::rand(&mut rng, 1 << log_size, 1).values;
+        let mut codeword = Radix2Dit::default().lde(evals, 1);
+        reverse_slice_index_bits(&mut codeword);
+
+        let base_field_input = || {
+            let mut input: [Option<Vec<Val>>; 32] = Default::default();
+            input[log_size + 1] = Some(codeword.clone());
+            input
+        };
+        let extension_input = || {
+            let mut input: [Option<Vec<Challenge>>; 32] = Default::default();
+            input[log_size + 1] = Some(codeword.iter().map(|&x| Challenge::from_base(x)).collect());
+            input
+        };
+        let prove_base_field = |input: [Option<Vec<Val>>; 32]| {
+            let mut challenger = Challenger::new(perm.clone());
+            prover::prove_base_field::<_, Challenge, _, _, _>(
+                &config,
+                &base_mmcs,
+                &input,
+                &mut challenger,
+            )
+        };
+        let prove_extension = |input: [Option<Vec<Challenge>>; 32]| {
+            prover::prove(&config, &input, &mut Challenger::new(perm.clone()))
+        };
+
+        group.bench_function(BenchmarkId::new("base_field", 1 << log_size), |b| {
+            b.iter_batched(base_field_input, prove_base_field, batch_size)
+        });
+        group.bench_function(BenchmarkId::new("extension", 1 << log_size), |b| {
+            b.iter_batched(extension_input, prove_extension, batch_size)
+        });
+    }
+}
+
+criterion_group!(time, bench_base_field_fri_time);
+criterion_group! {
+    name = peak_memory;
+    config = Criterion::default().with_measurement(PeakMemory);
+    targets = bench_base_field_fri_peak_memory
+}
+criterion_main!(time, peak_memory);
@@ -23,7 +23,7 @@ where
         let n = 1 << log_size;
 
         let mut rng = thread_rng();
-        let beta = rng.sample(Standard);
+        let beta: F = rng.sample(Standard);
         let poly = rng.sample_iter(Standard).take(n).collect_vec();
 
         group.bench_function(BenchmarkId::from_parameter(n), |b| {
@@ -0,0 +1,170 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::measurement::{Measurement, ValueFormatter};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use p3_baby_bear::BabyBear;
+use p3_challenger::DuplexChallenger;
+use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
+use p3_field::Field;
+use p3_fri::prover::{self, FriScratch};
+use p3_fri::FriConfig;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{DiffusionMatrixBabybear, Poseidon2};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_util::reverse_slice_index_bits;
+use rand::thread_rng;
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, DiffusionMatrixBabybear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenger = DuplexChallenger<Val, Perm, 16>;
+
+const NUM_PROOFS: usize = 10;
+
+/// Counts calls to the system allocator, so that the benchmark can measure how many allocations
+/// each batch of proofs makes.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// A criterion measurement of the number of allocations made, rather than the time taken.
+struct Allocations;
+
+impl Measurement for Allocations {
+    type Intermediate = usize;
+    type Value = usize;
+
+    fn start(&self) -> usize {
+        ALLOCATIONS.load(Ordering::Relaxed)
+    }
+
+    fn end(&self, start: usize) -> usize {
+        ALLOCATIONS.load(Ordering::Relaxed) - start
+    }
+
+    fn add(&self, v1: &usize, v2: &usize) -> usize {
+        v1 + v2
+    }
+
+    fn zero(&self) -> usize {
+        0
+    }
+
+    fn to_f64(&self, value: &usize) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &AllocationsFormatter
+    }
+}
+
+struct AllocationsFormatter;
+
+impl ValueFormatter for AllocationsFormatter {
+    fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+        "allocations"
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let (n, unit) = match *throughput {
+            Throughput::Bytes(n) | Throughput::BytesDecimal(n) => (n, "allocations/byte"),
+            Throughput::Elements(n) => (n, "allocations/element"),
+        };
+        values.iter_mut().for_each(|v| *v /= n as f64);
+        unit
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "allocations"
+    }
+}
+
+fn bench_fri_scratch_time(c: &mut Criterion) {
+    bench_fri_scratch(c, "fri_scratch");
+}
+
+fn bench_fri_scratch_allocations(c: &mut Criterion<Allocations>) {
+    bench_fri_scratch(c, "fri_scratch_allocations");
+}
+
+/// Compares `NUM_PROOFS` consecutive FRI proofs made with `prove` against ones sharing a single
+/// `FriScratch`, by whichever measurement `c` uses.
+fn bench_fri_scratch<M: Measurement>(c: &mut Criterion<M>, name: &str) {
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+
+    let mut rng = thread_rng();
+    let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng);
+    let config = FriConfig {
+        log_blowup: 1,
+        num_queries: 100,
+        proof_of_work_bits: 16,
+        mmcs: ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone())),
+    };
+
+    for log_size in [14, 16] {
+        let evals = RowMajorMatrix::<Val>::rand(&mut rng, 1 << log_size, 1).values;
+        let mut codeword = Radix2Dit::default().lde(evals, 1);
+        reverse_slice_index_bits(&mut codeword);
+        let mut input: [Option<Vec<Val>>; 32] = Default::default();
+        input[log_size + 1] = Some(codeword);
+
+        let prove_without_scratch = || {
+            for _ in 0..NUM_PROOFS {
+                prover::prove(&config, &input, &mut Challenger::new(perm.clone()));
+            }
+        };
+        let mut scratch = FriScratch::new();
+        let mut prove_with_scratch = || {
+            for _ in 0..NUM_PROOFS {
+                let mut challenger = Challenger::new(perm.clone());
+                prover::prove_with_scratch(&config, &input, &mut challenger, &mut scratch);
+            }
+        };
+
+        group.bench_function(BenchmarkId::new("without_scratch", 1 << log_size), |b| {
+            b.iter(prove_without_scratch)
+        });
+        group.bench_function(BenchmarkId::new("with_scratch", 1 << log_size), |b| {
+            b.iter(&mut prove_with_scratch)
+        });
+    }
+}
+
+criterion_group!(time, bench_fri_scratch_time);
+criterion_group! {
+    name = allocations;
+    config = Criterion::default().with_measurement(Allocations);
+    targets = bench_fri_scratch_allocations
+}
+criterion_main!(time, allocations);
@@ -1,9 +1,8 @@
 use alloc::vec::Vec;
 
 use itertools::Itertools;
-use p3_field::TwoAdicField;
-use p3_matrix::dense::RowMajorMatrix;
-use p3_matrix::Matrix;
+use p3_field::{ExtensionField, TwoAdicField};
+use p3_matrix::MatrixRows;
 use p3_util::{log2_strict_usize, reverse_slice_index_bits};
 use tracing::instrument;
 
@@ -15,9 +14,53 @@ use tracing::instrument;
 /// ```ignore
 /// p_even(x) + beta p_odd(x)
 /// ```
-/// Expects input to be bit-reversed evaluations.
+/// Expects input to be bit-reversed evaluations. The polynomial may be over a subfield of `beta`'s
+/// field, in which case the fold lifts it.
+pub fn fold_even_odd<F: TwoAdicField, EF: ExtensionField<F>>(poly: Vec<F>, beta: EF) -> Vec<EF> {
+    let mut folded = Vec::with_capacity(poly.len() / 2);
+    fold_even_odd_into(&poly, beta, &mut Vec::new(), &mut folded);
+    folded
+}
+
+/// Like `fold_even_odd`, but writes the folded evaluations to `out`, and uses `powers` as scratch
+/// space. Both are cleared first, so that callers can reuse their allocations.
 #[instrument(skip_all, level = "debug")]
-pub fn fold_even_odd<F: TwoAdicField>(poly: Vec<F>, beta: F) -> Vec<F> {
+pub fn fold_even_odd_into<F: TwoAdicField, EF: ExtensionField<F>>(
+    poly: &[F],
+    beta: EF,
+    powers: &mut Vec<EF>,
+    out: &mut Vec<EF>,
+) {
+    let pairs = poly.chunks_exact(2).map(|pair| (pair[0], pair[1]));
+    fold_pairs_into(pairs, poly.len() / 2, beta, powers, out);
+}
+
+/// Like `fold_even_odd_into`, but reads the evaluations from a width-two matrix, each row of which
+/// holds a pair of evaluations to fold together, such as the leaves of a committed FRI layer.
+#[instrument(skip_all, level = "debug")]
+pub fn fold_even_odd_rows_into<F, EF, M>(
+    rows: &M,
+    beta: EF,
+    powers: &mut Vec<EF>,
+    out: &mut Vec<EF>,
+) where
+    F: TwoAdicField,
+    EF: ExtensionField<F>,
+    M: MatrixRows<F>,
+{
+    assert_eq!(rows.width(), 2);
+    let pairs = (0..rows.height()).map(|r| rows.row(r).into_iter().collect_tuple().unwrap());
+    fold_pairs_into(pairs, rows.height(), beta, powers, out);
+}
+
+/// Folds the `half_len` pairs `(p(x), p(-x))` of a bit-reversed codeword.
+fn fold_pairs_into<F: TwoAdicField, EF: ExtensionField<F>>(
+    pairs: impl Iterator<Item = (F, F)>,
+    half_len: usize,
+    beta: EF,
+    powers: &mut Vec<EF>,
+    out: &mut Vec<EF>,
+) {
     // We use the fact that
     //     p_e(x^2) = (p(x) + p(-x)) / 2
     //     p_o(x^2) = (p(x) - p(-x)) / (2 x)
@@ -26,33 +69,39 @@ pub fn fold_even_odd<F: TwoAdicField>(poly: Vec<F>, beta: F) -> Vec<F> {
     //     p_o(g^(2i)) = (p(g^i) - p(g^(n/2 + i))) / (2 g^i)
     // so
     //     result(g^(2i)) = p_e(g^(2i)) + beta p_o(g^(2i))
-    //                    = (1/2 + beta/2 g_inv^i) p(g^i)
-    //                    + (1/2 - beta/2 g_inv^i) p(g^(n/2 + i))
-    let m = RowMajorMatrix::new(poly, 2);
-    let g_inv = F::two_adic_generator(log2_strict_usize(m.height()) + 1).inverse();
+    //                    = (p(g^i) + p(g^(n/2 + i))) / 2
+    //                    + beta/2 g_inv^i (p(g^i) - p(g^(n/2 + i)))
+    // where only the second term needs arithmetic in `beta`'s field.
+    let g_inv = F::two_adic_generator(log2_strict_usize(half_len) + 1).inverse();
     let one_half = F::two().inverse();
     let half_beta = beta * one_half;
 
     // TODO: vectorize this (after we have packed extension fields)
 
     // beta/2 times successive powers of g_inv
-    let mut powers = g_inv
-        .shifted_powers(half_beta)
-        .take(m.height())
-        .collect_vec();
-    reverse_slice_index_bits(&mut powers);
-
-    m.rows()
-        .zip(powers)
-        .map(|(row, power)| (one_half + power) * row[0] + (one_half - power) * row[1])
-        .collect()
+    powers.clear();
+    powers.extend(
+        EF::from_base(g_inv)
+            .shifted_powers(half_beta)
+            .take(half_len),
+    );
+    reverse_slice_index_bits(powers);
+
+    out.clear();
+    out.extend(
+        pairs
+            .zip(powers.iter())
+            .map(|((lo, hi), &power)| power * (lo - hi) + (lo + hi) * one_half),
+    );
 }
 
 #[cfg(test)]
 mod tests {
-    use itertools::izip;
+    use itertools::{izip, Itertools};
     use p3_baby_bear::BabyBear;
     use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
+    use p3_field::extension::BinomialExtensionField;
+    use p3_field::AbstractExtensionField;
     use rand::{thread_rng, Rng};
 
     use super::*;
@@ -89,4 +138,17 @@ mod tests {
 
         assert_eq!(expected, folded);
     }
+
+    #[test]
+    fn test_fold_even_odd_lifts() {
+        type F = BabyBear;
+        type EF = BinomialExtensionField<F, 4>;
+
+        let mut rng = thread_rng();
+        let poly = (0..1 << 8).map(|_| rng.gen::<F>()).collect::<Vec<_>>();
+        let lifted = poly.iter().map(|&x| EF::from_base(x)).collect::<Vec<_>>();
+        let beta = rng.gen::<EF>();
+
+        assert_eq!(fold_even_odd(poly, beta), fold_even_odd(lifted, beta));
+    }
 }
@@ -8,10 +8,12 @@ mod config;
 mod fold_even_odd;
 mod proof;
 pub mod prover;
+mod query_sampler;
 pub mod two_adic_pcs;
 pub mod verifier;
 
 pub use config::*;
 pub use fold_even_odd::*;
 pub use proof::*;
+pub use query_sampler::*;
 pub use two_adic_pcs::*;
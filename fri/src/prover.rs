@@ -1,17 +1,20 @@
 use alloc::vec;
 use alloc::vec::Vec;
+use core::mem;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 use itertools::Itertools;
 use p3_challenger::{CanObserve, CanSample, GrindingChallenger};
-use p3_commit::{DirectMmcs, Mmcs};
-use p3_field::{Field, TwoAdicField};
+use p3_commit::{DirectMmcs, Mmcs, COMMIT_PHASE_SPAN, QUERY_PHASE_SPAN};
+use p3_field::{ExtensionField, Field, TwoAdicField};
 use p3_matrix::dense::RowMajorMatrix;
 use tracing::{info_span, instrument};
 
-use crate::fold_even_odd::fold_even_odd;
-use crate::{CommitPhaseProofStep, FriConfig, FriProof, QueryProof};
+use crate::fold_even_odd::{fold_even_odd_into, fold_even_odd_rows_into};
+use crate::{
+    ChallengerQuerySampler, CommitPhaseProofStep, FriConfig, FriProof, QueryProof, QuerySampler,
+};
 
-#[instrument(name = "FRI prover", skip_all)]
 pub fn prove<F, M, Challenger>(
     config: &FriConfig<M>,
     input: &[Option<Vec<F>>; 32],
@@ -21,29 +24,315 @@ where
     F: TwoAdicField,
     M: DirectMmcs<F>,
     Challenger: GrindingChallenger + CanObserve<M::Commitment> + CanSample<F>,
+{
+    prove_with_sampler(config, input, challenger, &ChallengerQuerySampler)
+}
+
+/// Working buffers for the FRI prover, which can be kept between proofs to avoid reallocating them
+/// each time.
+///
+/// `prove_with_scratch` clears the buffers before using them, so a scratch left over from any
+/// earlier proof can be passed in, and the proof doesn't depend on it. Each round's codeword is
+/// moved into its commitment rather than copied, so the codeword buffers are handed on to the
+/// commitments, while the folding coefficients' buffer is kept. Its capacity only grows, so once a
+/// scratch has been used for the largest input it will see, later proofs don't allocate for it.
+#[derive(Debug)]
+pub struct FriScratch<F> {
+    /// The codeword being folded in the current round.
+    current: Vec<F>,
+    /// The output of the current round's fold.
+    folded: Vec<F>,
+    /// The folding coefficients for the current round.
+    powers: Vec<F>,
+}
+
+impl<F> Default for FriScratch<F> {
+    fn default() -> Self {
+        Self {
+            current: Vec::new(),
+            folded: Vec::new(),
+            powers: Vec::new(),
+        }
+    }
+}
+
+impl<F> FriScratch<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Like `prove`, but folds the codewords in the buffers of `scratch` rather than in fresh
+/// allocations. The proof is identical to the one `prove` would produce.
+pub fn prove_with_scratch<F, M, Challenger>(
+    config: &FriConfig<M>,
+    input: &[Option<Vec<F>>; 32],
+    challenger: &mut Challenger,
+    scratch: &mut FriScratch<F>,
+) -> (FriProof<F, M, Challenger::Witness>, Vec<usize>)
+where
+    F: TwoAdicField,
+    M: DirectMmcs<F>,
+    Challenger: GrindingChallenger + CanObserve<M::Commitment> + CanSample<F>,
+{
+    prove_internal(
+        config,
+        input,
+        challenger,
+        &ChallengerQuerySampler,
+        scratch,
+        None,
+    )
+    .expect("proving without a cancellation flag cannot be cancelled")
+}
+
+/// Like `prove`, but checks `cancel` before each round of folding, and returns `None` at the first
+/// check after it has been set.
+pub fn prove_cancellable<F, M, Challenger>(
+    config: &FriConfig<M>,
+    input: &[Option<Vec<F>>; 32],
+    challenger: &mut Challenger,
+    cancel: &AtomicBool,
+) -> Option<(FriProof<F, M, Challenger::Witness>, Vec<usize>)>
+where
+    F: TwoAdicField,
+    M: DirectMmcs<F>,
+    Challenger: GrindingChallenger + CanObserve<M::Commitment> + CanSample<F>,
+{
+    prove_internal(
+        config,
+        input,
+        challenger,
+        &ChallengerQuerySampler,
+        &mut FriScratch::new(),
+        Some(cancel),
+    )
+}
+
+/// Like `prove`, but with query indices drawn by the given `QuerySampler`.
+pub fn prove_with_sampler<F, M, Challenger, S>(
+    config: &FriConfig<M>,
+    input: &[Option<Vec<F>>; 32],
+    challenger: &mut Challenger,
+    query_sampler: &S,
+) -> (FriProof<F, M, Challenger::Witness>, Vec<usize>)
+where
+    F: TwoAdicField,
+    M: DirectMmcs<F>,
+    Challenger: GrindingChallenger + CanObserve<M::Commitment> + CanSample<F>,
+    S: QuerySampler<Challenger>,
+{
+    prove_internal(
+        config,
+        input,
+        challenger,
+        query_sampler,
+        &mut FriScratch::new(),
+        None,
+    )
+    .expect("proving without a cancellation flag cannot be cancelled")
+}
+
+#[instrument(name = "FRI prover", skip_all)]
+fn prove_internal<F, M, Challenger, S>(
+    config: &FriConfig<M>,
+    input: &[Option<Vec<F>>; 32],
+    challenger: &mut Challenger,
+    query_sampler: &S,
+    scratch: &mut FriScratch<F>,
+    cancel: Option<&AtomicBool>,
+) -> Option<(FriProof<F, M, Challenger::Witness>, Vec<usize>)>
+where
+    F: TwoAdicField,
+    M: DirectMmcs<F>,
+    Challenger: GrindingChallenger + CanObserve<M::Commitment> + CanSample<F>,
+    S: QuerySampler<Challenger>,
 {
     let log_max_height = input.iter().rposition(Option::is_some).unwrap();
 
-    let commit_phase_result = commit_phase(config, input, log_max_height, challenger);
+    let CommitPhaseResult {
+        commits,
+        data,
+        final_poly,
+    } = info_span!(COMMIT_PHASE_SPAN).in_scope(|| {
+        scratch.current.clear();
+        scratch
+            .current
+            .extend_from_slice(input[log_max_height].as_ref().unwrap());
+        commit_phase(config, input, log_max_height, challenger, scratch, cancel)
+    })?;
+
+    Some(query_phase(
+        config,
+        challenger,
+        query_sampler,
+        log_max_height,
+        commits,
+        final_poly,
+        |index| answer_query(config, &data, index),
+    ))
+}
 
+/// Like `prove`, but for codewords over a subfield `F` of the challenge field `EF`, such as those
+/// of base field polynomials.
+///
+/// The largest codeword stays in `F`: it's committed with `base_mmcs`, which must produce the same
+/// commitments and proofs as `config.mmcs` does for its lifts, such as the inner MMCS of an
+/// `ExtensionMmcs`. The first fold, by a challenge drawn from `EF`, lifts it, and the smaller
+/// codewords are added to the folded ones without being lifted first. Compared to lifting the
+/// input and calling `prove`, this keeps the input and the first commit phase tree `EF::D` times
+/// smaller, and makes the first fold mostly base field arithmetic. When `F` is `EF` itself the
+/// proof is the same as `prove`'s, so code generic over the codewords' field can always call this,
+/// and takes the base field path whenever that field is a proper subfield. It must be checked with
+/// `verifier::verify_challenges_base_field`.
+pub fn prove_base_field<F, EF, M, BaseMmcs, Challenger>(
+    config: &FriConfig<M>,
+    base_mmcs: &BaseMmcs,
+    input: &[Option<Vec<F>>; 32],
+    challenger: &mut Challenger,
+) -> (FriProof<EF, M, Challenger::Witness>, Vec<usize>)
+where
+    F: TwoAdicField,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: DirectMmcs<EF>,
+    BaseMmcs: DirectMmcs<F, Commitment = M::Commitment, Proof = M::Proof>,
+    Challenger: GrindingChallenger + CanObserve<M::Commitment> + CanSample<EF>,
+{
+    prove_base_field_with_sampler(
+        config,
+        base_mmcs,
+        input,
+        challenger,
+        &ChallengerQuerySampler,
+    )
+}
+
+/// Like `prove_base_field`, but with query indices drawn by the given `QuerySampler`.
+#[instrument(name = "FRI prover", skip_all)]
+pub fn prove_base_field_with_sampler<F, EF, M, BaseMmcs, Challenger, S>(
+    config: &FriConfig<M>,
+    base_mmcs: &BaseMmcs,
+    input: &[Option<Vec<F>>; 32],
+    challenger: &mut Challenger,
+    query_sampler: &S,
+) -> (FriProof<EF, M, Challenger::Witness>, Vec<usize>)
+where
+    F: TwoAdicField,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: DirectMmcs<EF>,
+    BaseMmcs: DirectMmcs<F, Commitment = M::Commitment, Proof = M::Proof>,
+    Challenger: GrindingChallenger + CanObserve<M::Commitment> + CanSample<EF>,
+    S: QuerySampler<Challenger>,
+{
+    let log_max_height = input.iter().rposition(Option::is_some).unwrap();
+    let largest = input[log_max_height].as_ref().unwrap();
+    let mut scratch = FriScratch::new();
+
+    let (base_layer, commit_phase_result) = info_span!(COMMIT_PHASE_SPAN).in_scope(|| {
+        if log_max_height == config.log_blowup {
+            // There's nothing to fold, so the input is the final polynomial.
+            scratch
+                .current
+                .extend(largest.iter().map(|&x| EF::from_base(x)));
+            let result = commit_phase(
+                config,
+                input,
+                log_max_height,
+                challenger,
+                &mut scratch,
+                None,
+            );
+            return (None, result);
+        }
+
+        let (commit, prover_data) =
+            base_mmcs.commit_matrix(RowMajorMatrix::new(largest.clone(), 2));
+        challenger.observe(commit.clone());
+        let beta: EF = challenger.sample();
+        fold_even_odd_into(largest, beta, &mut scratch.powers, &mut scratch.current);
+        if let Some(v) = &input[log_max_height - 1] {
+            add_codeword(&mut scratch.current, v);
+        }
+
+        let mut result = commit_phase(
+            config,
+            input,
+            log_max_height - 1,
+            challenger,
+            &mut scratch,
+            None,
+        );
+        if let Some(result) = &mut result {
+            result.commits.insert(0, commit);
+        }
+        (Some(prover_data), result)
+    });
+
+    let CommitPhaseResult {
+        commits,
+        data,
+        final_poly,
+    } = commit_phase_result.expect("proving without a cancellation flag cannot be cancelled");
+    let answer_base_field_query = |index: usize| match &base_layer {
+        None => answer_query(config, &data, index),
+        Some(base_layer) => {
+            let (sibling_value, opening_proof) = open_sibling(base_mmcs, base_layer, index);
+            let mut query_proof = answer_query(config, &data, index >> 1);
+            query_proof.commit_phase_openings.insert(
+                0,
+                CommitPhaseProofStep {
+                    sibling_value: EF::from_base(sibling_value),
+                    opening_proof,
+                },
+            );
+            query_proof
+        }
+    };
+
+    query_phase(
+        config,
+        challenger,
+        query_sampler,
+        log_max_height,
+        commits,
+        final_poly,
+        answer_base_field_query,
+    )
+}
+
+/// Grinds, samples the query indices, and answers each query with `answer_query`.
+fn query_phase<F, M, Challenger, S>(
+    config: &FriConfig<M>,
+    challenger: &mut Challenger,
+    query_sampler: &S,
+    log_max_height: usize,
+    commit_phase_commits: Vec<M::Commitment>,
+    final_poly: F,
+    answer_query: impl Fn(usize) -> QueryProof<F, M>,
+) -> (FriProof<F, M, Challenger::Witness>, Vec<usize>)
+where
+    F: Field,
+    M: Mmcs<F>,
+    Challenger: GrindingChallenger,
+    S: QuerySampler<Challenger>,
+{
     let pow_witness = challenger.grind(config.proof_of_work_bits);
 
-    let query_indices: Vec<usize> = (0..config.num_queries)
-        .map(|_| challenger.sample_bits(log_max_height))
-        .collect();
+    let query_indices =
+        query_sampler.sample_query_indices(challenger, config.num_queries, log_max_height);
 
-    let query_proofs = info_span!("query phase").in_scope(|| {
+    let query_proofs = info_span!(QUERY_PHASE_SPAN).in_scope(|| {
         query_indices
             .iter()
-            .map(|&index| answer_query(config, &commit_phase_result.data, index))
+            .map(|&index| answer_query(index))
             .collect()
     });
 
     (
         FriProof {
-            commit_phase_commits: commit_phase_result.commits,
+            commit_phase_commits,
             query_proofs,
-            final_poly: commit_phase_result.final_poly,
+            final_poly,
             pow_witness,
         },
         query_indices,
@@ -63,16 +352,7 @@ where
         .iter()
         .enumerate()
         .map(|(i, commit)| {
-            let index_i = index >> i;
-            let index_i_sibling = index_i ^ 1;
-            let index_pair = index_i >> 1;
-
-            let (mut opened_rows, opening_proof) = config.mmcs.open_batch(index_pair, commit);
-            assert_eq!(opened_rows.len(), 1);
-            let opened_row = opened_rows.pop().unwrap();
-            assert_eq!(opened_row.len(), 2, "Committed data should be in pairs");
-            let sibling_value = opened_row[index_i_sibling % 2];
-
+            let (sibling_value, opening_proof) = open_sibling(&config.mmcs, commit, index >> i);
             CommitPhaseProofStep {
                 sibling_value,
                 opening_proof,
@@ -85,50 +365,88 @@ where
     }
 }
 
-#[instrument(name = "commit phase", skip_all)]
-fn commit_phase<F, M, Challenger>(
+/// Opens the pair containing `index` in a commit phase codeword, returning the other value in it.
+fn open_sibling<F, M>(mmcs: &M, prover_data: &M::ProverData, index: usize) -> (F, M::Proof)
+where
+    F: Field,
+    M: Mmcs<F>,
+{
+    let (mut opened_rows, opening_proof) = mmcs.open_batch(index >> 1, prover_data);
+    assert_eq!(opened_rows.len(), 1);
+    let opened_row = opened_rows.pop().unwrap();
+    assert_eq!(opened_row.len(), 2, "Committed data should be in pairs");
+    (opened_row[(index ^ 1) % 2], opening_proof)
+}
+
+fn add_codeword<F: Field, EF: ExtensionField<F>>(current: &mut [EF], v: &[F]) {
+    current.iter_mut().zip_eq(v).for_each(|(c, &v)| *c += v);
+}
+
+/// Commits to and folds `scratch.current`, a codeword with `2^log_height` evaluations, adding the
+/// smaller codewords in `input` as their heights are reached. Returns `None` if `cancel` is set
+/// before a round begins.
+fn commit_phase<F, EF, M, Challenger>(
     config: &FriConfig<M>,
     input: &[Option<Vec<F>>; 32],
-    log_max_height: usize,
+    log_height: usize,
     challenger: &mut Challenger,
-) -> CommitPhaseResult<F, M>
+    scratch: &mut FriScratch<EF>,
+    cancel: Option<&AtomicBool>,
+) -> Option<CommitPhaseResult<EF, M>>
 where
-    F: TwoAdicField,
-    M: DirectMmcs<F>,
-    Challenger: CanObserve<M::Commitment> + CanSample<F>,
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: DirectMmcs<EF>,
+    Challenger: CanObserve<M::Commitment> + CanSample<EF>,
 {
-    let mut current = input[log_max_height].as_ref().unwrap().clone();
+    let FriScratch {
+        current,
+        folded,
+        powers,
+    } = scratch;
 
     let mut commits = vec![];
     let mut data = vec![];
 
-    for log_folded_height in (config.log_blowup..log_max_height).rev() {
-        let leaves = RowMajorMatrix::new(current.clone(), 2);
+    for log_folded_height in (config.log_blowup..log_height).rev() {
+        if is_cancelled(cancel) {
+            return None;
+        }
+        // The tree takes ownership of the codeword, and has to keep it until the queries are
+        // answered, so we move it in and fold what was committed rather than copying it.
+        let leaves = RowMajorMatrix::new(mem::take(current), 2);
         let (commit, prover_data) = config.mmcs.commit_matrix(leaves);
         challenger.observe(commit.clone());
+
+        let beta: EF = challenger.sample();
+        let committed = config.mmcs.get_matrices(&prover_data);
+        fold_even_odd_rows_into(&committed[0], beta, powers, folded);
+        drop(committed);
+        mem::swap(current, folded);
         commits.push(commit);
         data.push(prover_data);
 
-        let beta: F = challenger.sample();
-        current = fold_even_odd(current, beta);
-
         if let Some(v) = &input[log_folded_height] {
-            current.iter_mut().zip_eq(v).for_each(|(c, v)| *c += *v);
+            add_codeword(current, v);
         }
     }
 
     // We should be left with `blowup` evaluations of a constant polynomial.
     assert_eq!(current.len(), config.blowup());
     let final_poly = current[0];
-    for x in current {
+    for &x in current.iter() {
         assert_eq!(x, final_poly);
     }
 
-    CommitPhaseResult {
+    Some(CommitPhaseResult {
         commits,
         data,
         final_poly,
-    }
+    })
+}
+
+fn is_cancelled(cancel: Option<&AtomicBool>) -> bool {
+    cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed))
 }
 
 struct CommitPhaseResult<F, M: Mmcs<F>> {
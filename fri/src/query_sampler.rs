@@ -0,0 +1,34 @@
+use alloc::vec::Vec;
+
+use p3_challenger::CanSampleBits;
+
+/// Determines how the indices of FRI queries are drawn.
+///
+/// Soundness relies on indices which are unpredictable to the prover, so anything other than
+/// `ChallengerQuerySampler` should only be used for testing.
+pub trait QuerySampler<Challenger> {
+    /// Returns `num_queries` indices, each less than `2^log_max_height`.
+    fn sample_query_indices(
+        &self,
+        challenger: &mut Challenger,
+        num_queries: usize,
+        log_max_height: usize,
+    ) -> Vec<usize>;
+}
+
+/// The standard query sampler, which draws each index from the challenger.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ChallengerQuerySampler;
+
+impl<Challenger: CanSampleBits<usize>> QuerySampler<Challenger> for ChallengerQuerySampler {
+    fn sample_query_indices(
+        &self,
+        challenger: &mut Challenger,
+        num_queries: usize,
+        log_max_height: usize,
+    ) -> Vec<usize> {
+        (0..num_queries)
+            .map(|_| challenger.sample_bits(log_max_height))
+            .collect()
+    }
+}
@@ -2,20 +2,27 @@ use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::{Debug, Formatter};
 use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, CanSample, FieldChallenger, GrindingChallenger};
-use p3_commit::{DirectMmcs, Mmcs, OpenedValues, Pcs, UnivariatePcs, UnivariatePcsWithLde};
+use p3_commit::{
+    query_index_for_height, DirectMmcs, Mmcs, OpenedValues, Pcs, UnivariatePcs,
+    UnivariatePcsWithLde, LDE_SPAN,
+};
 use p3_dft::TwoAdicSubgroupDft;
 use p3_field::{
-    batch_multiplicative_inverse, cyclic_subgroup_coset_known_order, AbstractField, ExtensionField,
-    Field, PackedValue, TwoAdicField,
+    batch_multiplicative_inverse, cyclic_subgroup_coset_known_order, dot_product, AbstractField,
+    ExtensionField, Field, PackedValue, TwoAdicField,
 };
 use p3_interpolation::interpolate_coset;
 use p3_matrix::bitrev::{BitReversableMatrix, BitReversedMatrixView};
-use p3_matrix::dense::RowMajorMatrixView;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
+use p3_matrix::periodic::PeriodicMatrix;
+use p3_matrix::util::reverse_matrix_index_bits;
 use p3_matrix::{Dimensions, Matrix, MatrixRows};
 use p3_maybe_rayon::prelude::*;
+use p3_util::allocator::{GlobalTraceAllocator, TraceAllocator};
 use p3_util::linear_map::LinearMap;
 use p3_util::{log2_strict_usize, reverse_bits_len, reverse_slice_index_bits, VecExt};
 use serde::{Deserialize, Serialize};
@@ -36,21 +43,39 @@ pub trait TwoAdicFriPcsGenericConfig: Default {
     type InputMmcs: 'static
         + for<'a> DirectMmcs<Self::Val, Mat<'a> = RowMajorMatrixView<'a, Self::Val>>;
     type FriMmcs: DirectMmcs<Self::Challenge>;
+    type Allocator: TraceAllocator;
 }
 
-pub struct TwoAdicFriPcsConfig<Val, Challenge, Challenger, Dft, InputMmcs, FriMmcs>(
-    PhantomData<(Val, Challenge, Challenger, Dft, InputMmcs, FriMmcs)>,
+pub struct TwoAdicFriPcsConfig<
+    Val,
+    Challenge,
+    Challenger,
+    Dft,
+    InputMmcs,
+    FriMmcs,
+    Allocator = GlobalTraceAllocator,
+>(
+    PhantomData<(
+        Val,
+        Challenge,
+        Challenger,
+        Dft,
+        InputMmcs,
+        FriMmcs,
+        Allocator,
+    )>,
 );
-impl<Val, Challenge, Challenger, Dft, InputMmcs, FriMmcs> Default
-    for TwoAdicFriPcsConfig<Val, Challenge, Challenger, Dft, InputMmcs, FriMmcs>
+
+impl<Val, Challenge, Challenger, Dft, InputMmcs, FriMmcs, Allocator> Default
+    for TwoAdicFriPcsConfig<Val, Challenge, Challenger, Dft, InputMmcs, FriMmcs, Allocator>
 {
     fn default() -> Self {
         Self(PhantomData)
     }
 }
 
-impl<Val, Challenge, Challenger, Dft, InputMmcs, FriMmcs> TwoAdicFriPcsGenericConfig
-    for TwoAdicFriPcsConfig<Val, Challenge, Challenger, Dft, InputMmcs, FriMmcs>
+impl<Val, Challenge, Challenger, Dft, InputMmcs, FriMmcs, Allocator> TwoAdicFriPcsGenericConfig
+    for TwoAdicFriPcsConfig<Val, Challenge, Challenger, Dft, InputMmcs, FriMmcs, Allocator>
 where
     Val: TwoAdicField,
     Challenge: TwoAdicField + ExtensionField<Val>,
@@ -61,6 +86,7 @@ where
     Dft: TwoAdicSubgroupDft<Val>,
     InputMmcs: 'static + for<'a> DirectMmcs<Val, Mat<'a> = RowMajorMatrixView<'a, Val>>,
     FriMmcs: DirectMmcs<Challenge>,
+    Allocator: TraceAllocator,
 {
     type Val = Val;
     type Challenge = Challenge;
@@ -68,21 +94,143 @@ where
     type Dft = Dft;
     type InputMmcs = InputMmcs;
     type FriMmcs = FriMmcs;
+    type Allocator = Allocator;
 }
 
 pub struct TwoAdicFriPcs<C: TwoAdicFriPcsGenericConfig> {
     fri: FriConfig<C::FriMmcs>,
     dft: C::Dft,
     mmcs: C::InputMmcs,
+    allocator: C::Allocator,
 }
 
 impl<C: TwoAdicFriPcsGenericConfig> TwoAdicFriPcs<C> {
-    pub fn new(fri: FriConfig<C::FriMmcs>, dft: C::Dft, mmcs: C::InputMmcs) -> Self {
-        Self { fri, dft, mmcs }
+    /// The input MMCS may pack several consecutive rows into each leaf, as
+    /// `FieldMerkleTreeMmcs::with_rows_per_leaf` does, to shorten its authentication paths. The
+    /// FRI MMCS in `fri` must not, since each commit phase query opens a single pair.
+    pub fn new(fri: FriConfig<C::FriMmcs>, dft: C::Dft, mmcs: C::InputMmcs) -> Self
+    where
+        C::Allocator: Default,
+    {
+        Self::new_with_allocator(fri, dft, mmcs, C::Allocator::default())
+    }
+
+    /// Like `new`, but with the LDE buffers taken from `allocator`.
+    pub fn new_with_allocator(
+        fri: FriConfig<C::FriMmcs>,
+        dft: C::Dft,
+        mmcs: C::InputMmcs,
+        allocator: C::Allocator,
+    ) -> Self {
+        Self {
+            fri,
+            dft,
+            mmcs,
+            allocator,
+        }
+    }
+
+    /// Like `commit_batch`, but for a single matrix given as its columns, each of which must have
+    /// the same power-of-two length.
+    pub fn commit_col_major(
+        &self,
+        cols: Vec<Vec<C::Val>>,
+    ) -> (
+        <C::InputMmcs as Mmcs<C::Val>>::Commitment,
+        <C::InputMmcs as Mmcs<C::Val>>::ProverData,
+    )
+    where
+        C::Dft: Sync,
+    {
+        self.commit_shifted_col_major(cols, C::Val::one())
+    }
+
+    /// Like `commit_shifted_batch`, but for a single matrix given as its columns.
+    ///
+    /// The columns' LDEs are computed independently, in parallel, and the MMCS builds its leaves
+    /// straight from them with `commit_col_major`, so the input is never transposed.
+    pub fn commit_shifted_col_major(
+        &self,
+        cols: Vec<Vec<C::Val>>,
+        coset_shift: C::Val,
+    ) -> (
+        <C::InputMmcs as Mmcs<C::Val>>::Commitment,
+        <C::InputMmcs as Mmcs<C::Val>>::ProverData,
+    )
+    where
+        C::Dft: Sync,
+    {
+        let ldes = info_span!(LDE_SPAN).in_scope(|| {
+            let shift = C::Val::generator() / coset_shift;
+            cols.into_par_iter()
+                .map(|col| {
+                    let mut lde = self.dft.coset_lde(col, self.fri.log_blowup, shift);
+                    // Commit to the bit-reversed LDE, as in `commit_shifted_batches`.
+                    reverse_slice_index_bits(&mut lde);
+                    lde
+                })
+                .collect()
+        });
+        self.mmcs.commit_col_major(ldes)
+    }
+
+    /// Like `commit_batch`, but for a single periodic matrix.
+    pub fn commit_periodic(
+        &self,
+        matrix: PeriodicMatrix<C::Val>,
+    ) -> (
+        <C::InputMmcs as Mmcs<C::Val>>::Commitment,
+        <C::InputMmcs as Mmcs<C::Val>>::ProverData,
+    ) {
+        self.commit_shifted_periodic(matrix, C::Val::one())
+    }
+
+    /// Like `commit_shifted_batch`, but for a single periodic matrix.
+    ///
+    /// If the matrix's period `p` is a power of two dividing its height `n`, then each column is a
+    /// polynomial in `x^(n / p)`, so its LDE repeats every `p << log_blowup` points. Only that much
+    /// of it is computed, from the first `p` rows, and then tiled, so the full trace is never
+    /// expanded. Otherwise this falls back to `commit_shifted_batches`.
+    pub fn commit_shifted_periodic(
+        &self,
+        matrix: PeriodicMatrix<C::Val>,
+        coset_shift: C::Val,
+    ) -> (
+        <C::InputMmcs as Mmcs<C::Val>>::Commitment,
+        <C::InputMmcs as Mmcs<C::Val>>::ProverData,
+    ) {
+        let height = matrix.height();
+        let period = matrix.period();
+        if !period.is_power_of_two() || height % period != 0 {
+            return self.commit_shifted_batches(vec![matrix], &[coset_shift]);
+        }
+        let lde = info_span!(LDE_SPAN).in_scope(|| {
+            let repeats = height / period;
+            let shift = C::Val::generator() / coset_shift;
+            let period_lde = self
+                .dft
+                .coset_lde_batch(
+                    matrix.first_period().to_row_major_matrix(),
+                    self.fri.log_blowup,
+                    shift.exp_u64(repeats as u64),
+                )
+                .to_row_major_matrix();
+            let mut values = self.allocator.allocate(period_lde.values.len() * repeats);
+            for _ in 0..repeats {
+                values.extend_from_slice(&period_lde.values);
+            }
+            let mut lde = RowMajorMatrix::new(values, matrix.width());
+            // Commit to the bit-reversed LDE, as in `commit_shifted_batches`.
+            reverse_matrix_index_bits(&mut lde);
+            lde
+        });
+        self.mmcs.commit_matrix(lde)
     }
 }
 
 pub enum VerificationError<C: TwoAdicFriPcsGenericConfig> {
+    /// The claimed values don't match the shape of the points and dimensions being opened.
+    InvalidOpenedValuesShape,
     InputMmcsError(<C::InputMmcs as Mmcs<C::Val>>::Error),
     FriError(FriError<<C::FriMmcs as Mmcs<C::Challenge>>::Error>),
 }
@@ -90,6 +238,7 @@ pub enum VerificationError<C: TwoAdicFriPcsGenericConfig> {
 impl<C: TwoAdicFriPcsGenericConfig> Debug for VerificationError<C> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
+            VerificationError::InvalidOpenedValuesShape => f.write_str("InvalidOpenedValuesShape"),
             VerificationError::InputMmcsError(e) => {
                 f.debug_tuple("InputMmcsError").field(e).finish()
             }
@@ -128,6 +277,11 @@ impl<C: TwoAdicFriPcsGenericConfig, In: MatrixRows<C::Val>>
     UnivariatePcsWithLde<C::Val, C::Challenge, In, C::Challenger> for TwoAdicFriPcs<C>
 {
     type Lde<'a> = BitReversedMatrixView<<C::InputMmcs as Mmcs<C::Val>>::Mat<'a>> where Self: 'a;
+    type Allocator = C::Allocator;
+
+    fn allocator(&self) -> &Self::Allocator {
+        &self.allocator
+    }
 
     fn coset_shift(&self) -> C::Val {
         C::Val::generator()
@@ -154,34 +308,73 @@ impl<C: TwoAdicFriPcsGenericConfig, In: MatrixRows<C::Val>>
         polynomials: Vec<In>,
         coset_shifts: &[C::Val],
     ) -> (Self::Commitment, Self::ProverData) {
-        let ldes = info_span!("compute all coset LDEs").in_scope(|| {
+        self.commit_shifted_batches_cancellable(polynomials, coset_shifts, &AtomicBool::new(false))
+            .expect("committing without a cancellation flag cannot be cancelled")
+    }
+
+    /// Checks `cancel` before each LDE, and before hashing them.
+    fn commit_shifted_batches_cancellable(
+        &self,
+        polynomials: Vec<In>,
+        coset_shifts: &[C::Val],
+        cancel: &AtomicBool,
+    ) -> Option<(Self::Commitment, Self::ProverData)> {
+        let ldes = info_span!(LDE_SPAN).in_scope(|| {
             polynomials
                 .into_iter()
                 .zip_eq(coset_shifts)
                 .map(|(poly, coset_shift)| {
+                    if cancel.load(Ordering::Relaxed) {
+                        return None;
+                    }
                     let shift = C::Val::generator() / *coset_shift;
-                    let input = poly.to_row_major_matrix();
                     // Commit to the bit-reversed LDE.
-                    self.dft
-                        .coset_lde_batch(input, self.fri.log_blowup, shift)
+                    let lde = self
+                        .dft
+                        .coset_lde_batch_with_allocator(
+                            poly.to_row_major_matrix(),
+                            self.fri.log_blowup,
+                            shift,
+                            &self.allocator,
+                        )
                         .bit_reverse_rows()
-                        .to_row_major_matrix()
+                        .to_row_major_matrix();
+                    Some(lde)
                 })
-                .collect()
-        });
-        self.mmcs.commit(ldes)
+                .collect::<Option<Vec<_>>>()
+        })?;
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        Some(self.mmcs.commit(ldes))
     }
 }
 
 impl<C: TwoAdicFriPcsGenericConfig, In: MatrixRows<C::Val>>
     UnivariatePcs<C::Val, C::Challenge, In, C::Challenger> for TwoAdicFriPcs<C>
 {
-    #[instrument(name = "open_multi_batches", skip_all)]
     fn open_multi_batches(
         &self,
         prover_data_and_points: &[(&Self::ProverData, &[Vec<C::Challenge>])],
         challenger: &mut C::Challenger,
     ) -> (OpenedValues<C::Challenge>, Self::Proof) {
+        self.open_multi_batches_cancellable(
+            prover_data_and_points,
+            challenger,
+            &AtomicBool::new(false),
+        )
+        .expect("opening without a cancellation flag cannot be cancelled")
+    }
+
+    /// Checks `cancel` before reducing each matrix at each of its points, and before each round of
+    /// FRI folding.
+    #[instrument(name = "open_multi_batches", skip_all)]
+    fn open_multi_batches_cancellable(
+        &self,
+        prover_data_and_points: &[(&Self::ProverData, &[Vec<C::Challenge>])],
+        challenger: &mut C::Challenger,
+        cancel: &AtomicBool,
+    ) -> Option<(OpenedValues<C::Challenge>, Self::Proof)> {
         // Batch combination challenge
         let alpha = <C::Challenger as CanSample<C::Challenge>>::sample(challenger);
 
@@ -255,6 +448,9 @@ impl<C: TwoAdicFriPcsGenericConfig, In: MatrixRows<C::Val>>
 
                 let opened_values_for_mat = opened_values_for_round.pushed_mut(vec![]);
                 for &point in points_for_mat {
+                    if cancel.load(Ordering::Relaxed) {
+                        return None;
+                    }
                     let _guard =
                         info_span!("reduce matrix quotient", dims = %mat.dimensions()).entered();
 
@@ -294,7 +490,8 @@ impl<C: TwoAdicFriPcsGenericConfig, In: MatrixRows<C::Val>>
             }
         }
 
-        let (fri_proof, query_indices) = prover::prove(&self.fri, &reduced_openings, challenger);
+        let (fri_proof, query_indices) =
+            prover::prove_cancellable(&self.fri, &reduced_openings, challenger, cancel)?;
 
         let query_openings = query_indices
             .into_iter()
@@ -312,13 +509,13 @@ impl<C: TwoAdicFriPcsGenericConfig, In: MatrixRows<C::Val>>
             })
             .collect();
 
-        (
+        Some((
             all_opened_values,
             TwoAdicFriPcsProof {
                 fri_proof,
                 query_openings,
             },
-        )
+        ))
     }
 
     fn verify_multi_batches(
@@ -329,6 +526,26 @@ impl<C: TwoAdicFriPcsGenericConfig, In: MatrixRows<C::Val>>
         proof: &Self::Proof,
         challenger: &mut C::Challenger,
     ) -> Result<(), Self::Error> {
+        // Each matrix must have a value per column for each of its own points, or the checks below
+        // would silently skip some of them.
+        let valid_shape = values.len() == commits_and_points.len()
+            && dims.len() == commits_and_points.len()
+            && izip!(commits_and_points, dims, &values).all(
+                |((_, batch_points), batch_dims, batch_at_z)| {
+                    batch_points.len() == batch_dims.len()
+                        && batch_at_z.len() == batch_dims.len()
+                        && izip!(*batch_points, batch_dims, batch_at_z).all(
+                            |(mat_points, mat_dims, mat_at_z)| {
+                                mat_at_z.len() == mat_points.len()
+                                    && mat_at_z.iter().all(|ps| ps.len() == mat_dims.width)
+                            },
+                        )
+                },
+            );
+        if !valid_shape {
+            return Err(VerificationError::InvalidOpenedValuesShape);
+        }
+
         // Batch combination challenge
         let alpha = <C::Challenger as CanSample<C::Challenge>>::sample(challenger);
 
@@ -348,13 +565,23 @@ impl<C: TwoAdicFriPcsGenericConfig, In: MatrixRows<C::Val>>
                 for (batch_opening, batch_dims, (batch_commit, batch_points), batch_at_z) in
                     izip!(query_opening, dims, commits_and_points, &values)
                 {
-                    self.mmcs.verify_batch(
-                        batch_commit,
-                        batch_dims,
-                        index,
-                        &batch_opening.opened_values,
-                        &batch_opening.opening_proof,
-                    )?;
+                    // The committed matrices are the LDEs, so they're taller by the blowup factor.
+                    let lde_dims = batch_dims
+                        .iter()
+                        .map(|dims| Dimensions {
+                            width: dims.width,
+                            height: dims.height << self.fri.log_blowup,
+                        })
+                        .collect_vec();
+                    self.mmcs
+                        .verify_batch(
+                            batch_commit,
+                            &lde_dims,
+                            index,
+                            &batch_opening.opened_values,
+                            &batch_opening.opening_proof,
+                        )
+                        .map_err(VerificationError::InputMmcsError)?;
                     for (mat_opening, mat_dims, mat_points, mat_at_z) in izip!(
                         &batch_opening.opened_values,
                         batch_dims,
@@ -363,15 +590,30 @@ impl<C: TwoAdicFriPcsGenericConfig, In: MatrixRows<C::Val>>
                     ) {
                         let log_height = log2_strict_usize(mat_dims.height) + self.fri.log_blowup;
 
-                        let bits_reduced = log_max_height - log_height;
-                        let rev_reduced_index = reverse_bits_len(index >> bits_reduced, log_height);
+                        let reduced_index =
+                            query_index_for_height(index, log_height, log_max_height);
+                        let rev_reduced_index = reverse_bits_len(reduced_index, log_height);
 
                         let x = C::Val::generator()
                             * C::Val::two_adic_generator(log_height)
                                 .exp_u64(rev_reduced_index as u64);
 
+                        // The MMCS may pack several consecutive rows into each leaf, in which case
+                        // it opens all of them, and we pick out the queried one.
+                        let width = mat_dims.width;
+                        let row_at_x = if width == 0 {
+                            &mat_opening[..]
+                        } else {
+                            let rows_per_leaf = mat_opening.len() / width;
+                            if rows_per_leaf == 0 || mat_opening.len() % width != 0 {
+                                return Err(VerificationError::InvalidOpenedValuesShape);
+                            }
+                            let row_in_leaf = reduced_index % rows_per_leaf;
+                            &mat_opening[row_in_leaf * width..(row_in_leaf + 1) * width]
+                        };
+
                         for (&z, ps_at_z) in izip!(mat_points, mat_at_z) {
-                            for (&p_at_x, &p_at_z) in izip!(mat_opening, ps_at_z) {
+                            for (&p_at_x, &p_at_z) in izip!(row_at_x, ps_at_z) {
                                 let quotient = (-p_at_z + p_at_x) / (-z + x);
                                 ro[log_height] += alpha_pow[log_height] * quotient;
                                 alpha_pow[log_height] *= alpha;
@@ -381,8 +623,7 @@ impl<C: TwoAdicFriPcsGenericConfig, In: MatrixRows<C::Val>>
                 }
                 Ok(ro)
             })
-            .collect::<Result<Vec<_>, <C::InputMmcs as Mmcs<C::Val>>::Error>>()
-            .map_err(VerificationError::InputMmcsError)?;
+            .collect::<Result<Vec<_>, _>>()?;
 
         verifier::verify_challenges(
             &self.fri,
@@ -474,7 +715,7 @@ impl<F: Field, EF: ExtensionField<F>> PowersReducer<F, EF> {
 
     // Compute sum_i base^i * x_i
     fn reduce_ext(&self, xs: &[EF]) -> EF {
-        self.powers.iter().zip(xs).map(|(&pow, &x)| pow * x).sum()
+        dot_product(&self.powers[..xs.len()], xs)
     }
 
     // Same as `self.powers.iter().zip(xs).map(|(&pow, &x)| pow * x).sum()`
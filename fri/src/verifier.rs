@@ -1,14 +1,13 @@
-use alloc::vec;
 use alloc::vec::Vec;
 
 use itertools::izip;
 use p3_challenger::{CanObserve, CanSample, GrindingChallenger};
 use p3_commit::Mmcs;
-use p3_field::{Field, TwoAdicField};
+use p3_field::{ExtensionField, Field, TwoAdicField};
 use p3_matrix::Dimensions;
 use p3_util::reverse_bits_len;
 
-use crate::{FriConfig, FriProof, QueryProof};
+use crate::{ChallengerQuerySampler, FriConfig, FriProof, QueryProof, QuerySampler};
 
 #[derive(Debug)]
 pub enum FriError<CommitMmcsErr> {
@@ -33,6 +32,28 @@ where
     F: Field,
     M: Mmcs<F>,
     Challenger: GrindingChallenger + CanObserve<M::Commitment> + CanSample<F>,
+{
+    verify_shape_and_sample_challenges_with_sampler(
+        config,
+        proof,
+        challenger,
+        &ChallengerQuerySampler,
+    )
+}
+
+/// Like `verify_shape_and_sample_challenges`, but with query indices drawn by the given
+/// `QuerySampler`, which must match the one used by the prover.
+pub fn verify_shape_and_sample_challenges_with_sampler<F, M, Challenger, S>(
+    config: &FriConfig<M>,
+    proof: &FriProof<F, M, Challenger::Witness>,
+    challenger: &mut Challenger,
+    query_sampler: &S,
+) -> Result<FriChallenges<F>, FriError<M::Error>>
+where
+    F: Field,
+    M: Mmcs<F>,
+    Challenger: GrindingChallenger + CanObserve<M::Commitment> + CanSample<F>,
+    S: QuerySampler<Challenger>,
 {
     let betas: Vec<F> = proof
         .commit_phase_commits
@@ -54,9 +75,8 @@ where
 
     let log_max_height = proof.commit_phase_commits.len() + config.log_blowup;
 
-    let query_indices: Vec<usize> = (0..config.num_queries)
-        .map(|_| challenger.sample_bits(log_max_height))
-        .collect();
+    let query_indices =
+        query_sampler.sample_query_indices(challenger, config.num_queries, log_max_height);
 
     Ok(FriChallenges {
         query_indices,
@@ -73,6 +93,81 @@ pub fn verify_challenges<F, M, Witness>(
 where
     F: TwoAdicField,
     M: Mmcs<F>,
+{
+    verify_queries(
+        config,
+        proof,
+        challenges,
+        reduced_openings,
+        |_, commit, dims, index_pair, evals, opening_proof| {
+            config
+                .mmcs
+                .verify_batch(commit, dims, index_pair, &[evals.to_vec()], opening_proof)
+                .map_err(FriError::CommitPhaseMmcsError)
+        },
+    )
+}
+
+/// Like `verify_challenges`, but for a proof from `prover::prove_base_field`, whose reduced
+/// openings are in the subfield `F` and whose first commit phase codeword was committed with
+/// `base_mmcs`.
+pub fn verify_challenges_base_field<F, EF, M, BaseMmcs, Witness>(
+    config: &FriConfig<M>,
+    base_mmcs: &BaseMmcs,
+    proof: &FriProof<EF, M, Witness>,
+    challenges: &FriChallenges<EF>,
+    reduced_openings: &[[F; 32]],
+) -> Result<(), FriError<M::Error>>
+where
+    F: TwoAdicField,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    BaseMmcs: Mmcs<F, Commitment = M::Commitment, Proof = M::Proof, Error = M::Error>,
+{
+    verify_queries(
+        config,
+        proof,
+        challenges,
+        reduced_openings,
+        |layer, commit, dims, index_pair, evals, opening_proof| {
+            if layer > 0 {
+                return config
+                    .mmcs
+                    .verify_batch(commit, dims, index_pair, &[evals.to_vec()], opening_proof)
+                    .map_err(FriError::CommitPhaseMmcsError);
+            }
+            // The first codeword was committed in `F`, so its values must lie there.
+            let base_evals = evals
+                .iter()
+                .map(|eval| eval.as_base().ok_or(FriError::InvalidProofShape))
+                .collect::<Result<Vec<_>, _>>()?;
+            base_mmcs
+                .verify_batch(commit, dims, index_pair, &[base_evals], opening_proof)
+                .map_err(FriError::CommitPhaseMmcsError)
+        },
+    )
+}
+
+/// Checks each query, with `verify_layer` checking the opening of the pair of each commit phase
+/// codeword, given the index of the codeword.
+fn verify_queries<F, EF, M, Witness>(
+    config: &FriConfig<M>,
+    proof: &FriProof<EF, M, Witness>,
+    challenges: &FriChallenges<EF>,
+    reduced_openings: &[[F; 32]],
+    verify_layer: impl Fn(
+        usize,
+        &M::Commitment,
+        &[Dimensions],
+        usize,
+        &[EF; 2],
+        &M::Proof,
+    ) -> Result<(), FriError<M::Error>>,
+) -> Result<(), FriError<M::Error>>
+where
+    F: TwoAdicField,
+    EF: ExtensionField<F>,
+    M: Mmcs<EF>,
 {
     let log_max_height = proof.commit_phase_commits.len() + config.log_blowup;
     for (&index, query_proof, ro) in izip!(
@@ -88,6 +183,7 @@ where
             &challenges.betas,
             ro,
             log_max_height,
+            &verify_layer,
         )?;
 
         if folded_eval != proof.final_poly {
@@ -98,56 +194,59 @@ where
     Ok(())
 }
 
-fn verify_query<F, M>(
+#[allow(clippy::too_many_arguments)]
+fn verify_query<F, EF, M>(
     config: &FriConfig<M>,
     commit_phase_commits: &[M::Commitment],
     mut index: usize,
-    proof: &QueryProof<F, M>,
-    betas: &[F],
+    proof: &QueryProof<EF, M>,
+    betas: &[EF],
     reduced_openings: &[F; 32],
     log_max_height: usize,
-) -> Result<F, FriError<M::Error>>
+    verify_layer: &impl Fn(
+        usize,
+        &M::Commitment,
+        &[Dimensions],
+        usize,
+        &[EF; 2],
+        &M::Proof,
+    ) -> Result<(), FriError<M::Error>>,
+) -> Result<EF, FriError<M::Error>>
 where
     F: TwoAdicField,
-    M: Mmcs<F>,
+    EF: ExtensionField<F>,
+    M: Mmcs<EF>,
 {
-    let mut folded_eval = F::zero();
+    let mut folded_eval = EF::zero();
     let mut x = F::two_adic_generator(log_max_height)
         .exp_u64(reverse_bits_len(index, log_max_height) as u64);
 
-    for (log_folded_height, commit, step, &beta) in izip!(
+    for (layer, (log_folded_height, commit, step, &beta)) in izip!(
         (0..log_max_height).rev(),
         commit_phase_commits,
         &proof.commit_phase_openings,
         betas,
-    ) {
+    )
+    .enumerate()
+    {
         folded_eval += reduced_openings[log_folded_height + 1];
 
         let index_sibling = index ^ 1;
         let index_pair = index >> 1;
 
-        let mut evals = vec![folded_eval; 2];
+        let mut evals = [folded_eval; 2];
         evals[index_sibling % 2] = step.sibling_value;
 
         let dims = &[Dimensions {
             width: 2,
             height: (1 << log_folded_height),
         }];
-        config
-            .mmcs
-            .verify_batch(
-                commit,
-                dims,
-                index_pair,
-                &[evals.clone()],
-                &step.opening_proof,
-            )
-            .map_err(FriError::CommitPhaseMmcsError)?;
+        verify_layer(layer, commit, dims, index_pair, &evals, &step.opening_proof)?;
 
         let mut xs = [x; 2];
         xs[index_sibling % 2] *= F::two_adic_generator(1);
         // interpolate and evaluate at beta
-        folded_eval = evals[0] + (beta - xs[0]) * (evals[1] - evals[0]) / (xs[1] - xs[0]);
+        folded_eval = evals[0] + (evals[1] - evals[0]) * (beta - xs[0]) * (xs[1] - xs[0]).inverse();
 
         index = index_pair;
         x = x.square();
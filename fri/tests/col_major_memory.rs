@@ -0,0 +1,78 @@
+//! Compares the peak memory of committing to a column-major trace directly against transposing
+//! it first. This is its own test binary, with one test, so that nothing else allocates while the
+//! peak is being measured.
+
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use p3_commit::Pcs;
+use p3_matrix::dense::RowMajorMatrix;
+use rand::{thread_rng, Rng};
+
+use crate::common::{pcs, random_perm, MyPcs, Val};
+
+struct PeakTracker;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakTracker {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(current, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakTracker = PeakTracker;
+
+/// Run `f`, returning its result and how many bytes beyond those already allocated it held at
+/// its peak.
+fn peak_bytes<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let baseline = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+    let result = f();
+    (result, PEAK.load(Ordering::SeqCst) - baseline)
+}
+
+#[test]
+fn test_col_major_commit_uses_less_memory() {
+    let mut rng = thread_rng();
+    let pcs = pcs(&random_perm());
+
+    let (height, width) = (1 << 14, 32);
+    let cols: Vec<Vec<Val>> = (0..width)
+        .map(|_| (0..height).map(|_| rng.gen()).collect())
+        .collect();
+
+    let ((transposed_commit, transposed_data), transposed_peak) = peak_bytes(|| {
+        let cols = cols.clone();
+        let transposed = RowMajorMatrix::new(
+            (0..height)
+                .flat_map(|r| cols.iter().map(move |col| col[r]))
+                .collect(),
+            width,
+        );
+        drop(cols);
+        <MyPcs as Pcs<Val, RowMajorMatrix<Val>>>::commit_batch(&pcs, transposed)
+    });
+    drop(transposed_data);
+
+    let ((col_major_commit, col_major_data), col_major_peak) =
+        peak_bytes(|| pcs.commit_col_major(cols.clone()));
+    drop(col_major_data);
+
+    assert_eq!(transposed_commit, col_major_commit);
+    assert!(col_major_peak < transposed_peak);
+}
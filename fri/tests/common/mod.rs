@@ -0,0 +1,51 @@
+//! The PCS configuration shared by these tests. Each test file compiles this module separately
+//! and uses only some of it.
+#![allow(dead_code)]
+
+use p3_baby_bear::BabyBear;
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::Field;
+use p3_fri::{FriConfig, TwoAdicFriPcs, TwoAdicFriPcsConfig};
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{DiffusionMatrixBabybear, Poseidon2};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use rand::thread_rng;
+
+pub type Val = BabyBear;
+pub type Challenge = BinomialExtensionField<Val, 4>;
+pub type Perm = Poseidon2<Val, DiffusionMatrixBabybear, 16, 7>;
+pub type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+pub type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+pub type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+pub type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+pub type Dft = Radix2DitParallel;
+pub type Challenger = DuplexChallenger<Val, Perm, 16>;
+pub type MyPcs =
+    TwoAdicFriPcs<TwoAdicFriPcsConfig<Val, Challenge, Challenger, Dft, ValMmcs, ChallengeMmcs>>;
+
+pub fn random_perm() -> Perm {
+    Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng())
+}
+
+pub fn pcs(perm: &Perm) -> MyPcs {
+    pcs_with_rows_per_leaf(perm, 1)
+}
+
+/// Like `pcs`, but with the input MMCS packing `rows_per_leaf` rows into each leaf. FRI's own
+/// commitments keep one row per leaf.
+pub fn pcs_with_rows_per_leaf(perm: &Perm, rows_per_leaf: usize) -> MyPcs {
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::with_rows_per_leaf(hash.clone(), compress.clone(), rows_per_leaf);
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 10,
+        proof_of_work_bits: 8,
+        mmcs: ChallengeMmcs::new(ValMmcs::new(hash, compress)),
+    };
+    MyPcs::new(fri_config, Dft {}, val_mmcs)
+}
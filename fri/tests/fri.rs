@@ -1,3 +1,5 @@
+use core::ops::Range;
+
 use itertools::Itertools;
 use p3_baby_bear::BabyBear;
 use p3_challenger::{CanSampleBits, DuplexChallenger, FieldChallenger};
@@ -5,7 +7,8 @@ use p3_commit::ExtensionMmcs;
 use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
 use p3_field::extension::BinomialExtensionField;
 use p3_field::{AbstractField, Field};
-use p3_fri::{prover, verifier, FriConfig};
+use p3_fri::prover::FriScratch;
+use p3_fri::{prover, verifier, ChallengerQuerySampler, FriConfig, QuerySampler};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::util::reverse_matrix_index_bits;
 use p3_matrix::{Matrix, MatrixRows};
@@ -42,48 +45,55 @@ fn get_ldt_for_testing<R: Rng>(rng: &mut R) -> (Perm, MyFriConfig) {
     (perm, fri_config)
 }
 
-fn do_test_fri_ldt<R: Rng>(rng: &mut R) {
-    let (perm, fc) = get_ldt_for_testing(rng);
+/// Bit-reversed LDEs of random matrices, one of each degree in `deg_bits`.
+fn random_ldes<R: Rng>(rng: &mut R, deg_bits: Range<usize>) -> Vec<RowMajorMatrix<Val>> {
     let dft = Radix2Dit::default();
-
     let shift = Val::generator();
-
-    let ldes: Vec<RowMajorMatrix<Val>> = (3..10)
+    deg_bits
         .map(|deg_bits| {
             let evals = RowMajorMatrix::<Val>::rand_nonzero(rng, 1 << deg_bits, 16);
             let mut lde = dft.coset_lde_batch(evals, 1, shift);
             reverse_matrix_index_bits(&mut lde);
             lde
         })
-        .collect();
+        .collect()
+}
+
+/// The FRI input for `ldes`, with each height's columns combined using powers of `alpha`.
+fn reduce_ldes(ldes: &[RowMajorMatrix<Val>], alpha: Challenge) -> [Option<Vec<Challenge>>; 32] {
+    core::array::from_fn(|log_height| {
+        let matrices_with_log_height: Vec<&RowMajorMatrix<Val>> = ldes
+            .iter()
+            .filter(|m| log2_strict_usize(m.height()) == log_height)
+            .collect();
+        if matrices_with_log_height.is_empty() {
+            None
+        } else {
+            let reduced: Vec<Challenge> = (0..(1 << log_height))
+                .map(|r| {
+                    alpha
+                        .powers()
+                        .zip(matrices_with_log_height.iter().flat_map(|m| m.row(r)))
+                        .map(|(alpha_pow, v)| alpha_pow * v)
+                        .sum()
+                })
+                .collect();
+            Some(reduced)
+        }
+    })
+}
+
+fn do_test_fri_ldt<R: Rng, S: QuerySampler<Challenger>>(rng: &mut R, query_sampler: &S) {
+    let (perm, fc) = get_ldt_for_testing(rng);
+    let ldes = random_ldes(rng, 3..10);
 
     let (proof, reduced_openings, p_sample) = {
         // Prover world
         let mut chal = Challenger::new(perm.clone());
         let alpha: Challenge = chal.sample_ext_element();
+        let input = reduce_ldes(&ldes, alpha);
 
-        let input: [_; 32] = core::array::from_fn(|log_height| {
-            let matrices_with_log_height: Vec<&RowMajorMatrix<Val>> = ldes
-                .iter()
-                .filter(|m| log2_strict_usize(m.height()) == log_height)
-                .collect();
-            if matrices_with_log_height.is_empty() {
-                None
-            } else {
-                let reduced: Vec<Challenge> = (0..(1 << log_height))
-                    .map(|r| {
-                        alpha
-                            .powers()
-                            .zip(matrices_with_log_height.iter().flat_map(|m| m.row(r)))
-                            .map(|(alpha_pow, v)| alpha_pow * v)
-                            .sum()
-                    })
-                    .collect();
-                Some(reduced)
-            }
-        });
-
-        let (proof, idxs) = prover::prove(&fc, &input, &mut chal);
+        let (proof, idxs) = prover::prove_with_sampler(&fc, &input, &mut chal, query_sampler);
 
         let log_max_height = input.iter().rposition(Option::is_some).unwrap();
         let reduced_openings: Vec<[Challenge; 32]> = idxs
@@ -110,9 +120,13 @@ fn do_test_fri_ldt<R: Rng>(rng: &mut R) {
 
     let mut v_challenger = Challenger::new(perm);
     let _alpha: Challenge = v_challenger.sample_ext_element();
-    let fri_challenges =
-        verifier::verify_shape_and_sample_challenges(&fc, &proof, &mut v_challenger)
-            .expect("failed verify shape and sample");
+    let fri_challenges = verifier::verify_shape_and_sample_challenges_with_sampler(
+        &fc,
+        &proof,
+        &mut v_challenger,
+        query_sampler,
+    )
+    .expect("failed verify shape and sample");
     verifier::verify_challenges(&fc, &proof, &fri_challenges, &reduced_openings)
         .expect("failed verify challenges");
 
@@ -128,6 +142,118 @@ fn test_fri_ldt() {
     // FRI is kind of flaky depending on indexing luck
     for i in 0..4 {
         let mut rng = ChaCha20Rng::seed_from_u64(i);
-        do_test_fri_ldt(&mut rng);
+        do_test_fri_ldt(&mut rng, &ChallengerQuerySampler);
+    }
+}
+
+#[test]
+fn test_fri_ldt_base_field() {
+    for i in 0..4 {
+        let mut rng = ChaCha20Rng::seed_from_u64(i);
+        let (perm, fc) = get_ldt_for_testing(&mut rng);
+        let base_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+
+        // The first column of each LDE, left in the base field.
+        let input: [Option<Vec<Val>>; 32] = {
+            let mut input: [Option<Vec<Val>>; 32] = Default::default();
+            for lde in random_ldes(&mut rng, 3..10) {
+                input[log2_strict_usize(lde.height())] =
+                    Some(lde.rows().map(|row| row[0]).collect());
+            }
+            input
+        };
+
+        let mut p_challenger = Challenger::new(perm.clone());
+        let (proof, idxs) = prover::prove_base_field::<_, Challenge, _, _, _>(
+            &fc,
+            &base_mmcs,
+            &input,
+            &mut p_challenger,
+        );
+
+        let log_max_height = input.iter().rposition(Option::is_some).unwrap();
+        let reduced_openings: Vec<[Val; 32]> = idxs
+            .into_iter()
+            .map(|idx| {
+                core::array::from_fn(|log_height| {
+                    input[log_height]
+                        .as_ref()
+                        .map_or(Val::zero(), |v| v[idx >> (log_max_height - log_height)])
+                })
+            })
+            .collect();
+
+        let mut v_challenger = Challenger::new(perm);
+        let fri_challenges =
+            verifier::verify_shape_and_sample_challenges(&fc, &proof, &mut v_challenger)
+                .expect("failed verify shape and sample");
+        verifier::verify_challenges_base_field(
+            &fc,
+            &base_mmcs,
+            &proof,
+            &fri_challenges,
+            &reduced_openings,
+        )
+        .expect("failed verify challenges");
+
+        assert_eq!(
+            p_challenger.sample_bits(8),
+            v_challenger.sample_bits(8),
+            "prover and verifier transcript have same state after FRI"
+        );
+    }
+}
+
+/// Always queries the same indices, wrapped to fit the domain.
+struct FixedQuerySampler(Vec<usize>);
+
+impl QuerySampler<Challenger> for FixedQuerySampler {
+    fn sample_query_indices(
+        &self,
+        _challenger: &mut Challenger,
+        num_queries: usize,
+        log_max_height: usize,
+    ) -> Vec<usize> {
+        self.0
+            .iter()
+            .cycle()
+            .take(num_queries)
+            .map(|&index| index % (1 << log_max_height))
+            .collect()
+    }
+}
+
+#[test]
+fn test_fri_ldt_fixed_queries() {
+    let query_sampler = FixedQuerySampler(vec![0, 1, 7, 255, 1023]);
+    for i in 0..4 {
+        let mut rng = ChaCha20Rng::seed_from_u64(i);
+        do_test_fri_ldt(&mut rng, &query_sampler);
+    }
+}
+
+#[test]
+fn test_fri_scratch_reuse() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, fc) = get_ldt_for_testing(&mut rng);
+    let mut scratch = FriScratch::new();
+
+    // Shrink the input between proofs too, so the scratch is sometimes bigger than needed.
+    for max_deg_bits in [6, 10, 4, 8] {
+        let ldes = random_ldes(&mut rng, 3..max_deg_bits);
+        let mut chal = Challenger::new(perm.clone());
+        let alpha: Challenge = chal.sample_ext_element();
+        let input = reduce_ldes(&ldes, alpha);
+        let mut scratch_chal = chal.clone();
+
+        let (proof, idxs) = prover::prove(&fc, &input, &mut chal);
+        let (scratch_proof, scratch_idxs) =
+            prover::prove_with_scratch(&fc, &input, &mut scratch_chal, &mut scratch);
+
+        assert_eq!(idxs, scratch_idxs);
+        assert_eq!(
+            postcard::to_allocvec(&proof).unwrap(),
+            postcard::to_allocvec(&scratch_proof).unwrap()
+        );
     }
 }
@@ -1,57 +1,26 @@
-use p3_baby_bear::BabyBear;
-use p3_challenger::{CanObserve, DuplexChallenger, FieldChallenger};
-use p3_commit::{ExtensionMmcs, Pcs, UnivariatePcs};
-use p3_dft::Radix2DitParallel;
-use p3_field::extension::BinomialExtensionField;
-use p3_field::Field;
-use p3_fri::{FriConfig, TwoAdicFriPcs, TwoAdicFriPcsConfig};
-use p3_matrix::dense::RowMajorMatrix;
-use p3_matrix::Matrix;
-use p3_merkle_tree::FieldMerkleTreeMmcs;
-use p3_poseidon2::{DiffusionMatrixBabybear, Poseidon2};
-use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
-use rand::thread_rng;
-
-fn make_test_fri_pcs(log_degrees: &[usize]) {
-    let mut rng = thread_rng();
-    type Val = BabyBear;
-    type Challenge = BinomialExtensionField<Val, 4>;
-
-    type Perm = Poseidon2<Val, DiffusionMatrixBabybear, 16, 7>;
-    let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng);
-
-    type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
-    let hash = MyHash::new(perm.clone());
+mod common;
 
-    type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
-    let compress = MyCompress::new(perm.clone());
+use std::sync::atomic::AtomicBool;
 
-    type ValMmcs = FieldMerkleTreeMmcs<
-        <Val as Field>::Packing,
-        <Val as Field>::Packing,
-        MyHash,
-        MyCompress,
-        8,
-    >;
-    let val_mmcs = ValMmcs::new(hash, compress);
-
-    type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
-    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+use p3_challenger::{CanObserve, CanSample, FieldChallenger};
+use p3_commit::{Pcs, UnivariatePcs, UnivariatePcsWithLde};
+use p3_field::{AbstractField, TwoAdicField};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::periodic::{PeriodicColumn, PeriodicMatrix};
+use p3_matrix::{Matrix, MatrixRows};
+use rand::{thread_rng, Rng};
 
-    type Dft = Radix2DitParallel;
-    let dft = Dft {};
+use crate::common::{pcs, pcs_with_rows_per_leaf, random_perm, Challenge, Challenger, MyPcs, Val};
 
-    type Challenger = DuplexChallenger<Val, Perm, 16>;
+fn make_test_fri_pcs(log_degrees: &[usize]) {
+    make_test_fri_pcs_with_rows_per_leaf(log_degrees, 1);
+}
 
-    let fri_config = FriConfig {
-        log_blowup: 1,
-        num_queries: 10,
-        proof_of_work_bits: 8,
-        mmcs: challenge_mmcs,
-    };
-    type Pcs =
-        TwoAdicFriPcs<TwoAdicFriPcsConfig<Val, Challenge, Challenger, Dft, ValMmcs, ChallengeMmcs>>;
-    let pcs = Pcs::new(fri_config, dft, val_mmcs);
+/// Like `make_test_fri_pcs`, but with the input MMCS packing `rows_per_leaf` rows into each leaf.
+fn make_test_fri_pcs_with_rows_per_leaf(log_degrees: &[usize], rows_per_leaf: usize) {
+    let mut rng = thread_rng();
+    let perm = random_perm();
+    let pcs = pcs_with_rows_per_leaf(&perm, rows_per_leaf);
 
     let mut challenger = Challenger::new(perm.clone());
 
@@ -68,11 +37,12 @@ fn make_test_fri_pcs(log_degrees: &[usize]) {
 
     let points = polynomials.iter().map(|_| vec![zeta]).collect::<Vec<_>>();
 
-    let (opening, proof) = <Pcs as UnivariatePcs<_, _, RowMajorMatrix<Val>, _>>::open_multi_batches(
-        &pcs,
-        &[(&data, &points)],
-        &mut challenger,
-    );
+    let (opening, proof) =
+        <MyPcs as UnivariatePcs<_, _, RowMajorMatrix<Val>, _>>::open_multi_batches(
+            &pcs,
+            &[(&data, &points)],
+            &mut challenger,
+        );
 
     // verify the proof.
     let mut challenger = Challenger::new(perm);
@@ -82,7 +52,7 @@ fn make_test_fri_pcs(log_degrees: &[usize]) {
         .iter()
         .map(|p| p.dimensions())
         .collect::<Vec<_>>();
-    <Pcs as UnivariatePcs<_, _, RowMajorMatrix<Val>, _>>::verify_multi_batches(
+    <MyPcs as UnivariatePcs<_, _, RowMajorMatrix<Val>, _>>::verify_multi_batches(
         &pcs,
         &[(commit, &points)],
         &[dims],
@@ -120,3 +90,213 @@ fn test_fri_pcs_many_different_rev() {
         make_test_fri_pcs(&degrees);
     }
 }
+
+#[test]
+fn test_fri_pcs_rows_per_leaf() {
+    // The smallest LDEs are shorter than a leaf.
+    for rows_per_leaf in [2, 4, 8] {
+        make_test_fri_pcs_with_rows_per_leaf(&[1, 2, 5], rows_per_leaf);
+    }
+}
+
+#[test]
+fn test_periodic_commitment_matches_expanded() {
+    let mut rng = thread_rng();
+    let pcs = pcs(&random_perm());
+
+    let columns = vec![
+        PeriodicColumn::new((0..8).map(|_| rng.gen::<Val>()).collect(), 1),
+        PeriodicColumn::new((0..4).map(|_| rng.gen::<Val>()).collect(), 16),
+        PeriodicColumn::constant(rng.gen::<Val>()),
+    ];
+    let periodic = PeriodicMatrix::new(columns, 1 << 6);
+    let expanded = periodic.clone().to_row_major_matrix();
+
+    let (expanded_commit, _) = pcs.commit_batch(expanded);
+    let (periodic_commit, _) = pcs.commit_periodic(periodic.clone());
+    assert_eq!(periodic_commit, expanded_commit);
+    let (periodic_batch_commit, _) = pcs.commit_batch(periodic);
+    assert_eq!(periodic_batch_commit, expanded_commit);
+
+    // A period of 3 doesn't divide the height, so this takes the fallback path.
+    let columns = vec![
+        PeriodicColumn::new((0..3).map(|_| rng.gen::<Val>()).collect(), 1),
+        PeriodicColumn::constant(rng.gen::<Val>()),
+    ];
+    let periodic = PeriodicMatrix::new(columns, 1 << 6);
+    let (expanded_commit, _) = pcs.commit_batch(periodic.clone().to_row_major_matrix());
+    let (periodic_commit, _) = pcs.commit_periodic(periodic);
+    assert_eq!(periodic_commit, expanded_commit);
+}
+
+#[test]
+fn test_col_major_commitment_matches_transposed() {
+    let mut rng = thread_rng();
+    let pcs = pcs(&random_perm());
+
+    let (height, width) = (1 << 6, 5);
+    let cols: Vec<Vec<Val>> = (0..width)
+        .map(|_| (0..height).map(|_| rng.gen()).collect())
+        .collect();
+    let transposed = RowMajorMatrix::new(
+        (0..height)
+            .flat_map(|r| cols.iter().map(move |col| col[r]))
+            .collect(),
+        width,
+    );
+
+    let shift = Val::generator().square();
+    let (col_major_commit, col_major_data) = pcs.commit_shifted_col_major(cols, shift);
+    let (row_major_commit, row_major_data) = <MyPcs as UnivariatePcsWithLde<
+        _,
+        _,
+        RowMajorMatrix<Val>,
+        Challenger,
+    >>::commit_shifted_batch(&pcs, transposed, shift);
+    assert_eq!(col_major_commit, row_major_commit);
+
+    let ldes = |data| {
+        <MyPcs as UnivariatePcsWithLde<_, _, RowMajorMatrix<Val>, Challenger>>::get_ldes(&pcs, data)
+            .pop()
+            .unwrap()
+            .to_row_major_matrix()
+    };
+    assert_eq!(ldes(&col_major_data), ldes(&row_major_data));
+}
+
+#[test]
+fn test_fri_pcs_empty_round() {
+    let mut rng = thread_rng();
+    let perm = random_perm();
+    let pcs = pcs(&perm);
+
+    let commit_empty = || <MyPcs as Pcs<Val, RowMajorMatrix<Val>>>::commit_batches(&pcs, vec![]);
+    let (empty_commit, empty_data) = commit_empty();
+    assert_eq!(empty_commit, commit_empty().0);
+
+    let polynomials = vec![RowMajorMatrix::<Val>::rand(&mut rng, 1 << 4, 10)];
+    let dims = polynomials
+        .iter()
+        .map(|p| p.dimensions())
+        .collect::<Vec<_>>();
+    let (commit, data) = pcs.commit_batches(polynomials);
+
+    // Prover world
+    let mut challenger = Challenger::new(perm.clone());
+    challenger.observe(empty_commit);
+    challenger.observe(commit);
+    let zeta = challenger.sample_ext_element::<Challenge>();
+    let no_points: Vec<Vec<Challenge>> = vec![];
+    let points = vec![vec![zeta]];
+    let (opening, proof) =
+        <MyPcs as UnivariatePcs<_, _, RowMajorMatrix<Val>, _>>::open_multi_batches(
+            &pcs,
+            &[(&empty_data, &no_points), (&data, &points)],
+            &mut challenger,
+        );
+    assert!(opening[0].is_empty());
+    let prover_sample: Val = challenger.sample();
+
+    // Verifier world
+    let mut challenger = Challenger::new(perm);
+    challenger.observe(empty_commit);
+    challenger.observe(commit);
+    assert_eq!(challenger.sample_ext_element::<Challenge>(), zeta);
+    <MyPcs as UnivariatePcs<_, _, RowMajorMatrix<Val>, _>>::verify_multi_batches(
+        &pcs,
+        &[(empty_commit, &no_points), (commit, &points)],
+        &[vec![], dims],
+        opening,
+        &proof,
+        &mut challenger,
+    )
+    .expect("verification error");
+    let verifier_sample: Val = challenger.sample();
+    assert_eq!(verifier_sample, prover_sample);
+}
+
+#[test]
+fn test_fri_pcs_per_matrix_points() {
+    let mut rng = thread_rng();
+    let perm = random_perm();
+    let pcs = pcs(&perm);
+
+    // A "trace" opened at two points, and a "quotient" of a different shape opened at a third.
+    let polynomials = vec![
+        RowMajorMatrix::<Val>::rand(&mut rng, 1 << 5, 4),
+        RowMajorMatrix::<Val>::rand(&mut rng, 1 << 3, 7),
+    ];
+    let dims = polynomials
+        .iter()
+        .map(|p| p.dimensions())
+        .collect::<Vec<_>>();
+    let (commit, data) = pcs.commit_batches(polynomials);
+
+    // Prover world
+    let mut challenger = Challenger::new(perm.clone());
+    challenger.observe(commit);
+    let zeta = challenger.sample_ext_element::<Challenge>();
+    let points = vec![
+        vec![zeta, zeta * Val::two_adic_generator(5)],
+        vec![zeta.square()],
+    ];
+    let (opening, proof) =
+        <MyPcs as UnivariatePcs<_, _, RowMajorMatrix<Val>, _>>::open_multi_batches(
+            &pcs,
+            &[(&data, &points)],
+            &mut challenger,
+        );
+    assert_eq!(opening[0][0].len(), 2);
+    assert_eq!(opening[0][1].len(), 1);
+    assert_eq!(opening[0][0][0].len(), 4);
+    assert_eq!(opening[0][1][0].len(), 7);
+
+    let verify = |opening| {
+        let mut challenger = Challenger::new(perm.clone());
+        challenger.observe(commit);
+        let _ = challenger.sample_ext_element::<Challenge>();
+        <MyPcs as UnivariatePcs<_, _, RowMajorMatrix<Val>, _>>::verify_multi_batches(
+            &pcs,
+            &[(commit, &points)],
+            &[dims.clone()],
+            opening,
+            &proof,
+            &mut challenger,
+        )
+    };
+
+    // Dropping the values at one of the trace's points must not go unnoticed.
+    let mut truncated = opening.clone();
+    truncated[0][0].pop();
+    assert!(verify(truncated).is_err());
+
+    verify(opening).expect("verification error");
+}
+
+#[test]
+fn test_fri_pcs_cancelled() {
+    let mut rng = thread_rng();
+    let perm = random_perm();
+    let pcs = pcs(&perm);
+
+    let polynomials = vec![RowMajorMatrix::<Val>::rand(&mut rng, 1 << 4, 10)];
+    let shift = [Val::generator()];
+    let cancel = AtomicBool::new(true);
+    assert!(pcs
+        .commit_shifted_batches_cancellable(polynomials.clone(), &shift, &cancel)
+        .is_none());
+
+    // With the flag still set, an opening of an uncancelled commitment is abandoned too.
+    let (commit, data) = pcs.commit_shifted_batches(polynomials, &shift);
+    let mut challenger = Challenger::new(perm);
+    challenger.observe(commit);
+    let points = vec![vec![challenger.sample_ext_element::<Challenge>()]];
+    let opened =
+        <MyPcs as UnivariatePcs<_, _, RowMajorMatrix<Val>, _>>::open_multi_batches_cancellable(
+            &pcs,
+            &[(&data, &points)],
+            &mut challenger,
+            &cancel,
+        );
+    assert!(opened.is_none());
+}
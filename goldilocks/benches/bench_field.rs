@@ -1,8 +1,9 @@
 use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
 use p3_field::AbstractField;
 use p3_field_testing::bench_func::{
-    benchmark_add_latency, benchmark_add_throughput, benchmark_inv, benchmark_iter_sum,
-    benchmark_sub_latency, benchmark_sub_throughput,
+    benchmark_add_latency, benchmark_add_throughput, benchmark_dot_product, benchmark_inv,
+    benchmark_iter_sum, benchmark_sub_latency, benchmark_sub_throughput,
+    benchmark_sum_product_slice,
 };
 use p3_goldilocks::Goldilocks;
 
@@ -15,6 +16,8 @@ fn bench_field(c: &mut Criterion) {
     benchmark_iter_sum::<F, 4, REPS>(c, name);
     benchmark_iter_sum::<F, 8, REPS>(c, name);
     benchmark_iter_sum::<F, 12, REPS>(c, name);
+    benchmark_dot_product::<F, 1024>(c, name);
+    benchmark_sum_product_slice::<F, 4096>(c, name);
 
     // Note that each round of throughput has 10 operations
     // So we should have 10 * more repitions for latency tests.
@@ -1,16 +1,26 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use p3_field::extension::BinomialExtensionField;
-use p3_field_testing::bench_func::{benchmark_inv, benchmark_mul, benchmark_square};
+use p3_field_testing::bench_func::{benchmark_binomial_mul, benchmark_inv, benchmark_square};
 use p3_goldilocks::Goldilocks;
 
 type EF2 = BinomialExtensionField<Goldilocks, 2>;
+type EF3 = BinomialExtensionField<Goldilocks, 3>;
 
 fn bench_qudratic_extension(c: &mut Criterion) {
     let name = "BinomialExtensionField<Goldilocks, 2>";
     benchmark_square::<EF2>(c, name);
     benchmark_inv::<EF2>(c, name);
-    benchmark_mul::<EF2>(c, name);
+    benchmark_binomial_mul::<Goldilocks, 2>(c, name);
+}
+
+fn bench_cubic_extension(c: &mut Criterion) {
+    let name = "BinomialExtensionField<Goldilocks, 3>";
+    benchmark_square::<EF3>(c, name);
+    benchmark_inv::<EF3>(c, name);
+    benchmark_binomial_mul::<Goldilocks, 3>(c, name);
 }
 
 criterion_group!(bench_goldilocks_ef2, bench_qudratic_extension);
-criterion_main!(bench_goldilocks_ef2);
+criterion_group!(bench_goldilocks_ef3, bench_cubic_extension);
+
+criterion_main!(bench_goldilocks_ef2, bench_goldilocks_ef3);
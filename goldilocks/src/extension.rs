@@ -1,5 +1,5 @@
 use p3_field::extension::{BinomiallyExtendable, HasTwoAdicBionmialExtension};
-use p3_field::{AbstractField, TwoAdicField};
+use p3_field::{field_to_array, AbstractField, TwoAdicField};
 
 use crate::Goldilocks;
 
@@ -37,11 +37,38 @@ impl HasTwoAdicBionmialExtension<2> for Goldilocks {
     }
 }
 
+impl BinomiallyExtendable<3> for Goldilocks {
+    // Verifiable in Sage with
+    // `R.<x> = GF(p)[]; assert (x^3 - 2).is_irreducible()`.
+    fn w() -> Self {
+        Self::new(2)
+    }
+
+    // DTH_ROOT = W^((p - 1)/3).
+    fn dth_root() -> Self {
+        Self::new(4294967295)
+    }
+
+    fn ext_generator() -> [Self; 3] {
+        [Self::new(5), Self::one(), Self::zero()]
+    }
+}
+
+impl HasTwoAdicBionmialExtension<3> for Goldilocks {
+    const EXT_TWO_ADICITY: usize = 32;
+
+    fn ext_two_adic_generator(bits: usize) -> [Self; 3] {
+        field_to_array::<Self, 3>(Self::two_adic_generator(bits))
+    }
+}
+
 #[cfg(test)]
 mod test_quadratic_extension {
 
     use p3_field::extension::BinomialExtensionField;
-    use p3_field_testing::{test_field, test_two_adic_extension_field};
+    use p3_field_testing::{
+        test_binomial_extension_mul, test_field, test_two_adic_extension_field,
+    };
 
     use crate::Goldilocks;
 
@@ -49,6 +76,25 @@ mod test_quadratic_extension {
     type EF = BinomialExtensionField<F, 2>;
 
     test_field!(super::EF);
+    test_binomial_extension_mul!(super::F, 2);
+
+    test_two_adic_extension_field!(super::F, super::EF);
+}
+
+#[cfg(test)]
+mod test_cubic_extension {
+    use p3_field::extension::BinomialExtensionField;
+    use p3_field_testing::{
+        test_binomial_extension_mul, test_field, test_two_adic_extension_field,
+    };
+
+    use crate::Goldilocks;
+
+    type F = Goldilocks;
+    type EF = BinomialExtensionField<F, 3>;
+
+    test_field!(super::EF);
+    test_binomial_extension_mul!(super::F, 3);
 
     test_two_adic_extension_field!(super::F, super::EF);
 }
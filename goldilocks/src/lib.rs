@@ -10,14 +10,16 @@ use core::hash::{Hash, Hasher};
 use core::iter::{Product, Sum};
 use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
 
+use num_bigint::BigUint;
 use p3_field::{
-    exp_10540996611094048183, exp_u64_by_squaring, AbstractField, Field, Packable, PrimeField,
-    PrimeField64, TwoAdicField,
+    exp_10540996611094048183, exp_u64_by_squaring, AbstractField, ConstantTimeField, Field,
+    Packable, PrimeField, PrimeField64, TwoAdicField,
 };
 use p3_util::{assume, branch_hint};
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 
 /// The prime field known as Goldilocks, defined as `F_p` where `p = 2^64 - 2^32 + 1`.
 #[derive(Copy, Clone, Default, Serialize, Deserialize)]
@@ -210,9 +212,34 @@ impl Field for Goldilocks {
         // compute base^1111111111111111111111111111111011111111111111111111111111111111
         Some(t63.square() * *self)
     }
+
+    fn characteristic() -> BigUint {
+        Self::ORDER_U64.into()
+    }
 }
 
-impl PrimeField for Goldilocks {}
+impl PrimeField for Goldilocks {
+    fn as_canonical_biguint(&self) -> BigUint {
+        self.as_canonical_u64().into()
+    }
+}
+
+impl ConstantTimeField for Goldilocks {
+    #[inline]
+    fn ct_eq(&self, other: &Self) -> Choice {
+        // Like `as_canonical_u64`, but selecting the reduced value rather than branching on it.
+        let canonical = |x: u64| {
+            let (reduced, borrow) = x.overflowing_sub(Self::ORDER_U64);
+            u64::conditional_select(&reduced, &x, Choice::from(borrow as u8))
+        };
+        canonical(self.value).ct_eq(&canonical(other.value))
+    }
+
+    #[inline]
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self::new(u64::conditional_select(&a.value, &b.value, choice))
+    }
+}
 
 impl PrimeField64 for Goldilocks {
     const ORDER_U64: u64 = 0xFFFF_FFFF_0000_0001;
@@ -431,7 +458,9 @@ unsafe fn add_no_canonicalize_trashing_input(x: u64, y: u64) -> u64 {
 
 #[cfg(test)]
 mod tests {
-    use p3_field_testing::{test_field, test_two_adic_field};
+    use p3_field_testing::{
+        test_field, test_prime_field, test_prime_field_64, test_two_adic_field,
+    };
 
     use super::*;
 
@@ -519,6 +548,24 @@ mod tests {
         assert_eq!(f_2.exp_u64(10540996611094048183).exp_const_u64::<7>(), f_2);
     }
 
+    #[test]
+    fn test_characteristic() {
+        assert_eq!(F::characteristic(), BigUint::from(0xffff_ffff_0000_0001u64));
+        assert_eq!(F::order(), F::characteristic());
+    }
+
+    #[test]
+    fn test_ct_eq_non_canonical() {
+        // `F::ORDER_U64 + 5` is a non-canonical representation of 5.
+        let five = F::new(5);
+        let non_canonical = F::new(F::ORDER_U64 + 5);
+        assert!(bool::from(five.ct_eq(&non_canonical)));
+        assert!(!bool::from(F::new(6).ct_eq(&non_canonical)));
+        assert!(bool::from(F::zero().ct_eq(&F::new(F::ORDER_U64))));
+    }
+
     test_field!(crate::Goldilocks);
+    test_prime_field!(crate::Goldilocks);
+    test_prime_field_64!(crate::Goldilocks);
     test_two_adic_field!(crate::Goldilocks);
 }
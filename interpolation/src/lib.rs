@@ -8,7 +8,7 @@ use alloc::vec;
 use alloc::vec::Vec;
 
 use p3_field::{
-    batch_multiplicative_inverse, cyclic_subgroup_coset_known_order, scale_vec,
+    batch_multiplicative_inverse, cyclic_subgroup_coset_known_order, scale_vec, sum_vecs,
     two_adic_coset_zerofier, ExtensionField, Field, TwoAdicField,
 };
 use p3_matrix::MatrixRows;
@@ -56,6 +56,47 @@ where
     scale_vec(zerofier * denominator.inverse(), sum)
 }
 
+/// The barycentric weights `w_i = 1 / prod_{j != i} (x_i - x_j)` of distinct `points`, for use
+/// with `interpolate_points`.
+pub fn barycentric_weights<F: Field>(points: &[F]) -> Vec<F> {
+    let n = points.len();
+    let mut diffs = Vec::with_capacity(n);
+    let denominators = (0..n)
+        .map(|i| {
+            diffs.clear();
+            diffs.extend((0..n).filter(|&j| j != i).map(|j| points[i] - points[j]));
+            F::product_slice(&diffs)
+        })
+        .collect::<Vec<_>>();
+    batch_multiplicative_inverse(&denominators)
+}
+
+/// Given evaluations of a batch of polynomials over arbitrary distinct `points`, whose
+/// `barycentric_weights` have been computed, evaluate the polynomials at `x`.
+pub fn interpolate_points<F: Field, Mat: MatrixRows<F>>(
+    points: &[F],
+    values: &Mat,
+    x: F,
+    barycentric_weights: &[F],
+) -> Vec<F> {
+    // If x is in the list of points, the Lagrange formula would divide by zero.
+    for (i, &x_i) in points.iter().enumerate() {
+        if x_i == x {
+            return values.row(i).into_iter().collect();
+        }
+    }
+
+    let diffs = points.iter().map(|&x_i| x - x_i).collect::<Vec<_>>();
+    let l_x = F::product_slice(&diffs);
+
+    let sum = sum_vecs(diffs.iter().enumerate().map(|(i, &diff)| {
+        let y_i = values.row(i).into_iter().collect();
+        scale_vec(barycentric_weights[i] / diff, y_i)
+    }));
+
+    scale_vec(l_x, sum)
+}
+
 /// `x += y * s`, where `s` is a scalar.
 pub fn add_scaled_base_slice_in_place<F, EF, Y>(x: &mut [EF], y: Y, s: EF)
 where
@@ -70,12 +111,13 @@ where
 #[cfg(test)]
 mod tests {
     use alloc::vec;
+    use alloc::vec::Vec;
 
     use p3_baby_bear::BabyBear;
     use p3_field::AbstractField;
     use p3_matrix::dense::RowMajorMatrix;
 
-    use crate::{interpolate_coset, interpolate_subgroup};
+    use crate::{barycentric_weights, interpolate_coset, interpolate_points, interpolate_subgroup};
 
     #[test]
     fn test_interpolate_subgroup() {
@@ -105,4 +147,21 @@ mod tests {
         let result = interpolate_coset(&evals_mat, shift, point);
         assert_eq!(result, vec![F::from_canonical_u32(10203)]);
     }
+
+    #[test]
+    fn test_interpolate_points() {
+        // x^2 + 2 x + 3, at 1, 2, ..., 9.
+        type F = BabyBear;
+        let points = (1..10).map(F::from_canonical_u32).collect::<Vec<_>>();
+        let evals = points
+            .iter()
+            .map(|&x| x * x + x.double() + F::from_canonical_u32(3))
+            .collect();
+        let evals_mat = RowMajorMatrix::new(evals, 1);
+        let weights = barycentric_weights(&points);
+        let result = interpolate_points(&points, &evals_mat, F::from_canonical_u32(100), &weights);
+        assert_eq!(result, vec![F::from_canonical_u32(10203)]);
+        let result = interpolate_points(&points, &evals_mat, points[4], &weights);
+        assert_eq!(result, vec![F::from_canonical_u32(38)]);
+    }
 }
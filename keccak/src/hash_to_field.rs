@@ -0,0 +1,116 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_field::{AbstractField, PrimeField64};
+use tiny_keccak::{Hasher, Shake};
+
+/// The domain separation tag passed to `expand_message_xof`.
+pub const HASH_TO_FIELD_DST: &[u8] = b"p3-keccak/hash-to-field/SHAKE128";
+
+/// The security parameter `k` of RFC 9380, in bits.
+const SECURITY_BITS: usize = 128;
+
+/// Hashes `bytes` to `count` field elements, following `hash_to_field` from
+/// [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380#section-5.2) with
+/// `expand_message_xof` over SHAKE128 and the tag `HASH_TO_FIELD_DST`.
+///
+/// Each element is reduced from `L = ceil((ceil(log2(p)) + 128) / 8)` big-endian bytes of XOF
+/// output, so its distribution is within `2^-128` of uniform. The output depends only on `bytes`,
+/// `count` and the field order, so it's identical on every platform.
+pub fn hash_to_field<F: PrimeField64>(bytes: &[u8], count: usize) -> Vec<F> {
+    let bytes_per_element = (F::bits() + SECURITY_BITS).div_ceil(8);
+    let uniform_bytes = expand_message_xof(bytes, bytes_per_element * count);
+    let base = F::from_canonical_u16(256);
+    uniform_bytes
+        .chunks_exact(bytes_per_element)
+        .map(|chunk| {
+            chunk.iter().fold(F::zero(), |acc, &byte| {
+                acc * base + F::from_canonical_u8(byte)
+            })
+        })
+        .collect()
+}
+
+/// `expand_message_xof` from RFC 9380, section 5.3.2, instantiated with SHAKE128.
+fn expand_message_xof(msg: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    let len_in_bytes_u16: u16 = len_in_bytes
+        .try_into()
+        .expect("hash_to_field: too many bytes requested");
+
+    let mut shake = Shake::v128();
+    shake.update(msg);
+    shake.update(&len_in_bytes_u16.to_be_bytes());
+    shake.update(HASH_TO_FIELD_DST);
+    shake.update(&[HASH_TO_FIELD_DST.len() as u8]);
+
+    let mut output = vec![0; len_in_bytes];
+    shake.finalize(&mut output);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::{AbstractField, PrimeField64};
+    use p3_goldilocks::Goldilocks;
+
+    use super::*;
+
+    #[test]
+    fn test_baby_bear_vectors() {
+        let expected =
+            [1405432022, 551444068, 1401221775, 1292249353].map(BabyBear::from_canonical_u32);
+        assert_eq!(hash_to_field::<BabyBear>(b"", 4), expected);
+
+        let expected =
+            [634504980, 15746054, 1650414590, 1076572282].map(BabyBear::from_canonical_u32);
+        assert_eq!(hash_to_field::<BabyBear>(b"abc", 4), expected);
+    }
+
+    #[test]
+    fn test_goldilocks_vectors() {
+        let expected = [
+            9985926703670611557,
+            17530996552109101111,
+            14725111366923406858,
+        ]
+        .map(Goldilocks::from_canonical_u64);
+        assert_eq!(hash_to_field::<Goldilocks>(b"", 3), expected);
+
+        let expected = [
+            16361178101351159630,
+            1221092225198680638,
+            3367446380147857638,
+        ]
+        .map(Goldilocks::from_canonical_u64);
+        assert_eq!(hash_to_field::<Goldilocks>(b"abc", 3), expected);
+    }
+
+    #[test]
+    fn test_count_is_part_of_the_input() {
+        // As in RFC 9380, the requested length is hashed, so shorter outputs aren't prefixes of
+        // longer ones.
+        let short = hash_to_field::<BabyBear>(b"abc", 2);
+        let long = hash_to_field::<BabyBear>(b"abc", 4);
+        assert_ne!(short[..], long[..2]);
+    }
+
+    #[test]
+    fn test_uniformity() {
+        // Split the field into 16 equal ranges; each should get about 1/16 of the outputs.
+        const BUCKETS: usize = 16;
+        const SAMPLES: usize = 3200;
+        let mut counts = [0usize; BUCKETS];
+        for x in hash_to_field::<BabyBear>(b"uniformity", SAMPLES) {
+            let bucket =
+                x.as_canonical_u64() as u128 * BUCKETS as u128 / BabyBear::ORDER_U64 as u128;
+            counts[bucket as usize] += 1;
+        }
+
+        // The expected count is 200, with a standard deviation of about 14.
+        let expected = SAMPLES / BUCKETS;
+        for count in counts {
+            assert!(count.abs_diff(expected) < 75, "bucket counts: {counts:?}");
+        }
+    }
+}
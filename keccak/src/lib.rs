@@ -4,8 +4,11 @@
 
 extern crate alloc;
 
+mod hash_to_field;
+
 use alloc::vec::Vec;
 
+pub use hash_to_field::*;
 use p3_symmetric::{CryptographicHasher, CryptographicPermutation, Permutation};
 use tiny_keccak::{keccakf, Hasher, Keccak};
 
@@ -1,9 +1,9 @@
 use alloc::vec::Vec;
 
 use p3_field::{
-    batch_multiplicative_inverse, cyclic_subgroup_coset_known_order, cyclic_subgroup_known_order,
-    scale_vec, sum_vecs, Field, TwoAdicField,
+    cyclic_subgroup_coset_known_order, cyclic_subgroup_known_order, Field, TwoAdicField,
 };
+use p3_interpolation::{barycentric_weights, interpolate_points};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::stack::VerticalPair;
 use p3_matrix::{Matrix, MatrixRows};
@@ -36,7 +36,7 @@ where
 
         let added_values = (original_height..extended_height)
             .map(|x| F::from_canonical_usize(x))
-            .flat_map(|x| interpolate(&original_domain, &polys, x, &weights))
+            .flat_map(|x| interpolate_points(&original_domain, &polys, x, &weights))
             .collect();
         let extension = RowMajorMatrix::new(added_values, polys.width());
         VerticalPair::new(polys, extension)
@@ -58,7 +58,7 @@ where
         let lde_subgroup = cyclic_subgroup_known_order::<Val>(g_lde, 1 << lde_bits);
 
         let values = lde_subgroup
-            .flat_map(|x| interpolate(&subgroup, &polys, x, &weights))
+            .flat_map(|x| interpolate_points(&subgroup, &polys, x, &weights))
             .collect();
         RowMajorMatrix::new(values, polys.width())
     }
@@ -80,7 +80,7 @@ where
             cyclic_subgroup_coset_known_order(g_lde, self.shift(lde_bits), 1 << lde_bits);
 
         let values = lde_subgroup
-            .flat_map(|x| interpolate(&subgroup, &polys, x, &weights))
+            .flat_map(|x| interpolate_points(&subgroup, &polys, x, &weights))
             .collect();
         RowMajorMatrix::new(values, polys.width())
     }
@@ -96,44 +96,3 @@ where
         Val::generator()
     }
 }
-
-// TODO: Move to interpolation crate?
-fn barycentric_weights<F: Field>(points: &[F]) -> Vec<F> {
-    let n = points.len();
-    batch_multiplicative_inverse(
-        &(0..n)
-            .map(|i| {
-                (0..n)
-                    .filter(|&j| j != i)
-                    .map(|j| points[i] - points[j])
-                    .product::<F>()
-            })
-            .collect::<Vec<_>>(),
-    )
-}
-
-// TODO: Move to interpolation crate?
-fn interpolate<F: Field, Mat: MatrixRows<F>>(
-    points: &[F],
-    values: &Mat,
-    x: F,
-    barycentric_weights: &[F],
-) -> Vec<F> {
-    // If x is in the list of points, the Lagrange formula would divide by zero.
-    for (i, &x_i) in points.iter().enumerate() {
-        if x_i == x {
-            return values.row(i).into_iter().collect();
-        }
-    }
-
-    let l_x: F = points.iter().map(|&x_i| x - x_i).product();
-
-    let sum = sum_vecs((0..points.len()).map(|i| {
-        let x_i = points[i];
-        let y_i = values.row(i).into_iter().collect();
-        let w_i = barycentric_weights[i];
-        scale_vec(w_i / (x - x_i), y_i)
-    }));
-
-    scale_vec(l_x, sum)
-}
@@ -0,0 +1,79 @@
+use core::iter::{Skip, Take};
+use core::ops::Range;
+
+use crate::{Matrix, MatrixGet, MatrixRowSlices, MatrixRows};
+
+/// A view of a contiguous range of the columns of `Inner`.
+pub struct ColumnRangeView<Inner> {
+    inner: Inner,
+    columns: Range<usize>,
+}
+
+impl<Inner> ColumnRangeView<Inner> {
+    pub fn new<T>(inner: Inner, columns: Range<usize>) -> Self
+    where
+        Inner: Matrix<T>,
+    {
+        assert!(
+            columns.start <= columns.end && columns.end <= inner.width(),
+            "columns {columns:?} out of range for a matrix of width {}",
+            inner.width()
+        );
+        Self { inner, columns }
+    }
+}
+
+impl<T, Inner: Matrix<T>> Matrix<T> for ColumnRangeView<Inner> {
+    fn width(&self) -> usize {
+        self.columns.len()
+    }
+
+    fn height(&self) -> usize {
+        self.inner.height()
+    }
+}
+
+impl<T, Inner: MatrixGet<T>> MatrixGet<T> for ColumnRangeView<Inner> {
+    fn get(&self, r: usize, c: usize) -> T {
+        debug_assert!(c < self.columns.len());
+        self.inner.get(r, self.columns.start + c)
+    }
+}
+
+impl<T, Inner: MatrixRows<T>> MatrixRows<T> for ColumnRangeView<Inner> {
+    type Row<'a> = Take<Skip<<Inner::Row<'a> as IntoIterator>::IntoIter>> where Self: 'a;
+
+    fn row(&self, r: usize) -> Self::Row<'_> {
+        self.inner
+            .row(r)
+            .into_iter()
+            .skip(self.columns.start)
+            .take(self.columns.len())
+    }
+}
+
+impl<T, Inner: MatrixRowSlices<T>> MatrixRowSlices<T> for ColumnRangeView<Inner> {
+    fn row_slice(&self, r: usize) -> &[T] {
+        &self.inner.row_slice(r)[self.columns.clone()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::column_range::ColumnRangeView;
+    use crate::dense::RowMajorMatrix;
+    use crate::{Matrix, MatrixGet, MatrixRowSlices, MatrixRows};
+
+    #[test]
+    fn test_column_range_view() {
+        let mat = RowMajorMatrix::new(vec![1, 2, 3, 4, 5, 6, 7, 8], 4);
+        let view = ColumnRangeView::new(mat, 1..3);
+        assert_eq!(view.width(), 2);
+        assert_eq!(view.height(), 2);
+        assert_eq!(view.row_slice(1), &[6, 7]);
+        assert_eq!(view.row_vec(0), vec![2, 3]);
+        assert_eq!(view.get(1, 1), 7);
+    }
+}
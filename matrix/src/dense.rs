@@ -9,6 +9,7 @@ use rand::distributions::{Distribution, Standard};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
+use crate::util::reverse_matrix_index_bits;
 use crate::{Matrix, MatrixGet, MatrixRowSlices, MatrixRowSlicesMut, MatrixRows, MatrixTranspose};
 
 /// A default constant for block size matrix transposition. The value was chosen with 32-byte type, in mind.
@@ -86,6 +87,24 @@ impl<T> RowMajorMatrix<T> {
         }
     }
 
+    /// Split this matrix into `num_shards` views of consecutive rows, all of the same height.
+    /// Unlike `RowMajorMatrixView::split_rows`, this takes the number of pieces rather than a row
+    /// index to split at.
+    ///
+    /// Both the height and `num_shards` must be powers of two, so that each shard is a whole
+    /// subtree of a Merkle tree over the full matrix.
+    pub fn split_rows(&self, num_shards: usize) -> Vec<RowMajorMatrixView<T>> {
+        let height = self.height();
+        assert!(
+            num_shards.is_power_of_two() && height.is_power_of_two() && num_shards <= height,
+            "cannot split a matrix of height {height} into {num_shards} shards"
+        );
+        self.values
+            .chunks_exact(height / num_shards * self.width)
+            .map(|values| RowMajorMatrixView::new(values, self.width))
+            .collect()
+    }
+
     pub fn as_view_mut(&mut self) -> RowMajorMatrixViewMut<T> {
         RowMajorMatrixViewMut {
             values: &mut self.values,
@@ -113,7 +132,8 @@ impl<T> RowMajorMatrix<T> {
         }
     }
 
-    /// Flatten a matrix of extension field elements into a matrix of base field elements.
+    /// Flatten a matrix of extension field elements into a matrix of base field elements, with
+    /// each element expanded into its `D` coefficients.
     pub fn flatten_to_base<F: Field>(&self) -> RowMajorMatrix<F>
     where
         T: ExtensionField<F>,
@@ -127,6 +147,26 @@ impl<T> RowMajorMatrix<T> {
         RowMajorMatrix { values, width }
     }
 
+    /// The inverse of `flatten_to_base`: group each `D` adjacent base field elements into one
+    /// extension field element.
+    pub fn unflatten_from_base<F: Field>(base: &RowMajorMatrix<F>) -> Self
+    where
+        T: ExtensionField<F>,
+    {
+        assert_eq!(
+            base.width % T::D,
+            0,
+            "width must be a multiple of the extension degree"
+        );
+        let width = base.width / T::D;
+        let values = base
+            .values
+            .chunks_exact(T::D)
+            .map(T::from_base_slice)
+            .collect();
+        RowMajorMatrix { values, width }
+    }
+
     pub fn to_ext<EF: ExtensionField<T>>(&self) -> RowMajorMatrix<EF>
     where
         T: Field,
@@ -265,6 +305,16 @@ impl<'a, T> RowMajorMatrixView<'a, T> {
         };
         (upper, lower)
     }
+
+    /// Copy this matrix with its rows permuted into bit-reversed order.
+    pub fn to_owned_bit_reversed(&self) -> RowMajorMatrix<T>
+    where
+        T: Clone,
+    {
+        let mut mat = RowMajorMatrix::new(self.values.to_vec(), self.width);
+        reverse_matrix_index_bits(&mut mat);
+        mat
+    }
 }
 
 impl<T> Matrix<T> for RowMajorMatrixView<'_, T> {
@@ -482,8 +532,25 @@ where
 
 #[cfg(test)]
 mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::extension::BinomialExtensionField;
+    use p3_field::AbstractExtensionField;
+    use rand::thread_rng;
+
     use super::*;
 
+    #[test]
+    fn test_split_rows() {
+        let matrix = RowMajorMatrix::new((0..16).collect::<Vec<_>>(), 2);
+        let shards = matrix.split_rows(4);
+        assert_eq!(shards.len(), 4);
+        for (i, shard) in shards.iter().enumerate() {
+            assert_eq!(shard.width(), 2);
+            assert_eq!(shard.height(), 2);
+            assert_eq!(shard.values, &matrix.values[4 * i..4 * (i + 1)]);
+        }
+    }
+
     #[test]
     fn test_transpose_square_matrix() {
         const START_INDEX: usize = 1;
@@ -578,4 +645,22 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_flatten_to_base_round_trip() {
+        type F = BabyBear;
+        type EF = BinomialExtensionField<F, 4>;
+
+        let mut rng = thread_rng();
+        let matrix = RowMajorMatrix::<EF>::rand(&mut rng, 8, 3);
+        let flattened = matrix.flatten_to_base::<F>();
+        assert_eq!(flattened.width(), 3 * 4);
+        assert_eq!(flattened.height(), 8);
+        // Each extension element becomes its coefficients, in order.
+        assert_eq!(
+            flattened.row_slice(5)[4..8],
+            *matrix.get(5, 1).as_base_slice()
+        );
+        assert_eq!(RowMajorMatrix::unflatten_from_base(&flattened), matrix);
+    }
 }
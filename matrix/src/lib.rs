@@ -11,11 +11,14 @@ use crate::dense::RowMajorMatrix;
 use crate::strided::VerticallyStridedMatrixView;
 
 pub mod bitrev;
+pub mod column_range;
 pub mod dense;
 pub mod mul;
+pub mod periodic;
 pub mod sparse;
 pub mod stack;
 pub mod strided;
+pub mod trace_builder;
 pub mod util;
 
 pub trait Matrix<T> {
@@ -0,0 +1,165 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::Cloned;
+use core::slice;
+
+use crate::dense::RowMajorMatrixView;
+use crate::{Matrix, MatrixGet, MatrixRowSlices, MatrixRows};
+
+/// A compressed column which cycles through `values`, holding each value for `run_length`
+/// consecutive rows. With `run_length = 1` this is an ordinary periodic column, while a column made
+/// of a single cycle of runs, such as round constants which are fixed within each round, is
+/// run-length encoded.
+#[derive(Clone, Debug)]
+pub struct PeriodicColumn<T> {
+    pub values: Vec<T>,
+    pub run_length: usize,
+}
+
+impl<T: Clone> PeriodicColumn<T> {
+    pub fn new(values: Vec<T>, run_length: usize) -> Self {
+        assert!(!values.is_empty());
+        assert!(run_length > 0);
+        Self { values, run_length }
+    }
+
+    /// A column which is constant over all rows.
+    pub fn constant(value: T) -> Self {
+        Self::new(vec![value], 1)
+    }
+
+    /// The number of rows after which the column repeats.
+    pub fn period(&self) -> usize {
+        self.values.len() * self.run_length
+    }
+
+    pub fn get(&self, r: usize) -> T {
+        self.values[(r / self.run_length) % self.values.len()].clone()
+    }
+}
+
+/// A matrix made of `PeriodicColumn`s.
+///
+/// Only the rows up to the matrix's period, the least common multiple of its columns' periods, are
+/// stored, and every other row borrows from them. A PCS can also commit to this without expanding
+/// it, as `TwoAdicFriPcs::commit_periodic` does, by extending a single period.
+#[derive(Clone, Debug)]
+pub struct PeriodicMatrix<T> {
+    columns: Vec<PeriodicColumn<T>>,
+    height: usize,
+    period: usize,
+    /// The first `period` rows, in row-major order.
+    period_values: Vec<T>,
+}
+
+impl<T: Clone> PeriodicMatrix<T> {
+    pub fn new(columns: Vec<PeriodicColumn<T>>, height: usize) -> Self {
+        // If the columns only line up again after the last row, store every row.
+        let period = columns
+            .iter()
+            .try_fold(1, |period: usize, col| {
+                let col_period = col.period();
+                (period / gcd(period, col_period)).checked_mul(col_period)
+            })
+            .filter(|&period| period <= height)
+            .unwrap_or(height);
+        let period_values = (0..period)
+            .flat_map(|r| columns.iter().map(move |col| col.get(r)))
+            .collect();
+        Self {
+            columns,
+            height,
+            period,
+            period_values,
+        }
+    }
+
+    pub fn columns(&self) -> &[PeriodicColumn<T>] {
+        &self.columns
+    }
+
+    /// The number of rows after which the matrix repeats, or its height if it doesn't repeat
+    /// within it.
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// The first `period()` rows.
+    pub fn first_period(&self) -> RowMajorMatrixView<'_, T> {
+        RowMajorMatrixView::new(&self.period_values, self.width())
+    }
+}
+
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl<T> Matrix<T> for PeriodicMatrix<T> {
+    fn width(&self) -> usize {
+        self.columns.len()
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+}
+
+impl<T: Clone> MatrixGet<T> for PeriodicMatrix<T> {
+    fn get(&self, r: usize, c: usize) -> T {
+        debug_assert!(r < self.height);
+        self.row_slice(r)[c].clone()
+    }
+}
+
+impl<T: Clone> MatrixRows<T> for PeriodicMatrix<T> {
+    type Row<'a> = Cloned<slice::Iter<'a, T>> where T: 'a;
+
+    fn row(&self, r: usize) -> Self::Row<'_> {
+        self.row_slice(r).iter().cloned()
+    }
+}
+
+impl<T: Clone> MatrixRowSlices<T> for PeriodicMatrix<T> {
+    fn row_slice(&self, r: usize) -> &[T] {
+        debug_assert!(r < self.height);
+        let width = self.width();
+        let start = (r % self.period) * width;
+        &self.period_values[start..start + width]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dense::RowMajorMatrix;
+
+    #[test]
+    fn test_expand() {
+        let columns = vec![
+            PeriodicColumn::new(vec![1, 2, 3], 1),
+            PeriodicColumn::new(vec![4, 5], 3),
+            PeriodicColumn::constant(6),
+        ];
+        let matrix = PeriodicMatrix::new(columns, 8);
+        assert_eq!(matrix.period(), 6);
+        assert_eq!(matrix.get(4, 1), 5);
+
+        let expected = RowMajorMatrix::new(
+            vec![
+                1, 4, 6, //
+                2, 4, 6, //
+                3, 4, 6, //
+                1, 5, 6, //
+                2, 5, 6, //
+                3, 5, 6, //
+                1, 4, 6, //
+                2, 4, 6, //
+            ],
+            3,
+        );
+        assert_eq!(matrix.to_row_major_matrix(), expected);
+    }
+}
@@ -0,0 +1,179 @@
+use alloc::vec::Vec;
+
+use p3_field::Field;
+
+use crate::dense::RowMajorMatrix;
+
+/// How `TraceBuilder::finalize` fills the rows between the last pushed row and the next power of
+/// two.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PadMode<F> {
+    /// Pad with rows of zeros.
+    Zeros,
+    /// Pad with copies of the last pushed row.
+    RepeatLastRow,
+    /// Pad with copies of the given row, which must have the trace's width.
+    Row(Vec<F>),
+}
+
+/// Builds a trace one row at a time, for trace generators which don't know its height up front.
+///
+/// The width is set by the first row pushed. Capacity doubles whenever it runs out, starting from a
+/// single row, so it is always a power-of-two number of rows and `finalize` can pad the trace
+/// without reallocating.
+#[derive(Clone, Debug)]
+pub struct TraceBuilder<F> {
+    values: Vec<F>,
+    width: Option<usize>,
+}
+
+impl<F> Default for TraceBuilder<F> {
+    fn default() -> Self {
+        Self {
+            values: Vec::new(),
+            width: None,
+        }
+    }
+}
+
+impl<F: Field> TraceBuilder<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The width of the trace, or `None` if no rows have been pushed yet.
+    pub fn width(&self) -> Option<usize> {
+        self.width
+    }
+
+    /// The number of rows pushed so far.
+    pub fn height(&self) -> usize {
+        self.width.map_or(0, |width| self.values.len() / width)
+    }
+
+    /// Append a row to the trace.
+    ///
+    /// # Panics
+    /// Panics if `row` is empty, or if its width differs from that of the first row.
+    pub fn push_row(&mut self, row: &[F]) {
+        let width = *self.width.get_or_insert(row.len());
+        assert!(width > 0, "trace rows must not be empty");
+        assert_eq!(
+            row.len(),
+            width,
+            "row of width {} pushed to a trace of width {width}",
+            row.len()
+        );
+        if self.values.len() == self.values.capacity() {
+            self.values.reserve_exact(self.values.len().max(width));
+        }
+        self.values.extend_from_slice(row);
+    }
+
+    /// Pad the trace to the next power-of-two height according to `pad`, and return it.
+    ///
+    /// # Panics
+    /// Panics if no rows have been pushed, or if `pad` is a row of the wrong width.
+    pub fn finalize(mut self, pad: PadMode<F>) -> RowMajorMatrix<F> {
+        let width = self.width.expect("cannot finalize a trace with no rows");
+        let height = self.height();
+        let padded_len = height.next_power_of_two() * width;
+        match pad {
+            PadMode::Zeros => self.values.resize(padded_len, F::zero()),
+            PadMode::RepeatLastRow => {
+                let last_row = self.values[(height - 1) * width..].to_vec();
+                while self.values.len() < padded_len {
+                    self.values.extend_from_slice(&last_row);
+                }
+            }
+            PadMode::Row(row) => {
+                assert_eq!(
+                    row.len(),
+                    width,
+                    "padding row of width {} for a trace of width {width}",
+                    row.len()
+                );
+                while self.values.len() < padded_len {
+                    self.values.extend_from_slice(&row);
+                }
+            }
+        }
+        RowMajorMatrix::new(self.values, width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use rand::{thread_rng, Rng};
+
+    use crate::dense::RowMajorMatrix;
+    use crate::trace_builder::{PadMode, TraceBuilder};
+
+    type F = BabyBear;
+
+    fn builder_with_rows(rows: &[[u32; 2]]) -> TraceBuilder<F> {
+        let mut builder = TraceBuilder::new();
+        for row in rows {
+            builder.push_row(&row.map(F::from_canonical_u32));
+        }
+        builder
+    }
+
+    fn matrix(values: &[u32]) -> RowMajorMatrix<F> {
+        RowMajorMatrix::new(
+            values.iter().copied().map(F::from_canonical_u32).collect(),
+            2,
+        )
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_width_mismatch() {
+        let mut builder = builder_with_rows(&[[1, 2], [3, 4]]);
+        builder.push_row(&[F::one(); 3]);
+    }
+
+    #[test]
+    fn test_padding() {
+        let rows = [[1, 2], [3, 4], [5, 6]];
+        assert_eq!(
+            builder_with_rows(&rows).finalize(PadMode::Zeros),
+            matrix(&[1, 2, 3, 4, 5, 6, 0, 0])
+        );
+        assert_eq!(
+            builder_with_rows(&rows).finalize(PadMode::RepeatLastRow),
+            matrix(&[1, 2, 3, 4, 5, 6, 5, 6])
+        );
+        let pad_row = vec![F::from_canonical_u32(7), F::from_canonical_u32(8)];
+        assert_eq!(
+            builder_with_rows(&rows[..1]).finalize(PadMode::Row(pad_row.clone())),
+            matrix(&[1, 2])
+        );
+        assert_eq!(
+            builder_with_rows(&[[1, 2]; 5]).finalize(PadMode::Row(pad_row)),
+            matrix(&[1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 7, 8, 7, 8, 7, 8])
+        );
+    }
+
+    #[test]
+    fn test_matches_direct_construction() {
+        let mut rng = thread_rng();
+        let width = 5;
+        let values: Vec<F> = (0..64 * width).map(|_| rng.gen()).collect();
+
+        let mut builder = TraceBuilder::new();
+        for row in values.chunks(width) {
+            builder.push_row(row);
+        }
+        assert_eq!(builder.height(), 64);
+        assert_eq!(
+            builder.finalize(PadMode::Zeros),
+            RowMajorMatrix::new(values, width)
+        );
+    }
+}
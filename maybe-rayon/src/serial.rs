@@ -136,6 +136,21 @@ impl<T: Send> ParallelSliceMut<T> for [T] {
     }
 }
 
+pub trait ParallelExtend<T> {
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoIterator<Item = T>;
+}
+
+impl<T, E: Extend<T>> ParallelExtend<T> for E {
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.extend(par_iter);
+    }
+}
+
 pub trait ParIterExt: Iterator {
     fn find_any<P>(self, predicate: P) -> Option<Self::Item>
     where
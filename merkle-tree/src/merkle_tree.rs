@@ -4,7 +4,7 @@ use core::array;
 use core::cmp::Reverse;
 
 use itertools::Itertools;
-use p3_field::{PackedField, PackedValue};
+use p3_field::{AbstractField, PackedField, PackedValue};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::{Matrix, MatrixRowSlices};
 use p3_maybe_rayon::prelude::*;
@@ -20,6 +20,8 @@ use tracing::instrument;
 #[derive(Serialize, Deserialize)]
 pub struct FieldMerkleTree<F, W, const DIGEST_ELEMS: usize> {
     pub(crate) leaves: Vec<RowMajorMatrix<F>>,
+    /// For each matrix, how many of its consecutive rows each leaf holds.
+    pub(crate) rows_per_leaf: Vec<usize>,
     // Enable serialization for this field whenever the underlying array type supports it (len 1-32).
     #[serde(bound(serialize = "[W; DIGEST_ELEMS]: Serialize"))]
     // Enable deserialization for this field whenever the underlying array type supports it (len 1-32).
@@ -30,12 +32,39 @@ pub struct FieldMerkleTree<F, W, const DIGEST_ELEMS: usize> {
 impl<F: Clone, W: Clone, const DIGEST_ELEMS: usize> FieldMerkleTree<F, W, DIGEST_ELEMS> {
     /// Matrix heights need not be powers of two. However, if the heights of two given matrices
     /// round up to the same power of two, they must be equal.
+    ///
+    /// If no matrices are given, the tree consists of a single default digest, which serves as a
+    /// fixed commitment to the empty batch.
+    pub fn new<P, PW, H, C>(h: &H, c: &C, leaves: Vec<RowMajorMatrix<F>>) -> Self
+    where
+        P: PackedField<Scalar = F>,
+        PW: PackedValue<Value = W>,
+        W: Copy + Default,
+        H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+        H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+        H: Sync,
+        C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+        C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+        C: Sync,
+    {
+        let rows_per_leaf = vec![1; leaves.len()];
+        Self::new_with_rows_per_leaf::<P, PW, H, C>(h, c, leaves, rows_per_leaf)
+    }
+
+    /// Like `new`, but with each leaf matrix made by grouping `rows_per_leaf` consecutive rows of
+    /// the corresponding matrix, which is recorded so the original can be recovered.
     #[instrument(name = "build merkle tree", level = "debug", skip_all,
                  fields(dimensions = alloc::format!("{:?}", leaves.iter().map(|l| l.dimensions()).collect::<Vec<_>>())))]
-    pub fn new<P, PW, H, C>(h: &H, c: &C, leaves: Vec<RowMajorMatrix<F>>) -> Self
+    pub(crate) fn new_with_rows_per_leaf<P, PW, H, C>(
+        h: &H,
+        c: &C,
+        leaves: Vec<RowMajorMatrix<F>>,
+        rows_per_leaf: Vec<usize>,
+    ) -> Self
     where
         P: PackedField<Scalar = F>,
         PW: PackedValue<Value = W>,
+        W: Copy + Default,
         H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
         H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
         H: Sync,
@@ -43,7 +72,13 @@ impl<F: Clone, W: Clone, const DIGEST_ELEMS: usize> FieldMerkleTree<F, W, DIGEST
         C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
         C: Sync,
     {
-        assert!(!leaves.is_empty(), "No matrices given?");
+        if leaves.is_empty() {
+            return Self {
+                leaves,
+                rows_per_leaf,
+                digest_layers: vec![vec![[W::default(); DIGEST_ELEMS]]],
+            };
+        }
 
         assert_eq!(P::WIDTH, PW::WIDTH, "Packing widths must match");
 
@@ -96,6 +131,56 @@ impl<F: Clone, W: Clone, const DIGEST_ELEMS: usize> FieldMerkleTree<F, W, DIGEST
 
         Self {
             leaves,
+            rows_per_leaf,
+            digest_layers,
+        }
+    }
+
+    /// Like `new`, but for a single matrix given as its columns, each of the same length, with each
+    /// leaf holding `rows_per_leaf` consecutive rows.
+    ///
+    /// Leaves are hashed straight from the columns, and written into the row-major matrix kept for
+    /// openings in the same pass, so the columns are never transposed up front. The result is the
+    /// same as grouping the rows of the transposed matrix into leaves and calling `new`.
+    #[instrument(name = "build merkle tree", level = "debug", skip_all)]
+    pub fn new_col_major<P, PW, H, C>(h: &H, c: &C, cols: Vec<Vec<F>>, rows_per_leaf: usize) -> Self
+    where
+        P: PackedField<Scalar = F>,
+        PW: PackedValue<Value = W>,
+        W: Copy + Default,
+        H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+        H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+        H: Sync,
+        C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+        C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+        C: Sync,
+    {
+        assert_eq!(P::WIDTH, PW::WIDTH, "Packing widths must match");
+
+        let width = cols.len();
+        assert!(width > 0, "cannot commit to a matrix with no columns");
+        let height = cols[0].len();
+        assert!(
+            cols.iter().all(|col| col.len() == height),
+            "all columns must have the same length"
+        );
+        assert_eq!(
+            height % rows_per_leaf,
+            0,
+            "matrix height must be a multiple of rows_per_leaf"
+        );
+        let (leaves, digests) =
+            col_major_first_digest_layer::<P, PW, H, DIGEST_ELEMS>(h, cols, rows_per_leaf);
+
+        let mut digest_layers = vec![digests];
+        while digest_layers.last().unwrap().len() > 1 {
+            let next_digests = compress::<PW, C, DIGEST_ELEMS>(digest_layers.last().unwrap(), c);
+            digest_layers.push(next_digests);
+        }
+
+        Self {
+            leaves: vec![leaves],
+            rows_per_leaf: vec![rows_per_leaf],
             digest_layers,
         }
     }
@@ -107,6 +192,33 @@ impl<F: Clone, W: Clone, const DIGEST_ELEMS: usize> FieldMerkleTree<F, W, DIGEST
     {
         self.digest_layers.last().unwrap()[0].into()
     }
+
+    /// Build the top of a tree from the roots of trees over consecutive, equal-height shards of a
+    /// matrix, as produced by `RowMajorMatrix::split_rows`.
+    ///
+    /// The result equals the root of a tree over the whole matrix, provided each shard tree was
+    /// built from that shard alone.
+    pub fn combine_shard_roots<C>(
+        c: &C,
+        roots: &[Hash<F, W, DIGEST_ELEMS>],
+    ) -> Hash<F, W, DIGEST_ELEMS>
+    where
+        W: Copy,
+        C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+    {
+        assert!(
+            roots.len().is_power_of_two(),
+            "the number of shards must be a power of two"
+        );
+        let mut layer: Vec<[W; DIGEST_ELEMS]> = roots.iter().map(|&root| root.into()).collect();
+        while layer.len() > 1 {
+            layer = layer
+                .chunks_exact(2)
+                .map(|pair| c.compress([pair[0], pair[1]]))
+                .collect();
+        }
+        layer[0].into()
+    }
 }
 
 fn first_digest_layer<P, PW, H, const DIGEST_ELEMS: usize>(
@@ -153,6 +265,70 @@ where
     digests
 }
 
+/// Gather the leaves of the matrix with the given columns, each holding `rows_per_leaf` consecutive
+/// rows, returning them as a row-major matrix along with their digests.
+fn col_major_first_digest_layer<P, PW, H, const DIGEST_ELEMS: usize>(
+    h: &H,
+    cols: Vec<Vec<P::Scalar>>,
+    rows_per_leaf: usize,
+) -> (RowMajorMatrix<P::Scalar>, Vec<[PW::Value; DIGEST_ELEMS]>)
+where
+    P: PackedField,
+    PW: PackedValue,
+    H: CryptographicHasher<P::Scalar, [PW::Value; DIGEST_ELEMS]>,
+    H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+    H: Sync,
+{
+    let width = cols.len();
+    let height = cols[0].len();
+    let leaf_width = width * rows_per_leaf;
+    let num_leaves = height / rows_per_leaf;
+
+    // Write the rows starting at `first_row` into `rows`, in row-major order.
+    let gather_rows = |first_row: usize, rows: &mut [P::Scalar]| {
+        for (r, row) in rows.chunks_exact_mut(width).enumerate() {
+            for (x, col) in row.iter_mut().zip(&cols) {
+                *x = col[first_row + r];
+            }
+        }
+    };
+
+    let pack_width = PW::WIDTH;
+    let default_digest: [PW::Value; DIGEST_ELEMS] = [PW::Value::default(); DIGEST_ELEMS];
+    let mut digests = vec![default_digest; num_leaves.next_power_of_two()];
+    let mut values = vec![P::Scalar::zero(); height * width];
+
+    let num_packed_leaves = num_leaves / pack_width * pack_width;
+    digests[..num_packed_leaves]
+        .par_chunks_exact_mut(pack_width)
+        .zip(values.par_chunks_exact_mut(pack_width * leaf_width))
+        .enumerate()
+        .for_each(|(i, (digests_chunk, values_chunk))| {
+            let first_leaf = i * pack_width;
+            let packed_digest: [PW; DIGEST_ELEMS] = h.hash_iter((0..rows_per_leaf).flat_map(|j| {
+                cols.iter()
+                    .map(move |col| P::from_fn(|lane| col[(first_leaf + lane) * rows_per_leaf + j]))
+            }));
+            for (dst, src) in digests_chunk.iter_mut().zip(unpack_array(packed_digest)) {
+                *dst = src;
+            }
+            gather_rows(first_leaf * rows_per_leaf, values_chunk);
+        });
+
+    // If our packing width did not divide the number of leaves, fall back to single-threaded
+    // scalar code for the last bit.
+    for (l, leaf) in values
+        .chunks_exact_mut(leaf_width)
+        .enumerate()
+        .skip(num_packed_leaves)
+    {
+        gather_rows(l * rows_per_leaf, leaf);
+        digests[l] = h.hash_slice(&*leaf);
+    }
+
+    (RowMajorMatrix::new(values, leaf_width), digests)
+}
+
 /// Compress `n` digests from the previous layer into `n/2` digests, while potentially mixing in
 /// some leaf data, if there are input matrices with (padded) height `n/2`.
 fn compress_and_inject<P, PW, H, C, const DIGEST_ELEMS: usize>(
@@ -1,6 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use p3_field::extension::{BinomialExtensionField, Complex};
-use p3_field_testing::bench_func::{benchmark_inv, benchmark_mul, benchmark_square};
+use p3_field_testing::bench_func::{benchmark_binomial_mul, benchmark_inv, benchmark_square};
 use p3_mersenne_31::Mersenne31;
 
 type EF2 = BinomialExtensionField<Complex<Mersenne31>, 2>;
@@ -10,14 +10,14 @@ fn bench_qudratic_extension(c: &mut Criterion) {
     let name = "BinomialExtensionField<Mersenne31Complex<Mersenne31>, 2>";
     benchmark_square::<EF2>(c, name);
     benchmark_inv::<EF2>(c, name);
-    benchmark_mul::<EF2>(c, name);
+    benchmark_binomial_mul::<Complex<Mersenne31>, 2>(c, name);
 }
 
 fn bench_cubic_extension(c: &mut Criterion) {
     let name = "BinomialExtensionField<Mersenne31Complex<Mersenne31>, 3>";
     benchmark_square::<EF3>(c, name);
     benchmark_inv::<EF3>(c, name);
-    benchmark_mul::<EF3>(c, name);
+    benchmark_binomial_mul::<Complex<Mersenne31>, 3>(c, name);
 }
 
 criterion_group!(bench_mersennecomplex_ef2, bench_qudratic_extension);
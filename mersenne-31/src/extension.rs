@@ -100,7 +100,9 @@ impl HasTwoAdicComplexBinomialExtension<3> for Mersenne31 {
 #[cfg(test)]
 mod test_cubic_extension {
     use p3_field::extension::{BinomialExtensionField, Complex};
-    use p3_field_testing::{test_field, test_two_adic_extension_field};
+    use p3_field_testing::{
+        test_binomial_extension_mul, test_field, test_two_adic_extension_field,
+    };
 
     use crate::Mersenne31;
 
@@ -108,6 +110,7 @@ mod test_cubic_extension {
     type EF = BinomialExtensionField<F, 3>;
 
     test_field!(super::EF);
+    test_binomial_extension_mul!(super::F, 3);
 
     test_two_adic_extension_field!(super::F, super::EF);
 }
@@ -116,7 +119,9 @@ mod test_cubic_extension {
 mod test_quadratic_extension {
 
     use p3_field::extension::{BinomialExtensionField, Complex};
-    use p3_field_testing::{test_field, test_two_adic_extension_field};
+    use p3_field_testing::{
+        test_binomial_extension_mul, test_field, test_two_adic_extension_field,
+    };
 
     use crate::Mersenne31;
 
@@ -124,6 +129,7 @@ mod test_quadratic_extension {
     type EF = BinomialExtensionField<F, 2>;
 
     test_field!(super::EF);
+    test_binomial_extension_mul!(super::F, 2);
 
     test_two_adic_extension_field!(super::F, super::EF);
 }
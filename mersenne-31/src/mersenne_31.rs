@@ -4,13 +4,15 @@ use core::hash::{Hash, Hasher};
 use core::iter::{Product, Sum};
 use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
 
+use num_bigint::BigUint;
 use p3_field::{
-    exp_1717986917, exp_u64_by_squaring, AbstractField, Field, Packable, PrimeField, PrimeField32,
-    PrimeField64,
+    exp_1717986917, exp_u64_by_squaring, AbstractField, ConstantTimeField, Field, Packable,
+    PrimeField, PrimeField32, PrimeField64,
 };
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 
 /// The prime field `F_p` where `p = 2^31 - 1`.
 #[derive(Copy, Clone, Default, Serialize, Deserialize)]
@@ -225,9 +227,33 @@ impl Field for Mersenne31 {
             p1111111111111111111111111111.exp_power_of_2(3) * p101;
         Some(p1111111111111111111111111111101)
     }
+
+    fn characteristic() -> BigUint {
+        Self::ORDER_U32.into()
+    }
+}
+
+impl PrimeField for Mersenne31 {
+    fn as_canonical_biguint(&self) -> BigUint {
+        self.as_canonical_u64().into()
+    }
 }
 
-impl PrimeField for Mersenne31 {}
+impl ConstantTimeField for Mersenne31 {
+    #[inline]
+    fn ct_eq(&self, other: &Self) -> Choice {
+        // `value` is canonical except that zero may also be represented by `P`.
+        let canonical = |x: u32| u32::conditional_select(&x, &0, x.ct_eq(&Self::ORDER_U32));
+        canonical(self.value).ct_eq(&canonical(other.value))
+    }
+
+    #[inline]
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self {
+            value: u32::conditional_select(&a.value, &b.value, choice),
+        }
+    }
+}
 
 impl PrimeField32 for Mersenne31 {
     const ORDER_U32: u32 = (1 << 31) - 1;
@@ -384,13 +410,20 @@ fn from_u62(input: u64) -> Mersenne31 {
 
 #[cfg(test)]
 mod tests {
-    use p3_field::{AbstractField, Field, PrimeField32};
-    use p3_field_testing::test_field;
+    use num_bigint::BigUint;
+    use p3_field::{AbstractField, Field, PrimeField, PrimeField32};
+    use p3_field_testing::{test_field, test_prime_field, test_prime_field_64};
 
     use crate::Mersenne31;
 
     type F = Mersenne31;
 
+    #[test]
+    fn test_characteristic() {
+        assert_eq!(F::characteristic(), BigUint::from((1u32 << 31) - 1));
+        assert_eq!(F::order(), F::characteristic());
+    }
+
     #[test]
     fn add() {
         assert_eq!(F::one() + F::one(), F::two());
@@ -440,5 +473,16 @@ mod tests {
         assert_eq!(F::two().exp_u64(1717986917).exp_const_u64::<5>(), F::two());
     }
 
+    #[test]
+    fn test_ct_eq_non_canonical() {
+        // `P` is a non-canonical representation of zero.
+        let p = F::new(F::ORDER_U32);
+        assert!(bool::from(p.ct_eq(&F::zero())));
+        assert!(bool::from(F::zero().ct_eq(&p)));
+        assert!(!bool::from(p.ct_eq(&F::one())));
+    }
+
     test_field!(crate::Mersenne31);
+    test_prime_field!(crate::Mersenne31);
+    test_prime_field_64!(crate::Mersenne31);
 }
@@ -0,0 +1,23 @@
+//! A `no_std` crate which instantiates the STARK verifier with a concrete configuration, to check
+//! that verification only requires `alloc`. The configuration and AIR are the ones exposed by
+//! `p3-wasm-verifier`. Build it for a target without `std`, e.g.
+//!
+//! ```sh
+//! rustup target add thumbv7em-none-eabihf
+//! cargo build -p p3-no-std-check --target thumbv7em-none-eabihf
+//! ```
+
+#![no_std]
+
+use p3_uni_stark::{verify, Proof, VerificationError, VerifyingKey};
+use p3_wasm_verifier::{Challenger, MulAir, MyConfig, Perm};
+
+pub fn verify_mul_air(
+    config: &MyConfig,
+    vk: &VerifyingKey<MyConfig>,
+    perm: Perm,
+    proof: &Proof<MyConfig>,
+) -> Result<(), VerificationError> {
+    let mut challenger = Challenger::new(perm);
+    verify(config, vk, &MulAir, &mut challenger, proof)
+}
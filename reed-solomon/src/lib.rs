@@ -11,6 +11,10 @@ use p3_field::Field;
 use p3_lde::UndefinedLde;
 use p3_matrix::MatrixRows;
 
+mod two_adic;
+
+pub use two_adic::*;
+
 /// A Reed-Solomon code based on an `UndefinedLde`.
 pub struct UndefinedReedSolomonCode<F, L, In>
 where
@@ -0,0 +1,208 @@
+use p3_dft::TwoAdicSubgroupDft;
+use p3_field::TwoAdicField;
+use p3_util::log2_strict_usize;
+
+/// Encode `message`, taken as the coefficients of a polynomial, by evaluating it over the canonical
+/// subgroup whose size is `2^log_blowup` times the message length rounded up to a power of two.
+pub fn encode<F, Dft>(dft: &Dft, message: &[F], log_blowup: usize) -> Vec<F>
+where
+    F: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<F>,
+{
+    let codeword_len = message.len().next_power_of_two() << log_blowup;
+    let mut coeffs = message.to_vec();
+    coeffs.resize(codeword_len, F::zero());
+    dft.dft(coeffs)
+}
+
+/// Recover the `message_len` coefficients of a message from `received`, an `encode`d codeword in
+/// which at most `num_errors` entries may have been corrupted, using Berlekamp–Welch.
+///
+/// Returns `None` if no message is within `num_errors` of `received`, i.e. if more errors than that
+/// occurred (though too many errors may also lead to another message being returned, when
+/// `received` is close to its codeword instead). Decoding takes cubic time in the codeword length.
+///
+/// # Panics
+/// Panics if the length of `received` isn't a power of two, as that of any codeword from `encode`
+/// is. Also panics unless `message_len + 2 * num_errors <= received.len()`, beyond which errors
+/// can't be corrected unambiguously.
+pub fn decode<F: TwoAdicField>(
+    received: &[F],
+    message_len: usize,
+    num_errors: usize,
+) -> Option<Vec<F>> {
+    let n = received.len();
+    assert!(
+        n.is_power_of_two(),
+        "received a codeword of length {n}, which isn't a power of two"
+    );
+    assert!(
+        message_len + 2 * num_errors <= n,
+        "a codeword of length {n} can't correct {num_errors} errors in a message of length {message_len}"
+    );
+    let points: Vec<F> = F::two_adic_generator(log2_strict_usize(n))
+        .powers()
+        .take(n)
+        .collect();
+
+    // Find an error locator `E`, monic of degree `num_errors`, and `Q = E * P` of degree less than
+    // `message_len + num_errors`, such that `Q(x) = y * E(x)` at every point. The unknowns are the
+    // coefficients of `Q`, followed by those of `E` apart from its leading one.
+    let q_len = message_len + num_errors;
+    let rows: Vec<Vec<F>> = points
+        .iter()
+        .zip(received)
+        .map(|(&x, &y)| {
+            let mut row: Vec<F> = x.powers().take(q_len).collect();
+            row.extend(x.powers().take(num_errors).map(|x_j| -y * x_j));
+            row.push(y * x.exp_u64(num_errors as u64));
+            row
+        })
+        .collect();
+    let solution = solve_linear_system(rows, q_len + num_errors)?;
+    let q = &solution[..q_len];
+    let mut e = solution[q_len..].to_vec();
+    e.push(F::one());
+
+    let message = divide_exact(q, &e)?;
+    let num_mismatches = points
+        .iter()
+        .zip(received)
+        .filter(|&(&x, &y)| evaluate(&message, x) != y)
+        .count();
+    (num_mismatches <= num_errors).then_some(message)
+}
+
+/// Find a solution to the linear system whose augmented matrix has the given rows, each of which
+/// has `num_vars` coefficients followed by a constant, setting any free variables to zero.
+/// Returns `None` if the system is inconsistent.
+fn solve_linear_system<F: TwoAdicField>(mut rows: Vec<Vec<F>>, num_vars: usize) -> Option<Vec<F>> {
+    let mut pivots = Vec::new();
+    for col in 0..num_vars {
+        let rank = pivots.len();
+        let Some(pivot) = (rank..rows.len()).find(|&r| !rows[r][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        let inv = rows[rank][col].inverse();
+        rows[rank].iter_mut().for_each(|x| *x *= inv);
+        let pivot_row = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if r != rank && !factor.is_zero() {
+                row.iter_mut()
+                    .zip(&pivot_row)
+                    .for_each(|(x, &p)| *x -= factor * p);
+            }
+        }
+        pivots.push(col);
+    }
+
+    // Every row past the rank has all zero coefficients, so its constant must be zero too.
+    if rows[pivots.len()..]
+        .iter()
+        .any(|row| !row[num_vars].is_zero())
+    {
+        return None;
+    }
+    let mut solution = vec![F::zero(); num_vars];
+    for (row, &col) in rows.iter().zip(&pivots) {
+        solution[col] = row[num_vars];
+    }
+    Some(solution)
+}
+
+/// Divide `numerator` by the monic polynomial `denominator`, both given by their coefficients,
+/// returning the quotient truncated to `numerator.len() + 1 - denominator.len()` coefficients, or
+/// `None` if there is a remainder.
+fn divide_exact<F: TwoAdicField>(numerator: &[F], denominator: &[F]) -> Option<Vec<F>> {
+    let d = denominator.len() - 1;
+    let mut remainder = numerator.to_vec();
+    let mut quotient = vec![F::zero(); numerator.len() - d];
+    for i in (0..quotient.len()).rev() {
+        let coeff = remainder[i + d];
+        quotient[i] = coeff;
+        remainder[i..=i + d]
+            .iter_mut()
+            .zip(denominator)
+            .for_each(|(r, &c)| *r -= coeff * c);
+    }
+    remainder.iter().all(|r| r.is_zero()).then_some(quotient)
+}
+
+fn evaluate<F: TwoAdicField>(coeffs: &[F], x: F) -> F {
+    coeffs.iter().rev().fold(F::zero(), |acc, &c| acc * x + c)
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_dft::Radix2Dit;
+    use p3_field::AbstractField;
+    use rand::seq::index::sample;
+    use rand::{thread_rng, Rng};
+
+    use crate::{decode, encode};
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = thread_rng();
+        let message: Vec<F> = (0..12).map(|_| rng.gen()).collect();
+        let codeword = encode(&Radix2Dit::default(), &message, 1);
+        assert_eq!(codeword.len(), 32);
+        assert_eq!(decode(&codeword, message.len(), 0), Some(message.clone()));
+        assert_eq!(decode(&codeword, message.len(), 10), Some(message));
+    }
+
+    #[test]
+    fn test_corrects_errors() {
+        let mut rng = thread_rng();
+        let message: Vec<F> = (0..8).map(|_| rng.gen()).collect();
+        let codeword = encode(&Radix2Dit::default(), &message, 2);
+        let max_errors = (codeword.len() - message.len()) / 2;
+
+        for num_errors in 1..=max_errors {
+            let mut received = codeword.clone();
+            for i in sample(&mut rng, received.len(), num_errors) {
+                received[i] += F::from_canonical_u32(rng.gen_range(1..1000));
+            }
+            assert_eq!(
+                decode(&received, message.len(), max_errors),
+                Some(message.clone())
+            );
+            assert_eq!(
+                decode(&received, message.len(), num_errors),
+                Some(message.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn test_too_many_errors() {
+        let mut rng = thread_rng();
+        let message: Vec<F> = (0..8).map(|_| rng.gen()).collect();
+        let codeword = encode(&Radix2Dit::default(), &message, 2);
+
+        let mut received = codeword;
+        for i in sample(&mut rng, received.len(), 5) {
+            received[i] += F::from_canonical_u32(rng.gen_range(1..1000));
+        }
+        assert_eq!(decode(&received, message.len(), 4), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "can't correct 5 errors")]
+    fn test_unsupported_num_errors() {
+        let codeword = encode(&Radix2Dit::default(), &[F::from_canonical_u32(1); 8], 1);
+        decode(&codeword, 8, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "received a codeword of length 12, which isn't a power of two")]
+    fn test_non_power_of_two_length() {
+        let codeword = encode(&Radix2Dit::default(), &[F::from_canonical_u32(1); 4], 2);
+        decode(&codeword[..12], 4, 2);
+    }
+}
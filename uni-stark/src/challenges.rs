@@ -0,0 +1,114 @@
+use alloc::vec::Vec;
+
+use p3_air::StageShape;
+use p3_challenger::{CanObserve, FieldChallenger};
+
+use crate::{
+    Com, Commitments, StarkGenericConfig, FRI_LABEL, LATER_STAGE_COMMIT_LABEL,
+    PREPROCESSED_COMMIT_LABEL, PUBLIC_VALUES_LABEL, QUOTIENT_COMMIT_LABEL, TRACE_COMMIT_LABEL,
+};
+
+/// The challenges drawn by the STARK itself, in transcript order. FRI's challenges are drawn
+/// afterwards by the PCS, starting from the transcript state `sample` leaves behind.
+pub struct StarkChallenges<SC: StarkGenericConfig> {
+    /// For each witness stage after the main trace, the challenges drawn before generating it.
+    pub later_stage_challenges: Vec<Vec<SC::Challenge>>,
+    /// The randomness used to fold constraints, drawn after all trace commitments.
+    pub alpha: SC::Challenge,
+    /// The out-of-domain point, drawn after the quotient commitment.
+    pub zeta: SC::Challenge,
+}
+
+impl<SC: StarkGenericConfig> StarkChallenges<SC> {
+    /// Replay the transcript of a proof with the given later stages. The caller must check that
+    /// `commitments` has one commitment for each of `later_stages`.
+    ///
+    /// `preprocessed_commit` comes from the verifying key rather than the proof, and is `None` for
+    /// AIRs without a preprocessed trace.
+    pub fn sample(
+        challenger: &mut SC::Challenger,
+        preprocessed_commit: Option<&Com<SC>>,
+        commitments: &Commitments<Com<SC>>,
+        public_values: &[SC::Val],
+        later_stages: &[StageShape],
+    ) -> Self {
+        if let Some(commit) = preprocessed_commit {
+            Self::observe_preprocessed(challenger, commit);
+        }
+        Self::observe_public_values(challenger, public_values);
+        Self::observe_trace(challenger, &commitments.trace);
+        let later_stage_challenges = later_stages
+            .iter()
+            .zip(&commitments.later_stages)
+            .map(|(shape, commit)| {
+                let challenges = Self::sample_later_stage_challenges(challenger, shape);
+                Self::observe_later_stage(challenger, commit);
+                challenges
+            })
+            .collect();
+        let alpha = Self::sample_alpha(challenger);
+        let zeta = Self::sample_zeta(challenger, &commitments.quotient_chunks);
+        Self {
+            later_stage_challenges,
+            alpha,
+            zeta,
+        }
+    }
+
+    // The prover needs each challenge before it can make the next commitment, so it runs these
+    // steps one at a time.
+
+    pub(crate) fn observe_preprocessed(
+        challenger: &mut SC::Challenger,
+        preprocessed_commit: &Com<SC>,
+    ) {
+        challenger.observe_label(PREPROCESSED_COMMIT_LABEL);
+        challenger.observe(preprocessed_commit.clone());
+    }
+
+    pub(crate) fn observe_trace(challenger: &mut SC::Challenger, trace_commit: &Com<SC>) {
+        challenger.observe_label(TRACE_COMMIT_LABEL);
+        challenger.observe(trace_commit.clone());
+    }
+
+    /// Observes nothing if there are no public values, so that proofs without any keep the same
+    /// transcript.
+    pub(crate) fn observe_public_values(
+        challenger: &mut SC::Challenger,
+        public_values: &[SC::Val],
+    ) {
+        if !public_values.is_empty() {
+            challenger.observe_label(PUBLIC_VALUES_LABEL);
+            challenger.observe_slice(public_values);
+        }
+    }
+
+    pub(crate) fn sample_later_stage_challenges(
+        challenger: &mut SC::Challenger,
+        shape: &StageShape,
+    ) -> Vec<SC::Challenge> {
+        (0..shape.num_challenges)
+            .map(|_| challenger.sample_ext_element())
+            .collect()
+    }
+
+    pub(crate) fn observe_later_stage(challenger: &mut SC::Challenger, stage_commit: &Com<SC>) {
+        challenger.observe_label(LATER_STAGE_COMMIT_LABEL);
+        challenger.observe(stage_commit.clone());
+    }
+
+    pub(crate) fn sample_alpha(challenger: &mut SC::Challenger) -> SC::Challenge {
+        challenger.sample_ext_element()
+    }
+
+    pub(crate) fn sample_zeta(
+        challenger: &mut SC::Challenger,
+        quotient_commit: &Com<SC>,
+    ) -> SC::Challenge {
+        challenger.observe_label(QUOTIENT_COMMIT_LABEL);
+        challenger.observe(quotient_commit.clone());
+        let zeta = challenger.sample_ext_element();
+        challenger.observe_label(FRI_LABEL);
+        zeta
+    }
+}
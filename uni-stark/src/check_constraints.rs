@@ -1,16 +1,31 @@
-use p3_air::{Air, AirBuilder, TwoRowMatrixView};
-use p3_field::Field;
+use alloc::vec::Vec;
+
+use itertools::Itertools;
+use p3_air::{
+    Air, AirBuilder, AirBuilderWithPublicValues, ExtensionBuilder, MultiStageAirBuilder,
+    PairBuilder, PeriodicAirBuilder, RowPointAirBuilder, TwoRowMatrixView,
+};
+use p3_field::{ExtensionField, Field, TwoAdicField};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::{Matrix, MatrixRowSlices};
+use p3_util::log2_strict_usize;
 use tracing::instrument;
 
 #[instrument(name = "check constraints", skip_all)]
-pub(crate) fn check_constraints<F, A>(air: &A, main: &RowMajorMatrix<F>)
-where
-    F: Field,
-    A: for<'a> Air<DebugConstraintBuilder<'a, F>>,
+pub(crate) fn check_constraints<F, EF, A>(
+    air: &A,
+    main: &RowMajorMatrix<F>,
+    later_stages: &[RowMajorMatrix<EF>],
+    later_stage_challenges: &[Vec<EF>],
+    preprocessed: Option<&RowMajorMatrix<F>>,
+    public_values: &[F],
+) where
+    F: TwoAdicField,
+    EF: ExtensionField<F>,
+    A: for<'a> Air<DebugConstraintBuilder<'a, F, EF>>,
 {
     let height = main.height();
+    let g_subgroup = F::two_adic_generator(log2_strict_usize(height));
 
     (0..height).for_each(|i| {
         let i_next = (i + 1) % height;
@@ -21,13 +36,31 @@ where
             local: main_local,
             next: main_next,
         };
+        let later_stages = later_stages
+            .iter()
+            .map(|stage| TwoRowMatrixView {
+                local: stage.row_slice(i),
+                next: stage.row_slice(i_next),
+            })
+            .collect_vec();
+        let preprocessed = preprocessed.map_or(TwoRowMatrixView::new(&[], &[]), |preprocessed| {
+            TwoRowMatrixView {
+                local: preprocessed.row_slice(i),
+                next: preprocessed.row_slice(i_next),
+            }
+        });
 
         let mut builder = DebugConstraintBuilder {
             row_index: i,
             main,
+            later_stages,
+            later_stage_challenges,
+            preprocessed,
+            public_values,
             is_first_row: F::from_bool(i == 0),
             is_last_row: F::from_bool(i == height - 1),
             is_transition: F::from_bool(i != height - 1),
+            row_point: g_subgroup.exp_u64(i as u64),
         };
 
         air.eval(&mut builder);
@@ -36,17 +69,23 @@ where
 
 /// An `AirBuilder` which asserts that each constraint is zero, allowing any failed constraints to
 /// be detected early.
-pub struct DebugConstraintBuilder<'a, F: Field> {
+pub struct DebugConstraintBuilder<'a, F: Field, EF: ExtensionField<F>> {
     row_index: usize,
     main: TwoRowMatrixView<'a, F>,
+    later_stages: Vec<TwoRowMatrixView<'a, EF>>,
+    later_stage_challenges: &'a [Vec<EF>],
+    preprocessed: TwoRowMatrixView<'a, F>,
+    public_values: &'a [F],
     is_first_row: F,
     is_last_row: F,
     is_transition: F,
+    row_point: F,
 }
 
-impl<'a, F> AirBuilder for DebugConstraintBuilder<'a, F>
+impl<'a, F, EF> AirBuilder for DebugConstraintBuilder<'a, F, EF>
 where
     F: Field,
+    EF: ExtensionField<F>,
 {
     type F = F;
     type Expr = F;
@@ -82,6 +121,22 @@ where
         );
     }
 
+    fn assert_zero_many<I, It>(&mut self, exprs: It)
+    where
+        I: Into<Self::Expr>,
+        It: IntoIterator<Item = I>,
+    {
+        for (i, x) in exprs.into_iter().enumerate() {
+            assert_eq!(
+                x.into(),
+                F::zero(),
+                "constraint {} of batch had nonzero value on row {}",
+                i,
+                self.row_index
+            );
+        }
+    }
+
     fn assert_eq<I1: Into<Self::Expr>, I2: Into<Self::Expr>>(&mut self, x: I1, y: I2) {
         let x = x.into();
         let y = y.into();
@@ -92,3 +147,172 @@ where
         );
     }
 }
+
+impl<'a, F: Field, EF: ExtensionField<F>> PeriodicAirBuilder for DebugConstraintBuilder<'a, F, EF> {
+    fn is_periodic(&self, period: usize, offset: usize) -> Self::Expr {
+        F::from_bool(self.row_index % period == offset)
+    }
+}
+
+impl<'a, F: Field, EF: ExtensionField<F>> RowPointAirBuilder for DebugConstraintBuilder<'a, F, EF> {
+    fn row_point(&self) -> Self::Expr {
+        self.row_point
+    }
+}
+
+impl<'a, F: Field, EF: ExtensionField<F>> ExtensionBuilder for DebugConstraintBuilder<'a, F, EF> {
+    type EF = EF;
+    type ExprEF = EF;
+    type VarEF = EF;
+
+    fn assert_zero_ext<I>(&mut self, x: I)
+    where
+        I: Into<Self::ExprEF>,
+    {
+        assert_eq!(
+            x.into(),
+            EF::zero(),
+            "constraints had nonzero value on row {}",
+            self.row_index
+        );
+    }
+}
+
+impl<'a, F: Field, EF: ExtensionField<F>> MultiStageAirBuilder
+    for DebugConstraintBuilder<'a, F, EF>
+{
+    type MS = TwoRowMatrixView<'a, EF>;
+
+    fn later_stage(&self, stage: usize) -> Self::MS {
+        self.later_stages[stage]
+    }
+
+    fn later_stage_challenges(&self, stage: usize) -> &[Self::ExprEF] {
+        &self.later_stage_challenges[stage]
+    }
+}
+
+impl<'a, F: Field, EF: ExtensionField<F>> AirBuilderWithPublicValues
+    for DebugConstraintBuilder<'a, F, EF>
+{
+    fn public_values(&self) -> &[Self::Expr] {
+        self.public_values
+    }
+}
+
+impl<'a, F: Field, EF: ExtensionField<F>> PairBuilder for DebugConstraintBuilder<'a, F, EF> {
+    fn preprocessed(&self) -> Self::M {
+        self.preprocessed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_air::BaseAir;
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+
+    use super::*;
+
+    /// Asserts that `a * b = c` for each of two triples of columns, as a single batch.
+    struct BatchedMulAir;
+
+    impl<F> BaseAir<F> for BatchedMulAir {
+        fn width(&self) -> usize {
+            6
+        }
+    }
+
+    impl<AB: AirBuilder> Air<AB> for BatchedMulAir {
+        fn eval(&self, builder: &mut AB) {
+            let main = builder.main();
+            let local = main.row_slice(0);
+            builder.assert_zero_many(local.chunks_exact(3).map(|abc| abc[0] * abc[1] - abc[2]));
+        }
+    }
+
+    /// Asserts that the first row equals the public values.
+    struct PublicFirstRowAir;
+
+    impl<F> BaseAir<F> for PublicFirstRowAir {
+        fn width(&self) -> usize {
+            2
+        }
+
+        fn num_public_values(&self) -> usize {
+            2
+        }
+    }
+
+    impl<AB: AirBuilderWithPublicValues> Air<AB> for PublicFirstRowAir {
+        fn eval(&self, builder: &mut AB) {
+            let main = builder.main();
+            let local = main.row_slice(0);
+            builder
+                .when_first_row()
+                .assert_eq_public_values(local.iter().copied());
+        }
+    }
+
+    /// These AIRs have no later stages.
+    const NO_CHALLENGES: &[Vec<BabyBear>] = &[];
+
+    fn trace(values: &[u32]) -> RowMajorMatrix<BabyBear> {
+        let values = values.iter().map(|&v| BabyBear::from_canonical_u32(v));
+        RowMajorMatrix::new(values.collect(), 6)
+    }
+
+    #[test]
+    fn test_assert_zero_many() {
+        check_constraints(
+            &BatchedMulAir,
+            &trace(&[2, 3, 6, 4, 5, 20, 0, 7, 0, 1, 1, 1]),
+            &[],
+            NO_CHALLENGES,
+            None,
+            &[],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "constraint 1 of batch had nonzero value on row 1")]
+    fn test_assert_zero_many_one_nonzero() {
+        check_constraints(
+            &BatchedMulAir,
+            &trace(&[2, 3, 6, 4, 5, 20, 0, 7, 0, 1, 1, 2]),
+            &[],
+            NO_CHALLENGES,
+            None,
+            &[],
+        );
+    }
+
+    #[test]
+    fn test_assert_eq_public_values() {
+        let trace = RowMajorMatrix::new([7, 9, 1, 2].map(BabyBear::from_canonical_u32).to_vec(), 2);
+        let public_values = [7, 9].map(BabyBear::from_canonical_u32);
+        check_constraints(
+            &PublicFirstRowAir,
+            &trace,
+            &[],
+            NO_CHALLENGES,
+            None,
+            &public_values,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "constraints had nonzero value on row 0")]
+    fn test_assert_eq_public_values_mismatch() {
+        let trace = RowMajorMatrix::new([7, 9, 1, 2].map(BabyBear::from_canonical_u32).to_vec(), 2);
+        let public_values = [7, 8].map(BabyBear::from_canonical_u32);
+        check_constraints(
+            &PublicFirstRowAir,
+            &trace,
+            &[],
+            NO_CHALLENGES,
+            None,
+            &public_values,
+        );
+    }
+}
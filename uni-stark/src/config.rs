@@ -2,7 +2,7 @@ use core::marker::PhantomData;
 
 use p3_challenger::{CanObserve, FieldChallenger};
 use p3_commit::{Pcs, UnivariatePcsWithLde};
-use p3_field::{ExtensionField, Field, TwoAdicField};
+use p3_field::{AbstractField, ExtensionField, Field, TwoAdicField};
 use p3_matrix::dense::RowMajorMatrix;
 
 pub type PackedVal<SC> = <<SC as StarkGenericConfig>::Val as Field>::Packing;
@@ -30,17 +30,62 @@ pub trait StarkGenericConfig {
         + CanObserve<<Self::Pcs as Pcs<Self::Val, RowMajorMatrix<Self::Val>>>::Commitment>;
 
     fn pcs(&self) -> &Self::Pcs;
+
+    /// The shift `s` of the coset `s H` over which the trace is interpreted, where `H` is the
+    /// two-adic subgroup whose order matches the trace height. Row `i` of the trace is the
+    /// evaluation at `s g_H^i`.
+    ///
+    /// `s H` must be disjoint from the PCS's LDE coset, since the prover divides constraints by
+    /// `Z_{s H}` over that coset.
+    fn trace_domain_shift(&self) -> Self::Val {
+        Self::Val::one()
+    }
 }
 
 pub struct StarkConfig<Val, Challenge, Pcs, Challenger> {
     pcs: Pcs,
+    trace_domain_shift: Val,
     _phantom: PhantomData<(Val, Challenge, Challenger)>,
 }
 
-impl<Val, Challenge, Pcs, Challenger> StarkConfig<Val, Challenge, Pcs, Challenger> {
+impl<Val: Field, Challenge, Pcs, Challenger> StarkConfig<Val, Challenge, Pcs, Challenger> {
     pub fn new(pcs: Pcs) -> Self {
         Self {
             pcs,
+            trace_domain_shift: Val::one(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Val, Challenge, Pcs, Challenger> StarkConfig<Val, Challenge, Pcs, Challenger>
+where
+    Val: TwoAdicField,
+    Challenge: ExtensionField<Val>,
+    Pcs: UnivariatePcsWithLde<Val, Challenge, RowMajorMatrix<Val>, Challenger>,
+    Challenger: FieldChallenger<Val>,
+{
+    /// Like `new`, but interprets traces over the coset `trace_domain_shift * H` rather than the
+    /// subgroup `H`.
+    ///
+    /// # Panics
+    /// Panics if `trace_domain_shift * H` meets the PCS's LDE coset `s K` for some trace height,
+    /// i.e. if `s / trace_domain_shift` lies in the largest two-adic subgroup, as it does when
+    /// `trace_domain_shift = s`.
+    pub fn new_with_trace_domain_shift(pcs: Pcs, trace_domain_shift: Val) -> Self {
+        assert!(
+            !trace_domain_shift.is_zero(),
+            "the trace domain shift must be nonzero"
+        );
+        let ratio = pcs.coset_shift() / trace_domain_shift;
+        assert!(
+            ratio.exp_power_of_2(Val::TWO_ADICITY) != Val::one(),
+            "the trace domain shift {trace_domain_shift} makes the trace domain overlap the PCS's \
+             LDE coset"
+        );
+        Self {
+            pcs,
+            trace_domain_shift,
             _phantom: PhantomData,
         }
     }
@@ -63,4 +108,8 @@ where
     fn pcs(&self) -> &Self::Pcs {
         &self.pcs
     }
+
+    fn trace_domain_shift(&self) -> Val {
+        self.trace_domain_shift
+    }
 }
@@ -0,0 +1,399 @@
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+use p3_air::Air;
+use p3_field::Field;
+
+use crate::symbolic_builder::{get_symbolic_constraints, SymbolicAirBuilder};
+use crate::symbolic_expression::SymbolicExpression;
+
+/// A value read from the evaluation window by a `ConstraintProgram`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProgramInput {
+    /// A column of the local row. Columns of later stages follow those of `main`, in stage order,
+    /// and are followed by those of the preprocessed trace.
+    Local(usize),
+    /// A column of the next row, numbered as in `Local`.
+    Next(usize),
+    IsFirstRow,
+    IsLastRow,
+    IsTransition,
+    /// The point `g^r` at the current row `r`.
+    RowPoint,
+    /// The selector for the rows `r` with `r % period == offset`.
+    IsPeriodic {
+        period: usize,
+        offset: usize,
+    },
+    /// The `index`th challenge drawn before committing to the given later stage.
+    Challenge {
+        stage: usize,
+        index: usize,
+    },
+    /// The `index`th public value of the proof.
+    PublicValue(usize),
+}
+
+/// A gate in a `ConstraintProgram`. Operands are the indices of earlier gates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Instruction<F> {
+    Input(ProgramInput),
+    Constant(F),
+    Add(usize, usize),
+    Sub(usize, usize),
+    Neg(usize),
+    Mul(usize, usize),
+}
+
+/// An AIR's constraints, lowered to a DAG of arithmetic gates so that they can be evaluated
+/// without the AIR's code, e.g. by a recursive verifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintProgram<F> {
+    /// The gates, in topological order.
+    pub instructions: Vec<Instruction<F>>,
+    /// For each constraint, in the order the AIR asserted them, the gate holding its value.
+    pub constraints: Vec<usize>,
+}
+
+/// The values a `ConstraintProgram` reads, for one row of the trace.
+pub struct ProgramWindow<'a, F> {
+    pub local: &'a [F],
+    pub next: &'a [F],
+    pub is_first_row: F,
+    pub is_last_row: F,
+    pub is_transition: F,
+    pub row_point: F,
+    /// The value of each periodic selector the program reads, keyed by `(period, offset)`.
+    pub periodic_selectors: &'a [((usize, usize), F)],
+    pub later_stage_challenges: &'a [Vec<F>],
+    pub public_values: &'a [F],
+}
+
+impl<'a, F: Copy> ProgramWindow<'a, F> {
+    fn get(&self, input: ProgramInput) -> F {
+        match input {
+            ProgramInput::Local(column) => self.local[column],
+            ProgramInput::Next(column) => self.next[column],
+            ProgramInput::IsFirstRow => self.is_first_row,
+            ProgramInput::IsLastRow => self.is_last_row,
+            ProgramInput::IsTransition => self.is_transition,
+            ProgramInput::RowPoint => self.row_point,
+            ProgramInput::IsPeriodic { period, offset } => {
+                find_periodic_selector(self.periodic_selectors, period, offset)
+            }
+            ProgramInput::Challenge { stage, index } => self.later_stage_challenges[stage][index],
+            ProgramInput::PublicValue(index) => self.public_values[index],
+        }
+    }
+}
+
+/// Lowers the constraints of `air` to a `ConstraintProgram`, by evaluating them symbolically.
+pub fn compile_air<F, A>(air: &A) -> ConstraintProgram<F>
+where
+    F: Field,
+    A: Air<SymbolicAirBuilder<F>>,
+{
+    let constraints = get_symbolic_constraints(air);
+    let mut compiler = ConstraintCompiler::default();
+    let constraints = constraints.iter().map(|c| compiler.compile(c)).collect();
+    ConstraintProgram {
+        instructions: compiler.instructions,
+        constraints,
+    }
+}
+
+/// The `(period, offset)` of each periodic selector read by `air`'s constraints, in order of first
+/// use.
+pub fn get_periodic_selectors<F, A>(air: &A) -> Vec<(usize, usize)>
+where
+    F: Field,
+    A: Air<SymbolicAirBuilder<F>>,
+{
+    compile_air(air)
+        .instructions
+        .into_iter()
+        .filter_map(|instruction| match instruction {
+            Instruction::Input(ProgramInput::IsPeriodic { period, offset }) => {
+                Some((period, offset))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Looks up the periodic selector for `(period, offset)` among those computed for an AIR by
+/// `get_periodic_selectors`.
+pub(crate) fn find_periodic_selector<F: Copy>(
+    selectors: &[((usize, usize), F)],
+    period: usize,
+    offset: usize,
+) -> F {
+    selectors
+        .iter()
+        .find(|(key, _)| *key == (period, offset))
+        .map(|&(_, value)| value)
+        .unwrap_or_else(|| panic!("no periodic selector for period {period}, offset {offset}"))
+}
+
+/// Evaluates each constraint of `program` on the given window, in the order the AIR asserted them.
+pub fn eval_program<F: Field>(
+    program: &ConstraintProgram<F>,
+    window: &ProgramWindow<'_, F>,
+) -> Vec<F> {
+    let mut values = Vec::with_capacity(program.instructions.len());
+    for instruction in &program.instructions {
+        let value = match *instruction {
+            Instruction::Input(input) => window.get(input),
+            Instruction::Constant(c) => c,
+            Instruction::Add(x, y) => values[x] + values[y],
+            Instruction::Sub(x, y) => values[x] - values[y],
+            Instruction::Neg(x) => -values[x],
+            Instruction::Mul(x, y) => values[x] * values[y],
+        };
+        values.push(value);
+    }
+    program
+        .constraints
+        .iter()
+        .map(|&gate| values[gate])
+        .collect()
+}
+
+/// Emits gates for symbolic expressions. Inputs are emitted once each, and subexpressions which
+/// the symbolic builder shares by reference are emitted once rather than once per use.
+struct ConstraintCompiler<F: Field> {
+    instructions: Vec<Instruction<F>>,
+    inputs: BTreeMap<ProgramInput, usize>,
+    shared: BTreeMap<*const SymbolicExpression<F>, usize>,
+}
+
+impl<F: Field> Default for ConstraintCompiler<F> {
+    fn default() -> Self {
+        Self {
+            instructions: Vec::new(),
+            inputs: BTreeMap::new(),
+            shared: BTreeMap::new(),
+        }
+    }
+}
+
+impl<F: Field> ConstraintCompiler<F> {
+    fn compile(&mut self, expr: &SymbolicExpression<F>) -> usize {
+        match expr {
+            SymbolicExpression::Variable(v) if v.is_next => {
+                self.input(ProgramInput::Next(v.column))
+            }
+            SymbolicExpression::Variable(v) => self.input(ProgramInput::Local(v.column)),
+            SymbolicExpression::IsFirstRow => self.input(ProgramInput::IsFirstRow),
+            SymbolicExpression::IsLastRow => self.input(ProgramInput::IsLastRow),
+            SymbolicExpression::IsTransition => self.input(ProgramInput::IsTransition),
+            SymbolicExpression::RowPoint => self.input(ProgramInput::RowPoint),
+            SymbolicExpression::IsPeriodic { period, offset } => {
+                self.input(ProgramInput::IsPeriodic {
+                    period: *period,
+                    offset: *offset,
+                })
+            }
+            SymbolicExpression::Challenge { stage, index } => self.input(ProgramInput::Challenge {
+                stage: *stage,
+                index: *index,
+            }),
+            SymbolicExpression::PublicValue(index) => self.input(ProgramInput::PublicValue(*index)),
+            SymbolicExpression::Constant(c) => self.push(Instruction::Constant(*c)),
+            SymbolicExpression::Add { x, y, .. } => {
+                let (x, y) = (self.compile_shared(x), self.compile_shared(y));
+                self.push(Instruction::Add(x, y))
+            }
+            SymbolicExpression::Sub { x, y, .. } => {
+                let (x, y) = (self.compile_shared(x), self.compile_shared(y));
+                self.push(Instruction::Sub(x, y))
+            }
+            SymbolicExpression::Neg { x, .. } => {
+                let x = self.compile_shared(x);
+                self.push(Instruction::Neg(x))
+            }
+            SymbolicExpression::Mul { x, y, .. } => {
+                let (x, y) = (self.compile_shared(x), self.compile_shared(y));
+                self.push(Instruction::Mul(x, y))
+            }
+        }
+    }
+
+    fn compile_shared(&mut self, expr: &Rc<SymbolicExpression<F>>) -> usize {
+        let key = Rc::as_ptr(expr);
+        if let Some(&gate) = self.shared.get(&key) {
+            return gate;
+        }
+        let gate = self.compile(expr);
+        self.shared.insert(key, gate);
+        gate
+    }
+
+    fn input(&mut self, input: ProgramInput) -> usize {
+        if let Some(&gate) = self.inputs.get(&input) {
+            return gate;
+        }
+        let gate = self.push(Instruction::Input(input));
+        self.inputs.insert(input, gate);
+        gate
+    }
+
+    fn push(&mut self, instruction: Instruction<F>) -> usize {
+        self.instructions.push(instruction);
+        self.instructions.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use p3_air::{Air, AirBuilder, BaseAir, RowPointAirBuilder, TwoRowMatrixView};
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use p3_matrix::MatrixRowSlices;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::get_max_constraint_degree;
+
+    type F = BabyBear;
+
+    /// Constraints of degree up to 3, with each kind of selector.
+    struct CubicAir;
+
+    impl<T> BaseAir<T> for CubicAir {
+        fn width(&self) -> usize {
+            3
+        }
+    }
+
+    impl<AB: RowPointAirBuilder> Air<AB> for CubicAir {
+        fn eval(&self, builder: &mut AB) {
+            let main = builder.main();
+            let (local, next) = (main.row_slice(0), main.row_slice(1));
+            let (x, y, z) = (local[0], local[1], local[2]);
+
+            builder.when_first_row().assert_one(x);
+            builder
+                .when_transition()
+                .assert_eq(next[0], x * y * z + AB::Expr::two());
+            builder.assert_zero(y * y * y - z * x.into());
+            let row_point = builder.row_point();
+            builder.when_last_row().assert_zero(row_point - z - next[1]);
+        }
+    }
+
+    /// Records the value of each constraint, rather than asserting that it's zero.
+    struct RecordingBuilder<'a> {
+        main: TwoRowMatrixView<'a, F>,
+        is_first_row: F,
+        is_last_row: F,
+        is_transition: F,
+        row_point: F,
+        constraints: Vec<F>,
+    }
+
+    impl<'a> AirBuilder for RecordingBuilder<'a> {
+        type F = F;
+        type Expr = F;
+        type Var = F;
+        type M = TwoRowMatrixView<'a, F>;
+
+        fn main(&self) -> Self::M {
+            self.main
+        }
+
+        fn is_first_row(&self) -> Self::Expr {
+            self.is_first_row
+        }
+
+        fn is_last_row(&self) -> Self::Expr {
+            self.is_last_row
+        }
+
+        fn is_transition_window(&self, size: usize) -> Self::Expr {
+            assert_eq!(size, 2);
+            self.is_transition
+        }
+
+        fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+            self.constraints.push(x.into());
+        }
+    }
+
+    impl<'a> RowPointAirBuilder for RecordingBuilder<'a> {
+        fn row_point(&self) -> Self::Expr {
+            self.row_point
+        }
+    }
+
+    #[test]
+    fn test_compiled_program_matches_air() {
+        let program = compile_air::<F, _>(&CubicAir);
+        assert_eq!(program.constraints.len(), 4);
+        assert_eq!(get_max_constraint_degree::<F, _>(&CubicAir), 3);
+
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let local: [F; 3] = rng.gen();
+            let next: [F; 3] = rng.gen();
+            let [is_first_row, is_last_row, is_transition, row_point]: [F; 4] = rng.gen();
+
+            let mut builder = RecordingBuilder {
+                main: TwoRowMatrixView {
+                    local: &local,
+                    next: &next,
+                },
+                is_first_row,
+                is_last_row,
+                is_transition,
+                row_point,
+                constraints: vec![],
+            };
+            CubicAir.eval(&mut builder);
+
+            let window = ProgramWindow {
+                local: &local,
+                next: &next,
+                is_first_row,
+                is_last_row,
+                is_transition,
+                row_point,
+                periodic_selectors: &[],
+                later_stage_challenges: &[],
+                public_values: &[],
+            };
+            assert_eq!(eval_program(&program, &window), builder.constraints);
+        }
+    }
+
+    #[test]
+    fn test_inputs_are_shared() {
+        let program = compile_air::<F, _>(&CubicAir);
+        let inputs = program
+            .instructions
+            .iter()
+            .filter_map(|instruction| match instruction {
+                Instruction::Input(input) => Some(*input),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let mut deduped = inputs.clone();
+        deduped.sort();
+        deduped.dedup();
+        assert_eq!(inputs.len(), deduped.len());
+        // Operands always refer to earlier gates.
+        for (gate, instruction) in program.instructions.iter().enumerate() {
+            match *instruction {
+                Instruction::Add(x, y) | Instruction::Sub(x, y) | Instruction::Mul(x, y) => {
+                    assert!(x < gate && y < gate)
+                }
+                Instruction::Neg(x) => assert!(x < gate),
+                Instruction::Input(_) | Instruction::Constant(_) => {}
+            }
+        }
+    }
+}
@@ -4,32 +4,36 @@ use alloc::vec::Vec;
 use p3_field::{AbstractExtensionField, TwoAdicField};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_maybe_rayon::prelude::*;
+use p3_util::allocator::TraceAllocator;
 use p3_util::log2_strict_usize;
 use tracing::instrument;
 
 /// Decompose the quotient polynomial into chunks using a generalization of even-odd decomposition.
 /// Then, arrange the results in a row-major matrix, so that each chunk of the decomposed polynomial
 /// becomes `D` columns of the resulting matrix, where `D` is the field extension degree.
+///
+/// The matrix is allocated with `allocator`.
 #[instrument(name = "decompose and flatten quotient", skip_all)]
-pub fn decompose_and_flatten<Val, Challenge>(
+pub fn decompose_and_flatten<Val, Challenge, A>(
     quotient_poly: Vec<Challenge>,
     shift: Challenge,
     log_chunks: usize,
+    allocator: &A,
 ) -> RowMajorMatrix<Val>
 where
     Val: TwoAdicField,
     Challenge: AbstractExtensionField<Val> + TwoAdicField,
+    A: TraceAllocator,
 {
+    let len = quotient_poly.len() * <Challenge as AbstractExtensionField<Val>>::D;
     let chunks: Vec<Vec<Challenge>> = decompose(quotient_poly, shift, log_chunks);
     let degree = chunks[0].len();
-    let quotient_chunks_flattened: Vec<Val> = (0..degree)
-        .into_par_iter()
-        .flat_map_iter(|row| {
-            chunks
-                .iter()
-                .flat_map(move |chunk| chunk[row].as_base_slice().iter().copied())
-        })
-        .collect();
+    let mut quotient_chunks_flattened: Vec<Val> = allocator.allocate(len);
+    quotient_chunks_flattened.par_extend((0..degree).into_par_iter().flat_map_iter(|row| {
+        chunks
+            .iter()
+            .flat_map(move |chunk| chunk[row].as_base_slice().iter().copied())
+    }));
     let challenge_ext_degree = <Challenge as AbstractExtensionField<Val>>::D;
     RowMajorMatrix::new(
         quotient_chunks_flattened,
@@ -1,22 +1,42 @@
-use p3_air::{AirBuilder, TwoRowMatrixView};
+use alloc::vec::Vec;
+
+use p3_air::{
+    AirBuilder, AirBuilderWithPublicValues, ExtensionBuilder, MultiStageAirBuilder, PairBuilder,
+    PeriodicAirBuilder, RowPointAirBuilder, TwoRowMatrixView,
+};
 use p3_field::{AbstractField, Field};
 
+use crate::constraint_compiler::find_periodic_selector;
 use crate::{PackedChallenge, PackedVal, StarkGenericConfig};
 
 pub struct ProverConstraintFolder<'a, SC: StarkGenericConfig> {
     pub main: TwoRowMatrixView<'a, PackedVal<SC>>,
+    pub later_stages: Vec<TwoRowMatrixView<'a, PackedChallenge<SC>>>,
+    pub later_stage_challenges: &'a [Vec<PackedChallenge<SC>>],
+    pub public_values: &'a [PackedVal<SC>],
+    /// Empty if the AIR has no preprocessed trace.
+    pub preprocessed: TwoRowMatrixView<'a, PackedVal<SC>>,
     pub is_first_row: PackedVal<SC>,
     pub is_last_row: PackedVal<SC>,
     pub is_transition: PackedVal<SC>,
+    pub row_point: PackedVal<SC>,
+    pub periodic_selectors: &'a [((usize, usize), PackedVal<SC>)],
     pub alpha: SC::Challenge,
     pub accumulator: PackedChallenge<SC>,
 }
 
 pub struct VerifierConstraintFolder<'a, Challenge> {
     pub main: TwoRowMatrixView<'a, Challenge>,
+    pub later_stages: Vec<TwoRowMatrixView<'a, Challenge>>,
+    pub later_stage_challenges: &'a [Vec<Challenge>],
+    pub public_values: &'a [Challenge],
+    /// Empty if the AIR has no preprocessed trace.
+    pub preprocessed: TwoRowMatrixView<'a, Challenge>,
     pub is_first_row: Challenge,
     pub is_last_row: Challenge,
     pub is_transition: Challenge,
+    pub row_point: Challenge,
+    pub periodic_selectors: &'a [((usize, usize), Challenge)],
     pub alpha: Challenge,
     pub accumulator: Challenge,
 }
@@ -54,6 +74,57 @@ impl<'a, SC: StarkGenericConfig> AirBuilder for ProverConstraintFolder<'a, SC> {
     }
 }
 
+impl<'a, SC: StarkGenericConfig> PeriodicAirBuilder for ProverConstraintFolder<'a, SC> {
+    fn is_periodic(&self, period: usize, offset: usize) -> Self::Expr {
+        find_periodic_selector(self.periodic_selectors, period, offset)
+    }
+}
+
+impl<'a, SC: StarkGenericConfig> RowPointAirBuilder for ProverConstraintFolder<'a, SC> {
+    fn row_point(&self) -> Self::Expr {
+        self.row_point
+    }
+}
+
+impl<'a, SC: StarkGenericConfig> ExtensionBuilder for ProverConstraintFolder<'a, SC> {
+    type EF = SC::Challenge;
+    type ExprEF = PackedChallenge<SC>;
+    type VarEF = PackedChallenge<SC>;
+
+    fn assert_zero_ext<I>(&mut self, x: I)
+    where
+        I: Into<Self::ExprEF>,
+    {
+        let x: PackedChallenge<SC> = x.into();
+        self.accumulator *= PackedChallenge::<SC>::from_f(self.alpha);
+        self.accumulator += x;
+    }
+}
+
+impl<'a, SC: StarkGenericConfig> MultiStageAirBuilder for ProverConstraintFolder<'a, SC> {
+    type MS = TwoRowMatrixView<'a, PackedChallenge<SC>>;
+
+    fn later_stage(&self, stage: usize) -> Self::MS {
+        self.later_stages[stage]
+    }
+
+    fn later_stage_challenges(&self, stage: usize) -> &[Self::ExprEF] {
+        &self.later_stage_challenges[stage]
+    }
+}
+
+impl<'a, SC: StarkGenericConfig> AirBuilderWithPublicValues for ProverConstraintFolder<'a, SC> {
+    fn public_values(&self) -> &[Self::Expr] {
+        self.public_values
+    }
+}
+
+impl<'a, SC: StarkGenericConfig> PairBuilder for ProverConstraintFolder<'a, SC> {
+    fn preprocessed(&self) -> Self::M {
+        self.preprocessed
+    }
+}
+
 impl<'a, Challenge: Field> AirBuilder for VerifierConstraintFolder<'a, Challenge> {
     type F = Challenge;
     type Expr = Challenge;
@@ -86,3 +157,52 @@ impl<'a, Challenge: Field> AirBuilder for VerifierConstraintFolder<'a, Challenge
         self.accumulator += x;
     }
 }
+
+impl<'a, Challenge: Field> PeriodicAirBuilder for VerifierConstraintFolder<'a, Challenge> {
+    fn is_periodic(&self, period: usize, offset: usize) -> Self::Expr {
+        find_periodic_selector(self.periodic_selectors, period, offset)
+    }
+}
+
+impl<'a, Challenge: Field> RowPointAirBuilder for VerifierConstraintFolder<'a, Challenge> {
+    fn row_point(&self) -> Self::Expr {
+        self.row_point
+    }
+}
+
+impl<'a, Challenge: Field> ExtensionBuilder for VerifierConstraintFolder<'a, Challenge> {
+    type EF = Challenge;
+    type ExprEF = Challenge;
+    type VarEF = Challenge;
+
+    fn assert_zero_ext<I>(&mut self, x: I)
+    where
+        I: Into<Self::ExprEF>,
+    {
+        self.assert_zero(x);
+    }
+}
+
+impl<'a, Challenge: Field> MultiStageAirBuilder for VerifierConstraintFolder<'a, Challenge> {
+    type MS = TwoRowMatrixView<'a, Challenge>;
+
+    fn later_stage(&self, stage: usize) -> Self::MS {
+        self.later_stages[stage]
+    }
+
+    fn later_stage_challenges(&self, stage: usize) -> &[Self::ExprEF] {
+        &self.later_stage_challenges[stage]
+    }
+}
+
+impl<'a, Challenge: Field> AirBuilderWithPublicValues for VerifierConstraintFolder<'a, Challenge> {
+    fn public_values(&self) -> &[Self::Expr] {
+        self.public_values
+    }
+}
+
+impl<'a, Challenge: Field> PairBuilder for VerifierConstraintFolder<'a, Challenge> {
+    fn preprocessed(&self) -> Self::M {
+        self.preprocessed
+    }
+}
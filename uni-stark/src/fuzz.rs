@@ -0,0 +1,89 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use p3_air::Air;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::symbolic_builder::SymbolicAirBuilder;
+use crate::{
+    prove, setup, verify, ProverConstraintFolder, ProvingKey, StarkGenericConfig,
+    VerificationError, VerifierConstraintFolder, VerifyingKey,
+};
+
+/// The result of proving and then verifying a single trace.
+#[derive(Debug)]
+pub enum FuzzOutcome {
+    Accepted,
+    RejectedBy(VerificationError),
+    /// The prover panicked, e.g. because a debug build caught a violated constraint.
+    ProverPanicked,
+}
+
+/// Proves and verifies traces produced by a generator, so that fuzz targets for different AIRs can
+/// share the same scaffolding.
+pub struct FuzzHarness<'a, SC: StarkGenericConfig, A, G> {
+    config: &'a SC,
+    air: &'a A,
+    pk: ProvingKey<SC>,
+    vk: VerifyingKey<SC>,
+    /// The challenger state which both the prover and verifier start from.
+    challenger: SC::Challenger,
+    trace_generator: G,
+}
+
+impl<'a, SC, A, G> FuzzHarness<'a, SC, A, G>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<SC::Val>>,
+    G: FnMut() -> RowMajorMatrix<SC::Val>,
+{
+    /// Runs `setup` for `air`, whose keys are then shared by every trace the harness proves.
+    pub fn new(config: &'a SC, air: &'a A, challenger: SC::Challenger, trace_generator: G) -> Self {
+        let (pk, vk) = setup(config, air);
+        Self {
+            config,
+            air,
+            pk,
+            vk,
+            challenger,
+            trace_generator,
+        }
+    }
+}
+
+impl<
+        'a,
+        SC,
+        #[cfg(debug_assertions)] A: for<'b> Air<crate::check_constraints::DebugConstraintBuilder<'b, SC::Val, SC::Challenge>>,
+        #[cfg(not(debug_assertions))] A,
+        G,
+    > FuzzHarness<'a, SC, A, G>
+where
+    SC: StarkGenericConfig,
+    SC::Challenger: Clone,
+    A: for<'b> Air<ProverConstraintFolder<'b, SC>>
+        + for<'b> Air<VerifierConstraintFolder<'b, SC::Challenge>>,
+    G: FnMut() -> RowMajorMatrix<SC::Val>,
+{
+    /// Generate one trace, then prove and verify it.
+    pub fn run(&mut self) -> FuzzOutcome {
+        let trace = (self.trace_generator)();
+        self.run_with_trace(trace)
+    }
+
+    /// Prove and verify the given trace.
+    pub fn run_with_trace(&self, trace: RowMajorMatrix<SC::Val>) -> FuzzOutcome {
+        let proof = catch_unwind(AssertUnwindSafe(|| {
+            let mut challenger = self.challenger.clone();
+            prove::<SC, A>(self.config, &self.pk, self.air, &mut challenger, trace)
+        }));
+        let Ok(proof) = proof else {
+            return FuzzOutcome::ProverPanicked;
+        };
+
+        let mut challenger = self.challenger.clone();
+        match verify(self.config, &self.vk, self.air, &mut challenger, &proof) {
+            Ok(()) => FuzzOutcome::Accepted,
+            Err(e) => FuzzOutcome::RejectedBy(e),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use alloc::vec::Vec;
+
+use p3_air::Air;
+use p3_commit::{Pcs, UnivariatePcsWithLde};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_util::log2_strict_usize;
+use serde::{Deserialize, Serialize};
+use tracing::{info_span, instrument};
+
+use crate::symbolic_builder::{get_log_quotient_degree, SymbolicAirBuilder};
+use crate::{get_periodic_selectors, Com, StarkGenericConfig};
+
+type PcsProverData<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+    <SC as StarkGenericConfig>::Val,
+    RowMajorMatrix<<SC as StarkGenericConfig>::Val>,
+>>::ProverData;
+
+/// The data about an AIR which the prover needs besides the witness, computed by `setup`.
+pub struct ProvingKey<SC: StarkGenericConfig> {
+    pub(crate) preprocessed: Option<PreprocessedProverData<SC>>,
+    pub(crate) log_quotient_degree: usize,
+    pub(crate) periodic_selectors: Vec<(usize, usize)>,
+}
+
+pub(crate) struct PreprocessedProverData<SC: StarkGenericConfig> {
+    pub(crate) commitment: Com<SC>,
+    pub(crate) data: PcsProverData<SC>,
+    pub(crate) degree_bits: usize,
+}
+
+/// The data about an AIR which the verifier needs besides its constraints, computed by `setup`.
+/// It doesn't depend on the witness, so it can be computed once and reused for every proof.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct VerifyingKey<SC: StarkGenericConfig> {
+    pub(crate) preprocessed: Option<PreprocessedVerifierData<Com<SC>>>,
+    pub(crate) log_quotient_degree: usize,
+    pub(crate) periodic_selectors: Vec<(usize, usize)>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PreprocessedVerifierData<Com> {
+    pub(crate) commitment: Com,
+    pub(crate) width: usize,
+    /// The log of the preprocessed trace's height, which every trace proven with it must share.
+    pub(crate) degree_bits: usize,
+}
+
+/// Commit to `air`'s preprocessed trace, if it has one, and record the metadata which would
+/// otherwise be recomputed from `air` for each proof.
+#[instrument(skip_all)]
+pub fn setup<SC, A>(config: &SC, air: &A) -> (ProvingKey<SC>, VerifyingKey<SC>)
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<SC::Val>>,
+{
+    let (pk_preprocessed, vk_preprocessed) = match air.preprocessed_trace() {
+        Some(trace) => {
+            let width = trace.width();
+            assert_eq!(
+                width,
+                air.preprocessed_width(),
+                "the preprocessed trace's width doesn't match the AIR's preprocessed_width"
+            );
+            let degree_bits = log2_strict_usize(trace.height());
+            let (commitment, data) = info_span!("commit to preprocessed trace").in_scope(|| {
+                config
+                    .pcs()
+                    .commit_shifted_batch(trace, config.trace_domain_shift())
+            });
+            let vk_preprocessed = PreprocessedVerifierData {
+                commitment: commitment.clone(),
+                width,
+                degree_bits,
+            };
+            let pk_preprocessed = PreprocessedProverData {
+                commitment,
+                data,
+                degree_bits,
+            };
+            (Some(pk_preprocessed), Some(vk_preprocessed))
+        }
+        None => {
+            assert_eq!(
+                air.preprocessed_width(),
+                0,
+                "the AIR has a preprocessed_width but no preprocessed trace"
+            );
+            (None, None)
+        }
+    };
+
+    let log_quotient_degree = get_log_quotient_degree::<SC::Val, A>(air);
+    let periodic_selectors = get_periodic_selectors::<SC::Val, A>(air);
+    let pk = ProvingKey {
+        preprocessed: pk_preprocessed,
+        log_quotient_degree,
+        periodic_selectors: periodic_selectors.clone(),
+    };
+    let vk = VerifyingKey {
+        preprocessed: vk_preprocessed,
+        log_quotient_degree,
+        periodic_selectors,
+    };
+    (pk, vk)
+}
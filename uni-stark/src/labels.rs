@@ -0,0 +1,19 @@
+//! Domain separation labels, observed by the challenger before each phase of the protocol.
+
+/// Observed before the preprocessed trace commitment, for AIRs which have one.
+pub const PREPROCESSED_COMMIT_LABEL: &[u8] = b"p3-uni-stark/preprocessed-commit";
+
+/// Observed before the trace commitment.
+pub const TRACE_COMMIT_LABEL: &[u8] = b"p3-uni-stark/trace-commit";
+
+/// Observed before the public values, for proofs which have any.
+pub const PUBLIC_VALUES_LABEL: &[u8] = b"p3-uni-stark/public-values";
+
+/// Observed before each commitment to a later witness stage.
+pub const LATER_STAGE_COMMIT_LABEL: &[u8] = b"p3-uni-stark/later-stage-commit";
+
+/// Observed before the quotient chunks commitment.
+pub const QUOTIENT_COMMIT_LABEL: &[u8] = b"p3-uni-stark/quotient-commit";
+
+/// Observed before the PCS opening argument, which runs FRI.
+pub const FRI_LABEL: &[u8] = b"p3-uni-stark/fri";
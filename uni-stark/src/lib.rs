@@ -3,28 +3,46 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+mod challenges;
 mod config;
+mod constraint_compiler;
 mod decompose;
 mod folder;
+#[cfg(feature = "std")]
+mod fuzz;
+mod keys;
+mod labels;
 mod proof;
 mod prover;
 mod symbolic_builder;
 mod symbolic_expression;
 mod symbolic_variable;
+#[cfg(feature = "std")]
+mod timings;
 mod verifier;
 mod zerofier_coset;
 
 mod check_constraints;
 
+pub use challenges::*;
 pub use check_constraints::*;
 pub use config::*;
+pub use constraint_compiler::*;
 pub use decompose::*;
 pub use folder::*;
+#[cfg(feature = "std")]
+pub use fuzz::*;
+pub use keys::*;
+pub use labels::*;
 pub use proof::*;
 pub use prover::*;
 pub use symbolic_builder::*;
 pub use symbolic_expression::*;
 pub use symbolic_variable::*;
+#[cfg(feature = "std")]
+pub use timings::*;
 pub use verifier::*;
 pub use zerofier_coset::*;
@@ -8,7 +8,7 @@ use crate::StarkGenericConfig;
 
 type Val<SC> = <SC as StarkGenericConfig>::Val;
 type ValMat<SC> = RowMajorMatrix<Val<SC>>;
-type Com<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<Val<SC>, ValMat<SC>>>::Commitment;
+pub(crate) type Com<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<Val<SC>, ValMat<SC>>>::Commitment;
 type PcsProof<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<Val<SC>, ValMat<SC>>>::Proof;
 
 #[derive(Serialize, Deserialize)]
@@ -18,11 +18,30 @@ pub struct Proof<SC: StarkGenericConfig> {
     pub(crate) opened_values: OpenedValues<SC::Challenge>,
     pub(crate) opening_proof: PcsProof<SC>,
     pub(crate) degree_bits: usize,
+    /// Empty unless the proof was made with `prove_with_public_values`.
+    pub(crate) public_values: Vec<Val<SC>>,
+}
+
+impl<SC: StarkGenericConfig> Proof<SC> {
+    /// The public values the AIR was evaluated with.
+    pub fn public_values(&self) -> &[Val<SC>] {
+        &self.public_values
+    }
+
+    /// A digest of the full trace: its commitment, which the PCS computes with the configured
+    /// hasher. The verifier checks every opening of the trace against it, so unlike a hash stored
+    /// beside the proof, it can't differ from the trace that was proven. `verify_with_trace_digest`
+    /// checks it against an expected digest.
+    pub fn trace_digest(&self) -> &Com<SC> {
+        &self.commitments.trace
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Commitments<Com> {
     pub(crate) trace: Com,
+    /// One commitment per witness stage after `trace`.
+    pub(crate) later_stages: Vec<Com>,
     pub(crate) quotient_chunks: Com,
 }
 
@@ -30,5 +49,12 @@ pub struct Commitments<Com> {
 pub struct OpenedValues<Challenge> {
     pub(crate) trace_local: Vec<Challenge>,
     pub(crate) trace_next: Vec<Challenge>,
+    /// The openings of the `D` base field columns into which each later stage column was
+    /// flattened.
+    pub(crate) later_stages_local: Vec<Vec<Challenge>>,
+    pub(crate) later_stages_next: Vec<Vec<Challenge>>,
+    /// Empty if the AIR has no preprocessed trace.
+    pub(crate) preprocessed_local: Vec<Challenge>,
+    pub(crate) preprocessed_next: Vec<Challenge>,
     pub(crate) quotient_chunks: Vec<Challenge>,
 }
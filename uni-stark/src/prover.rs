@@ -1,9 +1,10 @@
 use alloc::vec;
 use alloc::vec::Vec;
+use core::iter;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 use itertools::Itertools;
-use p3_air::{Air, TwoRowMatrixView};
-use p3_challenger::{CanObserve, FieldChallenger};
+use p3_air::{Air, BaseAir, TwoRowMatrixView};
 use p3_commit::{Pcs, UnivariatePcs, UnivariatePcsWithLde};
 use p3_field::{
     cyclic_subgroup_coset_known_order, AbstractExtensionField, AbstractField, Field, PackedValue,
@@ -12,117 +13,483 @@ use p3_field::{
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::{Matrix, MatrixGet, MatrixRows};
 use p3_maybe_rayon::prelude::*;
+use p3_util::allocator::TraceAllocator;
 use p3_util::log2_strict_usize;
 use tracing::{info_span, instrument};
 
-use crate::symbolic_builder::{get_log_quotient_degree, SymbolicAirBuilder};
 use crate::{
-    decompose_and_flatten, Commitments, OpenedValues, PackedChallenge, PackedVal, Proof,
-    ProverConstraintFolder, StarkGenericConfig, ZerofierOnCoset,
+    decompose_and_flatten, Com, Commitments, OpenedValues, PackedChallenge, PackedVal, Proof,
+    ProverConstraintFolder, ProvingKey, StarkChallenges, StarkGenericConfig, ZerofierOnCoset,
 };
 
-#[instrument(skip_all)]
+// The names of the spans which `prove_timed` attributes to each of its phases, besides those which
+// the PCS enters, from `p3_commit`.
+pub(crate) const TRACE_COMMIT_SPAN: &str = "commit to trace data";
+pub(crate) const LATER_STAGE_COMMIT_SPAN: &str = "commit to later stage";
+pub(crate) const QUOTIENT_SPAN: &str = "compute quotient polynomial";
+pub(crate) const QUOTIENT_COMMIT_SPAN: &str = "commit to quotient poly chunks";
+
+/// Prove that `trace` satisfies `air`'s constraints, given a proving key which `setup` computed for
+/// the same AIR.
 pub fn prove<
     SC,
-    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, SC::Val>>,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, SC::Val, SC::Challenge>>,
     #[cfg(not(debug_assertions))] A,
 >(
     config: &SC,
+    pk: &ProvingKey<SC>,
     air: &A,
     challenger: &mut SC::Challenger,
     trace: RowMajorMatrix<SC::Val>,
 ) -> Proof<SC>
 where
     SC: StarkGenericConfig,
-    A: Air<SymbolicAirBuilder<SC::Val>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
 {
-    #[cfg(debug_assertions)]
-    crate::check_constraints::check_constraints(air, &trace);
+    prove_internal(
+        config,
+        pk,
+        air,
+        challenger,
+        trace,
+        vec![],
+        &mut no_later_stages::<SC::Val, SC::Challenge, _>(air),
+        false,
+        None,
+    )
+    .expect("proving without a cancellation flag cannot be cancelled")
+    .0
+}
+
+/// Like `prove`, for AIRs whose witness is committed in stages, as described by
+/// `BaseAir::later_stages`. After each commitment, the next stage's challenges are sampled, and
+/// `later_stage_generator(stage, challenges)` is called to generate that stage's trace. Both the
+/// challenges and the stage traces are over the extension field.
+pub fn prove_multi_stage<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, SC::Val, SC::Challenge>>,
+    #[cfg(not(debug_assertions))] A,
+    G,
+>(
+    config: &SC,
+    pk: &ProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<SC::Val>,
+    mut later_stage_generator: G,
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+    G: FnMut(usize, &[SC::Challenge]) -> RowMajorMatrix<SC::Challenge>,
+{
+    prove_internal(
+        config,
+        pk,
+        air,
+        challenger,
+        trace,
+        vec![],
+        &mut later_stage_generator,
+        false,
+        None,
+    )
+    .expect("proving without a cancellation flag cannot be cancelled")
+    .0
+}
+
+/// Like `prove`, for AIRs which read public values via `AirBuilderWithPublicValues`. They're
+/// observed by the challenger before the trace commitment, and stored in the proof. It's up to the
+/// verifier to check them, e.g. against the `Proof::trace_digest` of the previous proof in a chain
+/// of continuations.
+pub fn prove_with_public_values<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, SC::Val, SC::Challenge>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    pk: &ProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<SC::Val>,
+    public_values: Vec<SC::Val>,
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    prove_internal(
+        config,
+        pk,
+        air,
+        challenger,
+        trace,
+        public_values,
+        &mut no_later_stages::<SC::Val, SC::Challenge, _>(air),
+        false,
+        None,
+    )
+    .expect("proving without a cancellation flag cannot be cancelled")
+    .0
+}
+
+/// The later stage generator for the entry points which don't take one. It checks that `air` has
+/// no later stages before proving starts, rather than once the main trace has been committed.
+fn no_later_stages<F, EF, A: BaseAir<F>>(
+    air: &A,
+) -> impl FnMut(usize, &[EF]) -> RowMajorMatrix<EF> {
+    assert!(
+        air.later_stages().is_empty(),
+        "this AIR has later stages; use prove_multi_stage"
+    );
+    |_, _| unreachable!("an AIR without later stages has no later stage traces")
+}
 
+/// Like `prove`, but checks `cancel` between (and within) the major phases of proving, including
+/// the LDEs and the rounds of FRI folding, and returns `ProverError::Cancelled` at the first
+/// checkpoint after it has been set.
+pub fn prove_cancellable<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, SC::Val, SC::Challenge>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    pk: &ProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<SC::Val>,
+    cancel: &AtomicBool,
+) -> Result<Proof<SC>, ProverError>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    prove_internal(
+        config,
+        pk,
+        air,
+        challenger,
+        trace,
+        vec![],
+        &mut no_later_stages::<SC::Val, SC::Challenge, _>(air),
+        false,
+        Some(cancel),
+    )
+    .map(|(proof, _)| proof)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProverError {
+    /// The cancellation flag passed to `prove_cancellable` was set.
+    Cancelled,
+}
+
+fn checkpoint(cancel: Option<&AtomicBool>) -> Result<(), ProverError> {
+    if is_cancelled(cancel) {
+        Err(ProverError::Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
+fn is_cancelled(cancel: Option<&AtomicBool>) -> bool {
+    cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+}
+
+/// Like `prove`, but also returns intermediate data which would otherwise be discarded.
+pub fn prove_with_artifacts<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, SC::Val, SC::Challenge>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    pk: &ProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<SC::Val>,
+) -> (Proof<SC>, ProverArtifacts<SC>)
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let (proof, artifacts) = prove_internal(
+        config,
+        pk,
+        air,
+        challenger,
+        trace,
+        vec![],
+        &mut no_later_stages::<SC::Val, SC::Challenge, _>(air),
+        true,
+        None,
+    )
+    .expect("proving without a cancellation flag cannot be cancelled");
+    (proof, artifacts.unwrap())
+}
+
+/// Intermediate data from `prove_with_artifacts`, for callers which want to inspect or reuse it.
+pub struct ProverArtifacts<SC: StarkGenericConfig> {
+    /// The LDE of the trace over the PCS's coset, in natural order.
+    pub trace_lde: RowMajorMatrix<SC::Val>,
+    /// The quotient polynomial, decomposed into `2^log_quotient_degree` chunks, with each chunk
+    /// flattened into `D` base field columns. Rows are evaluations over
+    /// `coset_shift^(2^log_quotient_degree) H`, while chunks are in bit-reversed order.
+    pub quotient_chunks: RowMajorMatrix<SC::Val>,
+    pub trace_commit: Com<SC>,
+    pub quotient_commit: Com<SC>,
+}
+
+#[allow(clippy::too_many_arguments)]
+#[instrument(name = "prove", skip_all)]
+fn prove_internal<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, SC::Val, SC::Challenge>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    pk: &ProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<SC::Val>,
+    public_values: Vec<SC::Val>,
+    later_stage_generator: &mut dyn FnMut(usize, &[SC::Challenge]) -> RowMajorMatrix<SC::Challenge>,
+    retain_artifacts: bool,
+    cancel: Option<&AtomicBool>,
+) -> Result<(Proof<SC>, Option<ProverArtifacts<SC>>), ProverError>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
     let degree = trace.height();
     let log_degree = log2_strict_usize(degree);
 
-    let log_quotient_degree = get_log_quotient_degree::<SC::Val, A>(air);
+    let log_quotient_degree = pk.log_quotient_degree;
+    let later_stages = air.later_stages();
+    assert_eq!(
+        public_values.len(),
+        air.num_public_values(),
+        "wrong number of public values for this AIR"
+    );
 
     let g_subgroup = SC::Val::two_adic_generator(log_degree);
 
-    let pcs = config.pcs();
-    let (trace_commit, trace_data) =
-        info_span!("commit to trace data").in_scope(|| pcs.commit_batch(trace));
+    // The PCS takes a flag to check, so give it one which is never set if there's none.
+    let never_cancelled = AtomicBool::new(false);
+    let pcs_cancel = cancel.unwrap_or(&never_cancelled);
 
-    challenger.observe(trace_commit.clone());
-    let alpha: SC::Challenge = challenger.sample_ext_element();
+    // Constraints can only be checked once every stage has been generated, so keep copies of the
+    // traces until then.
+    #[cfg(debug_assertions)]
+    let main_for_check = trace.clone();
+    #[cfg(debug_assertions)]
+    let mut later_stages_for_check = Vec::with_capacity(later_stages.len());
 
-    let mut trace_ldes = pcs.get_ldes(&trace_data);
-    assert_eq!(trace_ldes.len(), 1);
-    let trace_lde = trace_ldes.pop().unwrap();
+    if let Some(preprocessed) = &pk.preprocessed {
+        assert_eq!(
+            preprocessed.degree_bits, log_degree,
+            "the trace must have the same height as the preprocessed trace"
+        );
+        StarkChallenges::<SC>::observe_preprocessed(challenger, &preprocessed.commitment);
+    }
 
-    let log_stride_for_quotient = pcs.log_blowup() - log_quotient_degree;
-    let trace_lde_for_quotient = trace_lde.vertically_strided(1 << log_stride_for_quotient, 0);
+    StarkChallenges::<SC>::observe_public_values(challenger, &public_values);
 
-    let quotient_values = quotient_values(
-        config,
+    checkpoint(cancel)?;
+    let pcs = config.pcs();
+    let trace_domain_shift = config.trace_domain_shift();
+    let (trace_commit, trace_data) = info_span!(TRACE_COMMIT_SPAN)
+        .in_scope(|| pcs.commit_shifted_batch_cancellable(trace, trace_domain_shift, pcs_cancel))
+        .ok_or(ProverError::Cancelled)?;
+    StarkChallenges::<SC>::observe_trace(challenger, &trace_commit);
+
+    let mut later_stage_challenges = Vec::with_capacity(later_stages.len());
+    let mut later_stage_commits = Vec::with_capacity(later_stages.len());
+    let mut later_stage_data = Vec::with_capacity(later_stages.len());
+    for (stage, shape) in later_stages.iter().enumerate() {
+        checkpoint(cancel)?;
+        let challenges = StarkChallenges::<SC>::sample_later_stage_challenges(challenger, shape);
+        let stage_trace = later_stage_generator(stage, &challenges);
+        assert_eq!(
+            stage_trace.width(),
+            shape.width,
+            "wrong width for stage {stage}"
+        );
+        assert_eq!(
+            stage_trace.height(),
+            degree,
+            "wrong height for stage {stage}"
+        );
+        #[cfg(debug_assertions)]
+        later_stages_for_check.push(stage_trace.clone());
+
+        // Each extension field column is committed as `D` base field columns.
+        let (commit, data) = info_span!(LATER_STAGE_COMMIT_SPAN, stage)
+            .in_scope(|| {
+                pcs.commit_shifted_batch_cancellable(
+                    stage_trace.flatten_to_base(),
+                    trace_domain_shift,
+                    pcs_cancel,
+                )
+            })
+            .ok_or(ProverError::Cancelled)?;
+        StarkChallenges::<SC>::observe_later_stage(challenger, &commit);
+        later_stage_challenges.push(challenges);
+        later_stage_commits.push(commit);
+        later_stage_data.push(data);
+    }
+
+    #[cfg(debug_assertions)]
+    crate::check_constraints::check_constraints(
         air,
-        log_degree,
-        log_quotient_degree,
-        trace_lde_for_quotient,
-        alpha,
+        &main_for_check,
+        &later_stages_for_check,
+        &later_stage_challenges,
+        air.preprocessed_trace().as_ref(),
+        &public_values,
     );
+
+    checkpoint(cancel)?;
+    let alpha = StarkChallenges::<SC>::sample_alpha(challenger);
+
+    let log_stride_for_quotient = pcs.log_blowup() - log_quotient_degree;
+    let lde_for_quotient = |data| {
+        let mut ldes = pcs.get_ldes(data);
+        assert_eq!(ldes.len(), 1);
+        ldes.pop()
+            .unwrap()
+            .vertically_strided(1 << log_stride_for_quotient, 0)
+    };
+    let trace_lde_for_quotient = lde_for_quotient(&trace_data);
+    let later_stage_ldes_for_quotient = later_stage_data.iter().map(lde_for_quotient).collect_vec();
+    let preprocessed_lde_for_quotient = pk
+        .preprocessed
+        .as_ref()
+        .map(|preprocessed| lde_for_quotient(&preprocessed.data));
+
+    let quotient_values = info_span!(QUOTIENT_SPAN).in_scope(|| {
+        quotient_values(
+            config,
+            air,
+            log_degree,
+            log_quotient_degree,
+            trace_lde_for_quotient,
+            &later_stage_ldes_for_quotient,
+            &later_stage_challenges,
+            &public_values,
+            preprocessed_lde_for_quotient,
+            &pk.periodic_selectors,
+            alpha,
+            cancel,
+        )
+    })?;
     let quotient_chunks_flattened = decompose_and_flatten(
         quotient_values,
         SC::Challenge::from_base(pcs.coset_shift()),
         log_quotient_degree,
+        pcs.allocator(),
     );
-    let (quotient_commit, quotient_data) =
-        info_span!("commit to quotient poly chunks").in_scope(|| {
-            pcs.commit_shifted_batch(
+    let quotient_chunks_artifact = retain_artifacts.then(|| quotient_chunks_flattened.clone());
+    let (quotient_commit, quotient_data) = info_span!(QUOTIENT_COMMIT_SPAN)
+        .in_scope(|| {
+            pcs.commit_shifted_batch_cancellable(
                 quotient_chunks_flattened,
                 pcs.coset_shift().exp_power_of_2(log_quotient_degree),
+                pcs_cancel,
             )
-        });
-    challenger.observe(quotient_commit.clone());
+        })
+        .ok_or(ProverError::Cancelled)?;
+    checkpoint(cancel)?;
+    let zeta = StarkChallenges::<SC>::sample_zeta(challenger, &quotient_commit);
+
+    let artifacts = quotient_chunks_artifact.map(|quotient_chunks| ProverArtifacts {
+        trace_lde: pcs
+            .get_ldes(&trace_data)
+            .pop()
+            .unwrap()
+            .to_row_major_matrix(),
+        quotient_chunks,
+        trace_commit: trace_commit.clone(),
+        quotient_commit: quotient_commit.clone(),
+    });
 
     let commitments = Commitments {
         trace: trace_commit,
+        later_stages: later_stage_commits,
         quotient_chunks: quotient_commit,
     };
 
-    let zeta: SC::Challenge = challenger.sample_ext_element();
-    let (opened_values, opening_proof) = pcs.open_multi_batches(
-        &[
-            (&trace_data, &[vec![zeta, zeta * g_subgroup]]),
-            (
-                &quotient_data,
-                &[vec![zeta.exp_power_of_2(log_quotient_degree)]],
-            ),
-        ],
-        challenger,
-    );
-    let trace_local = opened_values[0][0][0].clone();
-    let trace_next = opened_values[0][0][1].clone();
-    let quotient_chunks = opened_values[1][0][0].clone();
+    // The preprocessed trace and every stage of the witness are opened at `zeta` and `g zeta`.
+    let local_and_next = [vec![zeta, zeta * g_subgroup]];
+    let quotient_points = [vec![zeta.exp_power_of_2(log_quotient_degree)]];
+    let preprocessed_data = pk
+        .preprocessed
+        .as_ref()
+        .map(|preprocessed| &preprocessed.data);
+    let prover_data_and_points = preprocessed_data
+        .into_iter()
+        .chain(iter::once(&trace_data))
+        .chain(&later_stage_data)
+        .map(|data| (data, local_and_next.as_slice()))
+        .chain(iter::once((&quotient_data, quotient_points.as_slice())))
+        .collect_vec();
+    let (opened_values, opening_proof) = pcs
+        .open_multi_batches_cancellable(&prover_data_and_points, challenger, pcs_cancel)
+        .ok_or(ProverError::Cancelled)?;
+    let num_later_stages = later_stages.len();
+    let trace_round = usize::from(preprocessed_data.is_some());
+    let (preprocessed_local, preprocessed_next) = if preprocessed_data.is_some() {
+        (
+            opened_values[0][0][0].clone(),
+            opened_values[0][0][1].clone(),
+        )
+    } else {
+        (vec![], vec![])
+    };
+    let trace_local = opened_values[trace_round][0][0].clone();
+    let trace_next = opened_values[trace_round][0][1].clone();
+    let later_stage_rounds = trace_round + 1..=trace_round + num_later_stages;
+    let later_stages_local = later_stage_rounds
+        .clone()
+        .map(|round| opened_values[round][0][0].clone())
+        .collect();
+    let later_stages_next = later_stage_rounds
+        .map(|round| opened_values[round][0][1].clone())
+        .collect();
+    let quotient_chunks = opened_values[trace_round + num_later_stages + 1][0][0].clone();
     let opened_values = OpenedValues {
         trace_local,
         trace_next,
+        later_stages_local,
+        later_stages_next,
+        preprocessed_local,
+        preprocessed_next,
         quotient_chunks,
     };
-    Proof {
+    let proof = Proof {
         commitments,
         opened_values,
         opening_proof,
         degree_bits: log_degree,
-    }
+        public_values,
+    };
+    Ok((proof, artifacts))
 }
 
-#[instrument(name = "compute quotient polynomial", skip_all)]
+#[allow(clippy::too_many_arguments)]
 fn quotient_values<SC, A, Mat>(
     config: &SC,
     air: &A,
     degree_bits: usize,
     quotient_degree_bits: usize,
     trace_lde: Mat,
+    later_stage_ldes: &[Mat],
+    later_stage_challenges: &[Vec<SC::Challenge>],
+    public_values: &[SC::Val],
+    preprocessed_lde: Option<Mat>,
+    periodic_selectors: &[(usize, usize)],
     alpha: SC::Challenge,
-) -> Vec<SC::Challenge>
+    cancel: Option<&AtomicBool>,
+) -> Result<Vec<SC::Challenge>, ProverError>
 where
     SC: StarkGenericConfig,
     A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
@@ -134,17 +501,24 @@ where
     let g_subgroup = SC::Val::two_adic_generator(degree_bits);
     let g_extended = SC::Val::two_adic_generator(quotient_size_bits);
     let subgroup_last = g_subgroup.inverse();
-    let coset_shift = config.pcs().coset_shift();
     let next_step = 1 << quotient_degree_bits;
 
+    // The trace is defined over `t H` for some shift `t`, and we evaluate the quotient over `s K`.
+    // It's convenient to work with `y = x / t`, which ranges over `(s / t) K`, since our selectors
+    // and `Z_{t H}(x) = Z_H(x / t)` then take their usual forms in terms of `y`.
+    let coset_shift = config.pcs().coset_shift() / config.trace_domain_shift();
     let mut coset: Vec<_> =
         cyclic_subgroup_coset_known_order(g_extended, coset_shift, quotient_size).collect();
 
     let zerofier_on_coset = ZerofierOnCoset::new(degree_bits, quotient_degree_bits, coset_shift);
 
-    // Evaluations of L_first(x) = Z_H(x) / (x - 1) on our coset s H.
+    // Evaluations of L_first(y) = Z_H(y) / (y - 1) on our coset (s / t) K.
     let mut lagrange_first_evals = zerofier_on_coset.lagrange_basis_unnormalized(0);
     let mut lagrange_last_evals = zerofier_on_coset.lagrange_basis_unnormalized(degree - 1);
+    let mut periodic_evals = periodic_selectors
+        .iter()
+        .map(|&(period, offset)| zerofier_on_coset.periodic_selector_unnormalized(period, offset))
+        .collect_vec();
 
     // We have a few vectors of length `quotient_size`, and we're going to take slices therein of
     // length `WIDTH`. In the edge case where `quotient_size < WIDTH`, we need to pad those vectors
@@ -154,11 +528,49 @@ where
         coset.push(SC::Val::default());
         lagrange_first_evals.push(SC::Val::default());
         lagrange_last_evals.push(SC::Val::default());
+        for evals in &mut periodic_evals {
+            evals.push(SC::Val::default());
+        }
     }
 
-    (0..quotient_size)
+    // Pack the periodic selectors once, so that those for the packed row starting at `i` are the
+    // `num_periodic` entries from `i / WIDTH * num_periodic`.
+    let num_periodic = periodic_selectors.len();
+    let periodic_packed = (0..quotient_size)
+        .step_by(PackedVal::<SC>::WIDTH)
+        .flat_map(|i_start| {
+            let i_range = i_start..i_start + PackedVal::<SC>::WIDTH;
+            periodic_selectors
+                .iter()
+                .zip(&periodic_evals)
+                .map(move |(&key, evals)| {
+                    (key, *PackedVal::<SC>::from_slice(&evals[i_range.clone()]))
+                })
+        })
+        .collect_vec();
+
+    let later_stage_challenges: Vec<Vec<PackedChallenge<SC>>> = later_stage_challenges
+        .iter()
+        .map(|challenges| {
+            challenges
+                .iter()
+                .map(|&c| PackedChallenge::<SC>::from_f(c))
+                .collect()
+        })
+        .collect();
+    let public_values = public_values
+        .iter()
+        .map(|&v| PackedVal::<SC>::from_f(v))
+        .collect_vec();
+
+    // Each lane of a packed value belongs to a different point of the coset, and every column is
+    // packed separately, so the AIR's width needn't be a multiple of `WIDTH`. Both `quotient_size`
+    // and `WIDTH` are powers of two, so the only ragged chunk is the padded one above.
+    let quotients = (0..quotient_size)
         .into_par_iter()
         .step_by(PackedVal::<SC>::WIDTH)
+        // Once cancelled, skip the remaining chunks; the partial result is discarded below.
+        .filter(|_| !is_cancelled(cancel))
         .flat_map_iter(|i_local_start| {
             let wrap = |i| i % quotient_size;
             let i_next_start = wrap(i_local_start + next_step);
@@ -167,24 +579,45 @@ where
             let x = *PackedVal::<SC>::from_slice(&coset[i_range.clone()]);
             let is_transition = x - subgroup_last;
             let is_first_row = *PackedVal::<SC>::from_slice(&lagrange_first_evals[i_range.clone()]);
-            let is_last_row = *PackedVal::<SC>::from_slice(&lagrange_last_evals[i_range]);
+            let is_last_row = *PackedVal::<SC>::from_slice(&lagrange_last_evals[i_range.clone()]);
+            let periodic = &periodic_packed
+                [i_local_start / PackedVal::<SC>::WIDTH * num_periodic..][..num_periodic];
 
-            let local: Vec<_> = (0..trace_lde.width())
-                .map(|col| {
-                    PackedVal::<SC>::from_fn(|offset| {
-                        let row = wrap(i_local_start + offset);
-                        trace_lde.get(row, col)
-                    })
-                })
-                .collect();
-            let next: Vec<_> = (0..trace_lde.width())
-                .map(|col| {
-                    PackedVal::<SC>::from_fn(|offset| {
-                        let row = wrap(i_next_start + offset);
-                        trace_lde.get(row, col)
+            let packed_rows = |lde: &Mat, i_start: usize| -> Vec<PackedVal<SC>> {
+                (0..lde.width())
+                    .map(|col| {
+                        PackedVal::<SC>::from_fn(|offset| {
+                            let row = wrap(i_start + offset);
+                            lde.get(row, col)
+                        })
                     })
+                    .collect()
+            };
+            // Each extension field column of a later stage was committed as `D` base field columns.
+            let packed_ext_rows = |lde: &Mat, i_start: usize| -> Vec<PackedChallenge<SC>> {
+                packed_rows(lde, i_start)
+                    .chunks_exact(<SC::Challenge as AbstractExtensionField<SC::Val>>::D)
+                    .map(PackedChallenge::<SC>::from_base_slice)
+                    .collect()
+            };
+            let local = packed_rows(&trace_lde, i_local_start);
+            let next = packed_rows(&trace_lde, i_next_start);
+            let later_stages_local_and_next = later_stage_ldes
+                .iter()
+                .map(|lde| {
+                    (
+                        packed_ext_rows(lde, i_local_start),
+                        packed_ext_rows(lde, i_next_start),
+                    )
                 })
-                .collect();
+                .collect_vec();
+            let (preprocessed_local, preprocessed_next) =
+                preprocessed_lde.as_ref().map_or((vec![], vec![]), |lde| {
+                    (
+                        packed_rows(lde, i_local_start),
+                        packed_rows(lde, i_next_start),
+                    )
+                });
 
             let accumulator = PackedChallenge::<SC>::zero();
             let mut folder = ProverConstraintFolder {
@@ -192,15 +625,27 @@ where
                     local: &local,
                     next: &next,
                 },
+                later_stages: later_stages_local_and_next
+                    .iter()
+                    .map(|(local, next)| TwoRowMatrixView { local, next })
+                    .collect(),
+                later_stage_challenges: &later_stage_challenges,
+                public_values: &public_values,
+                preprocessed: TwoRowMatrixView {
+                    local: &preprocessed_local,
+                    next: &preprocessed_next,
+                },
                 is_first_row,
                 is_last_row,
                 is_transition,
+                row_point: x,
+                periodic_selectors: periodic,
                 alpha,
                 accumulator,
             };
             air.eval(&mut folder);
 
-            // quotient(x) = constraints(x) / Z_H(x)
+            // quotient(x) = constraints(x) / Z_{t H}(x)
             let zerofier_inv: PackedVal<SC> = zerofier_on_coset.eval_inverse_packed(i_local_start);
             let quotient = folder.accumulator * zerofier_inv;
 
@@ -212,6 +657,9 @@ where
                     .collect_vec();
                 SC::Challenge::from_base_slice(&quotient_value)
             })
-        })
-        .collect()
+        });
+    let mut values = config.pcs().allocator().allocate(quotient_size);
+    values.par_extend(quotients);
+    checkpoint(cancel)?;
+    Ok(values)
 }
@@ -2,7 +2,10 @@ use alloc::vec;
 use alloc::vec::Vec;
 use core::marker::PhantomData;
 
-use p3_air::{Air, AirBuilder};
+use p3_air::{
+    Air, AirBuilder, AirBuilderWithPublicValues, ExtensionBuilder, MultiStageAirBuilder,
+    PairBuilder, PeriodicAirBuilder, RowPointAirBuilder, StageShape,
+};
 use p3_field::Field;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_util::log2_ceil_usize;
@@ -45,7 +48,12 @@ where
     F: Field,
     A: Air<SymbolicAirBuilder<F>>,
 {
-    let mut builder = SymbolicAirBuilder::new(air.width());
+    let mut builder = SymbolicAirBuilder::new(
+        air.width(),
+        &air.later_stages(),
+        air.preprocessed_width(),
+        air.num_public_values(),
+    );
     air.eval(&mut builder);
     builder.constraints()
 }
@@ -53,25 +61,69 @@ where
 /// An `AirBuilder` for evaluating constraints symbolically, and recording them for later use.
 pub struct SymbolicAirBuilder<F: Field> {
     main: RowMajorMatrix<SymbolicVariable<F>>,
+    later_stages: Vec<RowMajorMatrix<SymbolicVariable<F>>>,
+    /// Zero-width if the AIR has no preprocessed trace.
+    preprocessed: RowMajorMatrix<SymbolicVariable<F>>,
+    later_stage_challenges: Vec<Vec<SymbolicExpression<F>>>,
+    public_values: Vec<SymbolicExpression<F>>,
     constraints: Vec<SymbolicExpression<F>>,
 }
 
 impl<F: Field> SymbolicAirBuilder<F> {
-    pub(crate) fn new(width: usize) -> Self {
+    /// Later stages' columns are numbered after the main trace's, in stage order, followed by the
+    /// preprocessed trace's.
+    pub(crate) fn new(
+        width: usize,
+        later_stages: &[StageShape],
+        preprocessed_width: usize,
+        num_public_values: usize,
+    ) -> Self {
+        let main = Self::window(0, width);
+        let mut first_column = width;
+        let later_stages_vars = later_stages
+            .iter()
+            .map(|shape| {
+                let stage = Self::window(first_column, shape.width);
+                first_column += shape.width;
+                stage
+            })
+            .collect();
+        let preprocessed = Self::window(first_column, preprocessed_width);
+        let later_stage_challenges = later_stages
+            .iter()
+            .enumerate()
+            .map(|(stage, shape)| {
+                (0..shape.num_challenges)
+                    .map(|index| SymbolicExpression::Challenge { stage, index })
+                    .collect()
+            })
+            .collect();
+        let public_values = (0..num_public_values)
+            .map(SymbolicExpression::PublicValue)
+            .collect();
+        Self {
+            main,
+            later_stages: later_stages_vars,
+            preprocessed,
+            later_stage_challenges,
+            public_values,
+            constraints: vec![],
+        }
+    }
+
+    fn window(first_column: usize, width: usize) -> RowMajorMatrix<SymbolicVariable<F>> {
         let values = [false, true]
             .into_iter()
             .flat_map(|is_next| {
-                (0..width).map(move |column| SymbolicVariable {
+                (first_column..first_column + width).map(move |column| SymbolicVariable {
                     is_next,
                     column,
                     _phantom: PhantomData,
                 })
             })
             .collect();
-        Self {
-            main: RowMajorMatrix::new(values, width),
-            constraints: vec![],
-        }
+        // Not `RowMajorMatrix::new`, which requires a positive width, since windows may be empty.
+        RowMajorMatrix { values, width }
     }
 
     pub(crate) fn constraints(self) -> Vec<SymbolicExpression<F>> {
@@ -109,3 +161,54 @@ impl<F: Field> AirBuilder for SymbolicAirBuilder<F> {
         self.constraints.push(x.into());
     }
 }
+
+/// Degrees don't depend on the field, so extension field constraints are recorded as if the
+/// extension were `F` itself.
+impl<F: Field> ExtensionBuilder for SymbolicAirBuilder<F> {
+    type EF = F;
+    type ExprEF = SymbolicExpression<F>;
+    type VarEF = SymbolicVariable<F>;
+
+    fn assert_zero_ext<I>(&mut self, x: I)
+    where
+        I: Into<Self::ExprEF>,
+    {
+        self.constraints.push(x.into());
+    }
+}
+
+impl<F: Field> MultiStageAirBuilder for SymbolicAirBuilder<F> {
+    type MS = RowMajorMatrix<Self::VarEF>;
+
+    fn later_stage(&self, stage: usize) -> Self::MS {
+        self.later_stages[stage].clone()
+    }
+
+    fn later_stage_challenges(&self, stage: usize) -> &[Self::ExprEF] {
+        &self.later_stage_challenges[stage]
+    }
+}
+
+impl<F: Field> PeriodicAirBuilder for SymbolicAirBuilder<F> {
+    fn is_periodic(&self, period: usize, offset: usize) -> Self::Expr {
+        SymbolicExpression::IsPeriodic { period, offset }
+    }
+}
+
+impl<F: Field> RowPointAirBuilder for SymbolicAirBuilder<F> {
+    fn row_point(&self) -> Self::Expr {
+        SymbolicExpression::RowPoint
+    }
+}
+
+impl<F: Field> AirBuilderWithPublicValues for SymbolicAirBuilder<F> {
+    fn public_values(&self) -> &[Self::Expr] {
+        &self.public_values
+    }
+}
+
+impl<F: Field> PairBuilder for SymbolicAirBuilder<F> {
+    fn preprocessed(&self) -> Self::M {
+        self.preprocessed.clone()
+    }
+}
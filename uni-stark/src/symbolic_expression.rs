@@ -14,7 +14,24 @@ pub enum SymbolicExpression<F: Field> {
     IsFirstRow,
     IsLastRow,
     IsTransition,
+    /// The point `g^r` at the current row `r`. Like `IsTransition`, it's a polynomial of degree
+    /// one, rather than of degree about `n` like a column, so its `degree_multiple` is zero.
+    RowPoint,
+    /// A selector for the rows `r` with `r % period == offset`. Like `IsFirstRow`, it's a multiple
+    /// of `Z_H` divided by a lower degree polynomial, so it counts as degree `n`.
+    IsPeriodic {
+        period: usize,
+        offset: usize,
+    },
     Constant(F),
+    /// The `index`th challenge drawn before committing to the given later stage. It is fixed
+    /// before that stage's trace is generated, so it has degree zero.
+    Challenge {
+        stage: usize,
+        index: usize,
+    },
+    /// The `index`th public value of the proof, which is fixed before any trace is committed.
+    PublicValue(usize),
     Add {
         x: Rc<Self>,
         y: Rc<Self>,
@@ -44,7 +61,11 @@ impl<F: Field> SymbolicExpression<F> {
             SymbolicExpression::IsFirstRow => 1,
             SymbolicExpression::IsLastRow => 1,
             SymbolicExpression::IsTransition => 0,
+            SymbolicExpression::RowPoint => 0,
+            SymbolicExpression::IsPeriodic { .. } => 1,
             SymbolicExpression::Constant(_) => 0,
+            SymbolicExpression::Challenge { .. } => 0,
+            SymbolicExpression::PublicValue(_) => 0,
             SymbolicExpression::Add {
                 degree_multiple, ..
             } => *degree_multiple,
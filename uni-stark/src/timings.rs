@@ -0,0 +1,181 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use p3_air::Air;
+use p3_commit::{COMMIT_PHASE_SPAN, LDE_SPAN, QUERY_PHASE_SPAN};
+use p3_matrix::dense::RowMajorMatrix;
+use tracing::dispatcher::{self, Dispatch};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+use crate::prover::{
+    LATER_STAGE_COMMIT_SPAN, QUOTIENT_COMMIT_SPAN, QUOTIENT_SPAN, TRACE_COMMIT_SPAN,
+};
+use crate::{prove, Proof, ProverConstraintFolder, ProvingKey, StarkGenericConfig};
+
+/// The wall-clock time spent in a phase of `prove_timed`, and the number of times it was entered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PhaseTiming {
+    pub duration: Duration,
+    /// How many of the phase's spans were entered, such as one per LDE. A phase which was entered
+    /// may still take no measurable time, if the clock is coarse.
+    pub spans: usize,
+}
+
+/// Wall-clock time spent in each phase of `prove_timed`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProveTimings {
+    /// Building the Merkle trees for the trace and any later stages, excluding their LDEs. The
+    /// preprocessed trace is committed to by `setup`, so it isn't included.
+    pub trace_commit: PhaseTiming,
+    /// Computing the LDEs of every committed matrix, i.e. the trace, later stages and quotient
+    /// chunks.
+    pub lde: PhaseTiming,
+    /// Evaluating the quotient polynomial over the quotient domain.
+    pub quotient: PhaseTiming,
+    /// Building the Merkle tree for the quotient chunks, excluding their LDE.
+    pub quotient_commit: PhaseTiming,
+    /// Folding and committing to each FRI layer.
+    pub fri_commit: PhaseTiming,
+    /// Answering the FRI queries.
+    pub fri_query: PhaseTiming,
+}
+
+/// Like `prove`, but also measures how long each phase takes.
+///
+/// Phases are timed by the spans which the prover and PCS already enter, so nothing is measured
+/// outside of this function. While it runs, those spans go to the timer rather than to the
+/// default subscriber of the calling thread.
+pub fn prove_timed<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, SC::Val, SC::Challenge>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    pk: &ProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<SC::Val>,
+) -> (Proof<SC>, ProveTimings)
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let dispatch = Dispatch::new(PhaseTimer::default());
+    let proof = dispatcher::with_default(&dispatch, || prove(config, pk, air, challenger, trace));
+    let timer = dispatch
+        .downcast_ref::<PhaseTimer>()
+        .expect("dispatch wraps a PhaseTimer");
+    let timings = timer.state.lock().unwrap().timings;
+    (proof, timings)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+    TraceCommit,
+    Lde,
+    Quotient,
+    QuotientCommit,
+    FriCommit,
+    FriQuery,
+}
+
+impl Phase {
+    const ALL: [Self; 6] = [
+        Self::TraceCommit,
+        Self::Lde,
+        Self::Quotient,
+        Self::QuotientCommit,
+        Self::FriCommit,
+        Self::FriQuery,
+    ];
+
+    fn from_span_name(name: &str) -> Option<Self> {
+        match name {
+            TRACE_COMMIT_SPAN | LATER_STAGE_COMMIT_SPAN => Some(Self::TraceCommit),
+            LDE_SPAN => Some(Self::Lde),
+            QUOTIENT_SPAN => Some(Self::Quotient),
+            QUOTIENT_COMMIT_SPAN => Some(Self::QuotientCommit),
+            COMMIT_PHASE_SPAN => Some(Self::FriCommit),
+            QUERY_PHASE_SPAN => Some(Self::FriQuery),
+            _ => None,
+        }
+    }
+
+    fn from_id(id: &Id) -> Self {
+        Self::ALL[id.into_u64() as usize - 1]
+    }
+
+    fn id(self) -> Id {
+        Id::from_u64(self as u64 + 1)
+    }
+
+    fn timing_mut(self, timings: &mut ProveTimings) -> &mut PhaseTiming {
+        match self {
+            Self::TraceCommit => &mut timings.trace_commit,
+            Self::Lde => &mut timings.lde,
+            Self::Quotient => &mut timings.quotient,
+            Self::QuotientCommit => &mut timings.quotient_commit,
+            Self::FriCommit => &mut timings.fri_commit,
+            Self::FriQuery => &mut timings.fri_query,
+        }
+    }
+}
+
+/// A `Subscriber` which is only interested in the spans of a phase, and adds up the time spent in
+/// each. Time spent in a nested phase, such as an LDE within a commitment, counts only towards the
+/// inner phase.
+#[derive(Default)]
+struct PhaseTimer {
+    state: Mutex<PhaseTimerState>,
+}
+
+#[derive(Default)]
+struct PhaseTimerState {
+    timings: ProveTimings,
+    /// The phases which have been entered but not yet exited, with the time each was entered and
+    /// the time spent in phases nested within it so far.
+    open: Vec<(Phase, Instant, Duration)>,
+}
+
+impl Subscriber for PhaseTimer {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.is_span() && Phase::from_span_name(metadata.name()).is_some()
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        // Only spans that `enabled` accepted get here, so each has a phase. Spans of the same phase
+        // share an ID, since the timer keeps no other state per span.
+        Phase::from_span_name(span.metadata().name())
+            .expect("only phase spans are enabled")
+            .id()
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .open
+            .push((Phase::from_id(span), Instant::now(), Duration::ZERO));
+    }
+
+    fn exit(&self, span: &Id) {
+        let mut state = self.state.lock().unwrap();
+        let (phase, entered, nested) = state.open.pop().expect("exited a span that wasn't entered");
+        debug_assert_eq!(phase, Phase::from_id(span));
+        let elapsed = entered.elapsed();
+        let timing = phase.timing_mut(&mut state.timings);
+        timing.duration += elapsed.saturating_sub(nested);
+        timing.spans += 1;
+        if let Some((_, _, parent_nested)) = state.open.last_mut() {
+            *parent_nested += elapsed;
+        }
+    }
+}
@@ -1,118 +1,268 @@
 use alloc::vec;
 use alloc::vec::Vec;
+use core::iter;
 
+use itertools::Itertools;
 use p3_air::{Air, BaseAir, TwoRowMatrixView};
-use p3_challenger::{CanObserve, FieldChallenger};
 use p3_commit::UnivariatePcs;
 use p3_field::{AbstractExtensionField, AbstractField, Field, TwoAdicField};
 use p3_matrix::Dimensions;
 use p3_util::reverse_slice_index_bits;
 use tracing::instrument;
 
-use crate::symbolic_builder::{get_log_quotient_degree, SymbolicAirBuilder};
-use crate::{Proof, StarkGenericConfig, VerifierConstraintFolder};
+use crate::zerofier_coset::periodic_rows_zerofier;
+use crate::{
+    Com, Proof, StarkChallenges, StarkGenericConfig, VerifierConstraintFolder, VerifyingKey,
+};
 
+/// Like `verify`, but also checks that the proof's `Proof::trace_digest` is `trace_digest`, e.g.
+/// one which the next proof in a chain of continuations takes as public values.
+pub fn verify_with_trace_digest<SC, A>(
+    config: &SC,
+    vk: &VerifyingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    trace_digest: &Com<SC>,
+) -> Result<(), VerificationError>
+where
+    SC: StarkGenericConfig,
+    Com<SC>: PartialEq,
+    A: BaseAir<SC::Val> + for<'a> Air<VerifierConstraintFolder<'a, SC::Challenge>>,
+{
+    if proof.trace_digest() != trace_digest {
+        return Err(VerificationError::TraceDigestMismatch);
+    }
+    verify(config, vk, air, challenger, proof)
+}
+
+/// Verify a proof of `air`, given a verifying key which `setup` computed for the same AIR.
 #[instrument(skip_all)]
 pub fn verify<SC, A>(
     config: &SC,
+    vk: &VerifyingKey<SC>,
     air: &A,
     challenger: &mut SC::Challenger,
     proof: &Proof<SC>,
 ) -> Result<(), VerificationError>
 where
     SC: StarkGenericConfig,
-    A: Air<SymbolicAirBuilder<SC::Val>> + for<'a> Air<VerifierConstraintFolder<'a, SC::Challenge>>,
+    A: BaseAir<SC::Val> + for<'a> Air<VerifierConstraintFolder<'a, SC::Challenge>>,
 {
-    let log_quotient_degree = get_log_quotient_degree::<SC::Val, A>(air);
+    let log_quotient_degree = vk.log_quotient_degree;
     let quotient_degree = 1 << log_quotient_degree;
+    let periodic_selectors = vk.periodic_selectors.clone();
 
     let Proof {
         commitments,
         opened_values,
         opening_proof,
         degree_bits,
+        public_values,
     } = proof;
 
     let air_width = <A as BaseAir<SC::Val>>::width(air);
-    let quotient_chunks = quotient_degree * <SC::Challenge as AbstractExtensionField<SC::Val>>::D;
+    let later_stages = <A as BaseAir<SC::Val>>::later_stages(air);
+    let challenge_ext_degree = <SC::Challenge as AbstractExtensionField<SC::Val>>::D;
+    let quotient_chunks = quotient_degree * challenge_ext_degree;
+    // Later stages are over the extension field, and each of their columns is committed as `D`
+    // base field columns.
+    let later_stage_widths = later_stages
+        .iter()
+        .map(|shape| shape.width * challenge_ext_degree)
+        .collect_vec();
+    let valid_later_stages = commitments.later_stages.len() == later_stages.len()
+        && opened_values.later_stages_local.len() == later_stages.len()
+        && opened_values.later_stages_next.len() == later_stages.len()
+        && later_stage_widths
+            .iter()
+            .enumerate()
+            .all(|(stage, &width)| {
+                opened_values.later_stages_local[stage].len() == width
+                    && opened_values.later_stages_next[stage].len() == width
+            });
+    let preprocessed_width = vk.preprocessed.as_ref().map_or(0, |p| p.width);
+    let valid_preprocessed = opened_values.preprocessed_local.len() == preprocessed_width
+        && opened_values.preprocessed_next.len() == preprocessed_width
+        && vk
+            .preprocessed
+            .as_ref()
+            .map_or(true, |p| p.degree_bits == *degree_bits);
     let valid_shape = opened_values.trace_local.len() == air_width
         && opened_values.trace_next.len() == air_width
-        && opened_values.quotient_chunks.len() == quotient_chunks;
+        && valid_later_stages
+        && valid_preprocessed
+        && opened_values.quotient_chunks.len() == quotient_chunks
+        && public_values.len() == air.num_public_values();
     if !valid_shape {
         return Err(VerificationError::InvalidProofShape);
     }
+    let valid_periodic_selectors = periodic_selectors.iter().all(|&(period, offset)| {
+        period.is_power_of_two()
+            && period.trailing_zeros() as usize <= *degree_bits
+            && offset < period
+    });
+    if !valid_periodic_selectors {
+        return Err(VerificationError::InvalidPeriodicSelector);
+    }
 
     let g_subgroup = SC::Val::two_adic_generator(*degree_bits);
 
-    challenger.observe(commitments.trace.clone());
-    let alpha: SC::Challenge = challenger.sample_ext_element();
-    challenger.observe(commitments.quotient_chunks.clone());
-    let zeta: SC::Challenge = challenger.sample_ext_element();
+    let StarkChallenges {
+        later_stage_challenges,
+        alpha,
+        zeta,
+    } = StarkChallenges::<SC>::sample(
+        challenger,
+        vk.preprocessed.as_ref().map(|p| &p.commitment),
+        commitments,
+        public_values,
+        &later_stages,
+    );
 
+    // The preprocessed trace and every stage of the witness are opened at `zeta` and `g zeta`, in
+    // the order they were committed.
     let local_and_next = [vec![zeta, zeta * g_subgroup]];
-    let commits_and_points = &[
-        (commitments.trace.clone(), local_and_next.as_slice()),
-        (
+    let quotient_points = [vec![zeta.exp_power_of_2(log_quotient_degree)]];
+    let commits_and_points = vk
+        .preprocessed
+        .iter()
+        .map(|p| &p.commitment)
+        .chain(iter::once(&commitments.trace))
+        .chain(&commitments.later_stages)
+        .map(|commit| (commit.clone(), local_and_next.as_slice()))
+        .chain(iter::once((
             commitments.quotient_chunks.clone(),
-            &[vec![zeta.exp_power_of_2(log_quotient_degree)]],
-        ),
-    ];
-    let values = vec![
+            quotient_points.as_slice(),
+        )))
+        .collect_vec();
+    let preprocessed_values = vk.preprocessed.as_ref().map(|_| {
         vec![vec![
+            opened_values.preprocessed_local.clone(),
+            opened_values.preprocessed_next.clone(),
+        ]]
+    });
+    let values = preprocessed_values
+        .into_iter()
+        .chain(iter::once(vec![vec![
             opened_values.trace_local.clone(),
             opened_values.trace_next.clone(),
-        ]],
-        vec![vec![opened_values.quotient_chunks.clone()]],
-    ];
-    let dims = &[
-        vec![Dimensions {
-            width: air_width,
-            height: 1 << degree_bits,
-        }],
-        vec![Dimensions {
-            width: quotient_chunks,
-            height: 1 << degree_bits,
-        }],
-    ];
+        ]]))
+        .chain(
+            opened_values
+                .later_stages_local
+                .iter()
+                .zip(&opened_values.later_stages_next)
+                .map(|(local, next)| vec![vec![local.clone(), next.clone()]]),
+        )
+        .chain(iter::once(vec![vec![opened_values
+            .quotient_chunks
+            .clone()]]))
+        .collect_vec();
+    let dims = vk
+        .preprocessed
+        .iter()
+        .map(|p| p.width)
+        .chain(iter::once(air_width))
+        .chain(later_stage_widths)
+        .chain(iter::once(quotient_chunks))
+        .map(|width| {
+            vec![Dimensions {
+                width,
+                height: 1 << degree_bits,
+            }]
+        })
+        .collect_vec();
     config
         .pcs()
-        .verify_multi_batches(commits_and_points, dims, values, opening_proof, challenger)
+        .verify_multi_batches(
+            &commits_and_points,
+            &dims,
+            values,
+            opening_proof,
+            challenger,
+        )
         .map_err(|_| VerificationError::InvalidOpeningArgument)?;
 
+    // Undo the flattening of extension field columns into `D` base field columns, given the
+    // openings of the base field columns.
+    let unflatten = |values: &[SC::Challenge]| -> Vec<SC::Challenge> {
+        values
+            .chunks(challenge_ext_degree)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &c)| {
+                        <SC::Challenge as AbstractExtensionField<SC::Val>>::monomial(i) * c
+                    })
+                    .sum()
+            })
+            .collect()
+    };
+
     // Derive the opening of the quotient polynomial, which was split into degree n chunks, then
     // flattened into D base field polynomials. We first undo the flattening.
-    let challenge_ext_degree = <SC::Challenge as AbstractExtensionField<SC::Val>>::D;
-    let mut quotient_parts: Vec<SC::Challenge> = opened_values
-        .quotient_chunks
-        .chunks(challenge_ext_degree)
-        .map(|chunk| {
-            chunk
-                .iter()
-                .enumerate()
-                .map(|(i, &c)| <SC::Challenge as AbstractExtensionField<SC::Val>>::monomial(i) * c)
-                .sum()
-        })
-        .collect();
+    let mut quotient_parts = unflatten(&opened_values.quotient_chunks);
     // Then we reconstruct the larger quotient polynomial from its degree-n parts.
     reverse_slice_index_bits(&mut quotient_parts);
-    let quotient: SC::Challenge = zeta
-        .powers()
-        .zip(quotient_parts)
-        .map(|(weight, part)| part * weight)
-        .sum();
+    let (quotient, _) = quotient_parts.iter().fold(
+        (SC::Challenge::zero(), SC::Challenge::one()),
+        |(acc, zeta_power), &part| (acc + zeta_power * part, zeta_power * zeta),
+    );
 
-    let z_h = zeta.exp_power_of_2(*degree_bits) - SC::Challenge::one();
-    let is_first_row = z_h / (zeta - SC::Val::one());
-    let is_last_row = z_h / (zeta - g_subgroup.inverse());
-    let is_transition = zeta - g_subgroup.inverse();
+    // The trace is defined over `t H`, so we evaluate the selectors and `Z_{t H}(zeta)` in terms of
+    // `zeta / t`, which lets them take their usual forms over `H`.
+    let zeta_unshifted = zeta * config.trace_domain_shift().inverse();
+    let z_h = zeta_unshifted.exp_power_of_2(*degree_bits) - SC::Challenge::one();
+    let is_first_row = z_h / (zeta_unshifted - SC::Val::one());
+    let is_last_row = z_h / (zeta_unshifted - g_subgroup.inverse());
+    let is_transition = zeta_unshifted - g_subgroup.inverse();
+    let periodic_selectors = periodic_selectors
+        .into_iter()
+        .map(|(period, offset)| {
+            let (log_m, c) = periodic_rows_zerofier::<SC::Val>(*degree_bits, period, offset);
+            (
+                (period, offset),
+                z_h / (zeta_unshifted.exp_power_of_2(log_m) - c),
+            )
+        })
+        .collect_vec();
+    let later_stages_local = opened_values
+        .later_stages_local
+        .iter()
+        .map(|values| unflatten(values))
+        .collect_vec();
+    let later_stages_next = opened_values
+        .later_stages_next
+        .iter()
+        .map(|values| unflatten(values))
+        .collect_vec();
+    let public_values = public_values
+        .iter()
+        .map(|&v| SC::Challenge::from_base(v))
+        .collect_vec();
     let mut folder = VerifierConstraintFolder {
         main: TwoRowMatrixView {
             local: &opened_values.trace_local,
             next: &opened_values.trace_next,
         },
+        later_stages: later_stages_local
+            .iter()
+            .zip(&later_stages_next)
+            .map(|(local, next)| TwoRowMatrixView { local, next })
+            .collect(),
+        later_stage_challenges: &later_stage_challenges,
+        public_values: &public_values,
+        preprocessed: TwoRowMatrixView {
+            local: &opened_values.preprocessed_local,
+            next: &opened_values.preprocessed_next,
+        },
         is_first_row,
         is_last_row,
         is_transition,
+        row_point: zeta_unshifted,
+        periodic_selectors: &periodic_selectors,
         alpha,
         accumulator: SC::Challenge::zero(),
     };
@@ -120,7 +270,7 @@ where
     let folded_constraints = folder.accumulator;
 
     // Finally, check that
-    //     folded_constraints(zeta) = Z_H(zeta) * quotient(zeta)
+    //     folded_constraints(zeta) = Z_{t H}(zeta) * quotient(zeta)
     if folded_constraints != z_h * quotient {
         return Err(VerificationError::OodEvaluationMismatch);
     }
@@ -136,4 +286,9 @@ pub enum VerificationError {
     /// Out-of-domain evaluation mismatch, i.e. `constraints(zeta)` did not match
     /// `quotient(zeta) Z_H(zeta)`.
     OodEvaluationMismatch,
+    /// The proof's trace digest differed from the one passed to `verify_with_trace_digest`.
+    TraceDigestMismatch,
+    /// A periodic selector's period wasn't a power of two no larger than the trace height, or its
+    /// offset wasn't less than its period.
+    InvalidPeriodicSelector,
 }
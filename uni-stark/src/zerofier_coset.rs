@@ -5,6 +5,7 @@ use p3_field::{
     batch_multiplicative_inverse, cyclic_subgroup_coset_known_order, Field, PackedField,
     TwoAdicField,
 };
+use p3_util::log2_strict_usize;
 
 /// Precomputations of the evaluation of `Z_H(X) = X^n - 1` on a coset `s K` with `H <= K`.
 pub struct ZerofierOnCoset<F: Field> {
@@ -81,4 +82,48 @@ impl<F: TwoAdicField> ZerofierOnCoset<F> {
             .map(|(&z_h, inv)| z_h * inv)
             .collect()
     }
+
+    /// Evaluate the periodic selector `Z_H(x) / (x^m - c)` on our coset `s K`, where `x^m - c` is
+    /// given by `periodic_rows_zerofier`. Over `H`, it's nonzero exactly on the rows `r` with
+    /// `r % period == offset`. Like `lagrange_basis_unnormalized`, it isn't normalized.
+    pub fn periodic_selector_unnormalized(&self, period: usize, offset: usize) -> Vec<F> {
+        let log_coset_size = self.log_n + self.rate_bits;
+        let coset_size = 1 << log_coset_size;
+        let g_k = F::two_adic_generator(log_coset_size);
+
+        let (log_m, c) = periodic_rows_zerofier::<F>(self.log_n, period, offset);
+        let denominators = cyclic_subgroup_coset_known_order(g_k, self.coset_shift, coset_size)
+            .map(|x| x.exp_power_of_2(log_m) - c)
+            .collect_vec();
+        let inverses = batch_multiplicative_inverse(&denominators);
+
+        self.evals
+            .iter()
+            .cycle()
+            .zip(inverses)
+            .map(|(&z_h, inv)| z_h * inv)
+            .collect()
+    }
+}
+
+/// Returns `log2(m)` and `c` such that `x^m - c` vanishes on exactly the rows `r` of `H` with
+/// `r % period == offset`. These rows are `g_H^offset` times the subgroup of order
+/// `m = n / period`, so `c = g_H^(offset * m)`.
+pub(crate) fn periodic_rows_zerofier<F: TwoAdicField>(
+    log_n: usize,
+    period: usize,
+    offset: usize,
+) -> (usize, F) {
+    let log_period = log2_strict_usize(period);
+    assert!(
+        log_period <= log_n,
+        "period {period} exceeds the trace height"
+    );
+    assert!(
+        offset < period,
+        "offset {offset} isn't less than period {period}"
+    );
+    let log_m = log_n - log_period;
+    let c = F::two_adic_generator(log_n).exp_u64((offset << log_m) as u64);
+    (log_m, c)
 }
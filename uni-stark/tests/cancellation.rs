@@ -0,0 +1,131 @@
+mod common;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::AbstractField;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_uni_stark::{
+    prove_cancellable, setup, verify, ProverConstraintFolder, ProverError, SymbolicAirBuilder,
+    VerifierConstraintFolder,
+};
+
+use crate::common::{config_and_challenger, Challenge, MyConfig, Val};
+
+/// Asserts `a * b = c` on each row. While the prover evaluates constraints for the quotient, it
+/// sets `cancel_while_proving` (if given), simulating a client disconnecting mid-proof.
+struct MulAir<'a> {
+    cancel_while_proving: Option<&'a AtomicBool>,
+}
+
+impl<'a> MulAir<'a> {
+    fn eval_mul<AB: AirBuilder>(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        builder.assert_zero(local[0] * local[1] - local[2]);
+    }
+}
+
+impl<'a, F> BaseAir<F> for MulAir<'a> {
+    fn width(&self) -> usize {
+        3
+    }
+}
+
+impl<'a> Air<SymbolicAirBuilder<Val>> for MulAir<'a> {
+    fn eval(&self, builder: &mut SymbolicAirBuilder<Val>) {
+        self.eval_mul(builder);
+    }
+}
+
+#[cfg(debug_assertions)]
+impl<'a, 'b> Air<p3_uni_stark::DebugConstraintBuilder<'b, Val, Challenge>> for MulAir<'a> {
+    fn eval(&self, builder: &mut p3_uni_stark::DebugConstraintBuilder<'b, Val, Challenge>) {
+        self.eval_mul(builder);
+    }
+}
+
+impl<'a, 'b> Air<ProverConstraintFolder<'b, MyConfig>> for MulAir<'a> {
+    fn eval(&self, builder: &mut ProverConstraintFolder<'b, MyConfig>) {
+        if let Some(cancel) = self.cancel_while_proving {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        self.eval_mul(builder);
+    }
+}
+
+impl<'a, 'b> Air<VerifierConstraintFolder<'b, Challenge>> for MulAir<'a> {
+    fn eval(&self, builder: &mut VerifierConstraintFolder<'b, Challenge>) {
+        self.eval_mul(builder);
+    }
+}
+
+fn mul_trace() -> RowMajorMatrix<Val> {
+    let height = 1 << 6;
+    let mut values = Vec::with_capacity(height * 3);
+    for i in 0..height {
+        let a = Val::from_canonical_usize(i);
+        let b = Val::from_canonical_usize(i + 7);
+        values.extend([a, b, a * b]);
+    }
+    RowMajorMatrix::new(values, 3)
+}
+
+#[test]
+fn test_not_cancelled() {
+    let (config, challenger) = config_and_challenger();
+    let air = MulAir {
+        cancel_while_proving: None,
+    };
+    let cancel = AtomicBool::new(false);
+    let (pk, vk) = setup(&config, &air);
+    let proof = prove_cancellable(
+        &config,
+        &pk,
+        &air,
+        &mut challenger.clone(),
+        mul_trace(),
+        &cancel,
+    )
+    .expect("proving should not be cancelled");
+    verify(&config, &vk, &air, &mut challenger.clone(), &proof).expect("verification failed");
+}
+
+#[test]
+fn test_cancelled_before_proving() {
+    let (config, challenger) = config_and_challenger();
+    let air = MulAir {
+        cancel_while_proving: None,
+    };
+    let cancel = AtomicBool::new(true);
+    let (pk, _) = setup(&config, &air);
+    let result = prove_cancellable(
+        &config,
+        &pk,
+        &air,
+        &mut challenger.clone(),
+        mul_trace(),
+        &cancel,
+    );
+    assert_eq!(result.err(), Some(ProverError::Cancelled));
+}
+
+#[test]
+fn test_cancelled_mid_proof() {
+    let (config, challenger) = config_and_challenger();
+    let cancel = AtomicBool::new(false);
+    let air = MulAir {
+        cancel_while_proving: Some(&cancel),
+    };
+    let (pk, _) = setup(&config, &air);
+    let result = prove_cancellable(
+        &config,
+        &pk,
+        &air,
+        &mut challenger.clone(),
+        mul_trace(),
+        &cancel,
+    );
+    assert_eq!(result.err(), Some(ProverError::Cancelled));
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use p3_air::StageShape;
+use p3_challenger::{CanObserve, CanSample, FieldChallenger};
+use p3_field::AbstractField;
+use p3_symmetric::Hash;
+use p3_uni_stark::{
+    Commitments, StarkChallenges, FRI_LABEL, LATER_STAGE_COMMIT_LABEL, PREPROCESSED_COMMIT_LABEL,
+    PUBLIC_VALUES_LABEL, QUOTIENT_COMMIT_LABEL, TRACE_COMMIT_LABEL,
+};
+
+use crate::common::{random_perm, Challenge, Challenger, MyConfig, Val};
+
+fn commit(seed: u32) -> Hash<Val, Val, 8> {
+    core::array::from_fn(|i| Val::from_canonical_u32(seed + i as u32)).into()
+}
+
+#[test]
+fn test_challenge_order() {
+    let perm = random_perm();
+    let commitments = Commitments {
+        trace: commit(1),
+        later_stages: vec![],
+        quotient_chunks: commit(100),
+    };
+
+    let mut challenger = Challenger::new(perm.clone());
+    let challenges =
+        StarkChallenges::<MyConfig>::sample(&mut challenger, None, &commitments, &[], &[]);
+    let next: Val = challenger.sample();
+
+    // The transcript which `sample` must follow.
+    let mut expected = Challenger::new(perm);
+    expected.observe_label(TRACE_COMMIT_LABEL);
+    expected.observe(commit(1));
+    let alpha: Challenge = expected.sample_ext_element();
+    expected.observe_label(QUOTIENT_COMMIT_LABEL);
+    expected.observe(commit(100));
+    let zeta: Challenge = expected.sample_ext_element();
+    expected.observe_label(FRI_LABEL);
+    let expected_next: Val = expected.sample();
+
+    assert!(challenges.later_stage_challenges.is_empty());
+    assert_eq!(challenges.alpha, alpha);
+    assert_eq!(challenges.zeta, zeta);
+    assert_eq!(next, expected_next);
+    assert_ne!(challenges.alpha, challenges.zeta);
+}
+
+#[test]
+fn test_later_stage_challenge_order() {
+    let perm = random_perm();
+    let commitments = Commitments {
+        trace: commit(1),
+        later_stages: vec![commit(10), commit(20)],
+        quotient_chunks: commit(100),
+    };
+    let later_stages = [
+        StageShape {
+            num_challenges: 2,
+            width: 1,
+        },
+        StageShape {
+            num_challenges: 1,
+            width: 3,
+        },
+    ];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let challenges = StarkChallenges::<MyConfig>::sample(
+        &mut challenger,
+        None,
+        &commitments,
+        &[],
+        &later_stages,
+    );
+
+    let mut expected = Challenger::new(perm);
+    expected.observe_label(TRACE_COMMIT_LABEL);
+    expected.observe(commit(1));
+    let first: [Challenge; 2] = [expected.sample_ext_element(), expected.sample_ext_element()];
+    expected.observe_label(LATER_STAGE_COMMIT_LABEL);
+    expected.observe(commit(10));
+    let second: Challenge = expected.sample_ext_element();
+    expected.observe_label(LATER_STAGE_COMMIT_LABEL);
+    expected.observe(commit(20));
+    let alpha: Challenge = expected.sample_ext_element();
+    expected.observe_label(QUOTIENT_COMMIT_LABEL);
+    expected.observe(commit(100));
+    let zeta: Challenge = expected.sample_ext_element();
+
+    assert_eq!(
+        challenges.later_stage_challenges,
+        vec![first.to_vec(), vec![second]]
+    );
+    assert_eq!(challenges.alpha, alpha);
+    assert_eq!(challenges.zeta, zeta);
+}
+
+#[test]
+fn test_preprocessed_challenge_order() {
+    let perm = random_perm();
+    let commitments = Commitments {
+        trace: commit(1),
+        later_stages: vec![],
+        quotient_chunks: commit(100),
+    };
+
+    let mut challenger = Challenger::new(perm.clone());
+    let challenges = StarkChallenges::<MyConfig>::sample(
+        &mut challenger,
+        Some(&commit(50)),
+        &commitments,
+        &[],
+        &[],
+    );
+
+    let mut expected = Challenger::new(perm);
+    expected.observe_label(PREPROCESSED_COMMIT_LABEL);
+    expected.observe(commit(50));
+    expected.observe_label(TRACE_COMMIT_LABEL);
+    expected.observe(commit(1));
+    let alpha: Challenge = expected.sample_ext_element();
+    expected.observe_label(QUOTIENT_COMMIT_LABEL);
+    expected.observe(commit(100));
+    let zeta: Challenge = expected.sample_ext_element();
+
+    assert_eq!(challenges.alpha, alpha);
+    assert_eq!(challenges.zeta, zeta);
+}
+
+#[test]
+fn test_public_values_challenge_order() {
+    let perm = random_perm();
+    let commitments = Commitments {
+        trace: commit(1),
+        later_stages: vec![commit(10)],
+        quotient_chunks: commit(100),
+    };
+    let public_values = [3, 1, 4].map(Val::from_canonical_u32);
+    let later_stages = [StageShape {
+        num_challenges: 1,
+        width: 1,
+    }];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let challenges = StarkChallenges::<MyConfig>::sample(
+        &mut challenger,
+        None,
+        &commitments,
+        &public_values,
+        &later_stages,
+    );
+
+    let mut expected = Challenger::new(perm);
+    expected.observe_label(PUBLIC_VALUES_LABEL);
+    expected.observe_slice(&public_values);
+    expected.observe_label(TRACE_COMMIT_LABEL);
+    expected.observe(commit(1));
+    let stage_challenge: Challenge = expected.sample_ext_element();
+    expected.observe_label(LATER_STAGE_COMMIT_LABEL);
+    expected.observe(commit(10));
+    let alpha: Challenge = expected.sample_ext_element();
+
+    assert_eq!(
+        challenges.later_stage_challenges,
+        vec![vec![stage_challenge]]
+    );
+    assert_eq!(challenges.alpha, alpha);
+}
@@ -0,0 +1,127 @@
+//! The configuration and AIRs shared by these tests. Each test file compiles this module separately
+//! and uses only some of it.
+#![allow(dead_code)]
+
+use itertools::Itertools;
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::BabyBear;
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs, TwoAdicFriPcsConfig};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{DiffusionMatrixBabybear, Poseidon2};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::StarkConfig;
+use rand::{thread_rng, Rng};
+
+pub type Val = BabyBear;
+pub type Challenge = BinomialExtensionField<Val, 4>;
+pub type Perm = Poseidon2<Val, DiffusionMatrixBabybear, 16, 7>;
+pub type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+pub type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+pub type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+pub type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+pub type Dft = Radix2DitParallel;
+pub type Challenger = DuplexChallenger<Val, Perm, 16>;
+pub type Pcs =
+    TwoAdicFriPcs<TwoAdicFriPcsConfig<Val, Challenge, Challenger, Dft, ValMmcs, ChallengeMmcs>>;
+pub type MyConfig = StarkConfig<Val, Challenge, Pcs, Challenger>;
+
+pub fn random_perm() -> Perm {
+    Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng())
+}
+
+pub fn val_mmcs(perm: &Perm) -> ValMmcs {
+    ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()))
+}
+
+pub fn fri_config(val_mmcs: &ValMmcs) -> FriConfig<ChallengeMmcs> {
+    FriConfig {
+        log_blowup: 1,
+        num_queries: 40,
+        proof_of_work_bits: 8,
+        mmcs: ChallengeMmcs::new(val_mmcs.clone()),
+    }
+}
+
+pub fn pcs(perm: &Perm) -> Pcs {
+    let val_mmcs = val_mmcs(perm);
+    Pcs::new(fri_config(&val_mmcs), Dft {}, val_mmcs)
+}
+
+pub fn config_and_challenger() -> (MyConfig, Challenger) {
+    let perm = random_perm();
+    (StarkConfig::new(pcs(&perm)), Challenger::new(perm))
+}
+
+/// Asserts `a * b = c` on each row.
+pub struct MulAir;
+
+impl<F> BaseAir<F> for MulAir {
+    fn width(&self) -> usize {
+        3
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for MulAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        builder.assert_zero(local[0] * local[1] - local[2]);
+    }
+}
+
+/// A trace for `MulAir` with random `a` and `b`.
+pub fn random_mul_trace(rows: usize) -> RowMajorMatrix<Val> {
+    let mut rng = thread_rng();
+    let mut values = vec![Val::default(); rows * 3];
+    for (a, b, c) in values.iter_mut().tuples() {
+        *a = rng.gen();
+        *b = rng.gen();
+        *c = *a * *b;
+    }
+    RowMajorMatrix::new(values, 3)
+}
+
+/// Fibonacci from `(0, 1)`, with constraints involving each of the selectors. The last row's
+/// window wraps around to the first, which it checks again.
+pub struct FibAir;
+
+impl<F> BaseAir<F> for FibAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for FibAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+
+        builder.when_first_row().assert_zero(local[0]);
+        builder.when_first_row().assert_one(local[1]);
+
+        let mut when_transition = builder.when_transition();
+        when_transition.assert_eq(next[0], local[1]);
+        when_transition.assert_eq(next[1], local[0] + local[1]);
+
+        builder.when_last_row().assert_zero(next[0]);
+    }
+}
+
+pub fn fib_trace(rows: usize) -> RowMajorMatrix<Val> {
+    let mut values = Vec::with_capacity(rows * 2);
+    let (mut a, mut b) = (Val::zero(), Val::one());
+    for _ in 0..rows {
+        values.extend([a, b]);
+        (a, b) = (b, a + b);
+    }
+    RowMajorMatrix::new(values, 2)
+}
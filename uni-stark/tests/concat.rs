@@ -0,0 +1,164 @@
+mod common;
+
+use p3_air::{Air, BaseAir, ConcatAir, PairBuilder};
+use p3_field::{AbstractField, Field};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_uni_stark::{prove, setup, verify, Proof, ProvingKey};
+
+use crate::common::{config_and_challenger, Challenger, MyConfig, Val};
+
+const HEIGHT: usize = 1 << 5;
+
+/// Asserts `y = p * x` on each row, where `p` is the row index plus one, taken from a preprocessed
+/// column.
+///
+/// A `relaxed` AIR has the same shape but no constraints, here and in `CounterAir`, so that they
+/// can prove traces which the strict AIRs don't accept.
+struct ScaleAir {
+    relaxed: bool,
+}
+
+impl<F: Field> BaseAir<F> for ScaleAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        let values = (1..=HEIGHT).map(F::from_canonical_usize).collect();
+        Some(RowMajorMatrix::new_col(values))
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: PairBuilder> Air<AB> for ScaleAir {
+    fn eval(&self, builder: &mut AB) {
+        if self.relaxed {
+            return;
+        }
+        let preprocessed = builder.preprocessed();
+        let main = builder.main();
+        let (x, y) = (main.row_slice(0)[0], main.row_slice(0)[1]);
+        builder.assert_eq(y, preprocessed.row_slice(0)[0] * x);
+    }
+}
+
+/// Asserts that `z` starts at zero and increases by `s * t` on each row, where `s` and `t` are
+/// preprocessed columns.
+struct CounterAir {
+    relaxed: bool,
+}
+
+impl<F: Field> BaseAir<F> for CounterAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        let values = (0..HEIGHT)
+            .flat_map(|i| [F::from_canonical_usize(i + 3), F::from_canonical_u32(5)])
+            .collect();
+        Some(RowMajorMatrix::new(values, 2))
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: PairBuilder> Air<AB> for CounterAir {
+    fn eval(&self, builder: &mut AB) {
+        if self.relaxed {
+            return;
+        }
+        let preprocessed = builder.preprocessed();
+        let main = builder.main();
+        let (s, t) = (preprocessed.row_slice(0)[0], preprocessed.row_slice(0)[1]);
+        let (z, z_next) = (main.row_slice(0)[0], main.row_slice(1)[0]);
+        builder.when_first_row().assert_zero(z);
+        builder.when_transition().assert_eq(z_next, z + s * t);
+    }
+}
+
+fn concat_air(relaxed: bool) -> ConcatAir<ScaleAir, CounterAir> {
+    ConcatAir::new(ScaleAir { relaxed }, CounterAir { relaxed })
+}
+
+/// A trace for `concat_air`, with one added to the entry at
+/// `(row, column)` of `corrupt`, if given.
+fn concat_trace(corrupt: Option<(usize, usize)>) -> RowMajorMatrix<Val> {
+    let mut values = Vec::with_capacity(HEIGHT * 3);
+    let mut z = Val::zero();
+    for i in 0..HEIGHT {
+        let x = Val::from_canonical_usize(7 * i + 2);
+        values.extend([x, Val::from_canonical_usize(i + 1) * x, z]);
+        z += Val::from_canonical_usize(5 * (i + 3));
+    }
+    if let Some((row, col)) = corrupt {
+        values[row * 3 + col] += Val::one();
+    }
+    RowMajorMatrix::new(values, 3)
+}
+
+fn prove_concat(
+    config: &MyConfig,
+    pk: &ProvingKey<MyConfig>,
+    challenger: &Challenger,
+    relaxed: bool,
+    corrupt: Option<(usize, usize)>,
+) -> Proof<MyConfig> {
+    prove(
+        config,
+        pk,
+        &concat_air(relaxed),
+        &mut challenger.clone(),
+        concat_trace(corrupt),
+    )
+}
+
+#[test]
+fn test_concat_air() {
+    let air = concat_air(false);
+    assert_eq!(BaseAir::<Val>::width(&air), 3);
+    assert_eq!(BaseAir::<Val>::preprocessed_width(&air), 3);
+
+    let (config, challenger) = config_and_challenger();
+    let (pk, vk) = setup(&config, &air);
+    let proof = prove_concat(&config, &pk, &challenger, false, None);
+    verify(&config, &vk, &air, &mut challenger.clone(), &proof).expect("verification failed");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "constraints had nonzero value on row 3")]
+fn test_concat_air_left_violated_debug() {
+    let (config, challenger) = config_and_challenger();
+    let (pk, _) = setup(&config, &concat_air(false));
+    prove_concat(&config, &pk, &challenger, false, Some((3, 1)));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "constraints had nonzero value on row 4")]
+fn test_concat_air_right_violated_debug() {
+    let (config, challenger) = config_and_challenger();
+    let (pk, _) = setup(&config, &concat_air(false));
+    prove_concat(&config, &pk, &challenger, false, Some((5, 2)));
+}
+
+#[test]
+fn test_concat_air_violated_rejected() {
+    let air = concat_air(false);
+    let (config, challenger) = config_and_challenger();
+    let (pk, vk) = setup(&config, &air);
+    for corrupt in [(3, 1), (5, 2)] {
+        let proof = prove_concat(&config, &pk, &challenger, true, Some(corrupt));
+        assert!(
+            verify(&config, &vk, &air, &mut challenger.clone(), &proof).is_err(),
+            "{corrupt:?} wasn't enforced"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use p3_challenger::{CanObserve, CanSample, CanSampleBits, FieldChallenger, GrindingChallenger};
+use p3_field::ExtensionField;
+use p3_fri::{TwoAdicFriPcs, TwoAdicFriPcsConfig};
+use p3_symmetric::Hash;
+use p3_uni_stark::{prove, setup, verify, StarkConfig};
+
+use crate::common::{
+    fri_config, random_mul_trace, random_perm, val_mmcs, Challenge, ChallengeMmcs, Challenger, Dft,
+    MulAir, Val, ValMmcs,
+};
+
+type Pcs = TwoAdicFriPcs<
+    TwoAdicFriPcsConfig<Val, Challenge, LabelDroppingChallenger, Dft, ValMmcs, ChallengeMmcs>,
+>;
+type MyConfig = StarkConfig<Val, Challenge, Pcs, LabelDroppingChallenger>;
+
+/// A challenger which behaves exactly like its inner challenger, except that it can be told to
+/// ignore domain separation labels.
+#[derive(Clone)]
+struct LabelDroppingChallenger {
+    inner: Challenger,
+    drop_labels: bool,
+}
+
+impl CanObserve<Val> for LabelDroppingChallenger {
+    fn observe(&mut self, value: Val) {
+        self.inner.observe(value);
+    }
+}
+
+impl CanObserve<Hash<Val, Val, 8>> for LabelDroppingChallenger {
+    fn observe(&mut self, value: Hash<Val, Val, 8>) {
+        self.inner.observe(value);
+    }
+}
+
+impl<EF: ExtensionField<Val>> CanSample<EF> for LabelDroppingChallenger {
+    fn sample(&mut self) -> EF {
+        self.inner.sample()
+    }
+}
+
+impl CanSampleBits<usize> for LabelDroppingChallenger {
+    fn sample_bits(&mut self, bits: usize) -> usize {
+        self.inner.sample_bits(bits)
+    }
+}
+
+impl FieldChallenger<Val> for LabelDroppingChallenger {
+    fn observe_label(&mut self, label: &'static [u8]) {
+        if !self.drop_labels {
+            self.inner.observe_label(label);
+        }
+    }
+}
+
+impl GrindingChallenger for LabelDroppingChallenger {
+    type Witness = Val;
+
+    fn grind(&mut self, bits: usize) -> Self::Witness {
+        self.inner.grind(bits)
+    }
+}
+
+fn prove_and_verify(verifier_drops_labels: bool) -> bool {
+    let perm = random_perm();
+    let val_mmcs = val_mmcs(&perm);
+    let pcs = Pcs::new(fri_config(&val_mmcs), Dft {}, val_mmcs);
+    let config = StarkConfig::new(pcs);
+    let (pk, vk) = setup(&config, &MulAir);
+
+    let mut challenger = LabelDroppingChallenger {
+        inner: Challenger::new(perm.clone()),
+        drop_labels: false,
+    };
+    let proof = prove::<MyConfig, _>(
+        &config,
+        &pk,
+        &MulAir,
+        &mut challenger,
+        random_mul_trace(1 << 6),
+    );
+
+    let mut challenger = LabelDroppingChallenger {
+        inner: Challenger::new(perm),
+        drop_labels: verifier_drops_labels,
+    };
+    verify(&config, &vk, &MulAir, &mut challenger, &proof).is_ok()
+}
+
+#[test]
+fn test_matching_labels_verify() {
+    assert!(prove_and_verify(false));
+}
+
+#[test]
+fn test_omitted_labels_fail() {
+    assert!(!prove_and_verify(true));
+}
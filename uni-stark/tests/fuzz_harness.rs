@@ -0,0 +1,44 @@
+mod common;
+
+use p3_field::AbstractField;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::{FuzzHarness, FuzzOutcome};
+use rand::{thread_rng, Rng};
+
+use crate::common::{config_and_challenger, fib_trace, FibAir, Val};
+
+const HEIGHT: usize = 1 << 5;
+
+/// A Fibonacci trace with one cell shifted by a random nonzero amount.
+fn corrupted_fib_trace() -> RowMajorMatrix<Val> {
+    let mut rng = thread_rng();
+    let mut trace = fib_trace(HEIGHT);
+    let cell = rng.gen_range(0..trace.values.len());
+    trace.values[cell] += Val::from_canonical_u32(rng.gen_range(1..1 << 30));
+    trace
+}
+
+#[test]
+fn test_fuzz_valid_fib_traces() {
+    let (config, challenger) = config_and_challenger();
+    let mut harness = FuzzHarness::new(&config, &FibAir, challenger, || fib_trace(HEIGHT));
+    for _ in 0..3 {
+        assert!(matches!(harness.run(), FuzzOutcome::Accepted));
+    }
+}
+
+#[test]
+fn test_fuzz_corrupted_fib_traces() {
+    let (config, challenger) = config_and_challenger();
+    let mut harness = FuzzHarness::new(&config, &FibAir, challenger, corrupted_fib_trace);
+    for _ in 0..3 {
+        let outcome = harness.run();
+        // Debug builds check constraints while proving, so the prover itself catches the
+        // corruption. Otherwise the verifier must reject.
+        if cfg!(debug_assertions) {
+            assert!(matches!(outcome, FuzzOutcome::ProverPanicked));
+        } else {
+            assert!(matches!(outcome, FuzzOutcome::RejectedBy(_)));
+        }
+    }
+}
@@ -12,7 +12,7 @@ use p3_matrix::MatrixRowSlices;
 use p3_merkle_tree::FieldMerkleTreeMmcs;
 use p3_poseidon2::{DiffusionMatrixBabybear, Poseidon2};
 use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
-use p3_uni_stark::{prove, verify, StarkConfig, VerificationError};
+use p3_uni_stark::{prove, setup, verify, StarkConfig, VerificationError};
 use rand::distributions::{Distribution, Standard};
 use rand::{thread_rng, Rng};
 use tracing_forest::ForestLayer;
@@ -114,7 +114,8 @@ fn test_prove_baby_bear() -> Result<(), VerificationError> {
 
     let mut challenger = Challenger::new(perm.clone());
     let trace = random_valid_trace::<Val>(HEIGHT);
-    let proof = prove::<MyConfig, _>(&config, &MulAir, &mut challenger, trace);
+    let (pk, vk) = setup(&config, &MulAir);
+    let proof = prove::<MyConfig, _>(&config, &pk, &MulAir, &mut challenger, trace);
 
     let serialized_proof = postcard::to_allocvec(&proof).expect("unable to serialize proof");
     tracing::debug!("serialized_proof len: {} bytes", serialized_proof.len());
@@ -123,7 +124,7 @@ fn test_prove_baby_bear() -> Result<(), VerificationError> {
         postcard::from_bytes(&serialized_proof).expect("unable to deserialize proof");
 
     let mut challenger = Challenger::new(perm);
-    verify(&config, &MulAir, &mut challenger, &deserialized_proof)
+    verify(&config, &vk, &MulAir, &mut challenger, &deserialized_proof)
 }
 
 #[test]
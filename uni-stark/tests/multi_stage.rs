@@ -0,0 +1,147 @@
+mod common;
+
+use p3_air::{Air, AirBuilder, BaseAir, ExtensionBuilder, MultiStageAirBuilder, StageShape};
+use p3_field::{AbstractField, Field};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Matrix, MatrixRowSlices};
+use p3_uni_stark::{prove, prove_multi_stage, setup, verify, Proof, ProvingKey};
+
+use crate::common::{config_and_challenger, Challenge, Challenger, MyConfig, Val};
+
+/// The Fibonacci sequence in columns `a` and `b`, alongside a third column `p` which must be a
+/// permutation of `a`.
+///
+/// After the main trace is committed, the prover draws a challenge `gamma` from the extension
+/// field and commits to a running product `z` with `z_0 = 1` and
+/// `z_{i+1} (gamma - p_i) = z_i (gamma - a_i)`. The latter also holds from the last row back to
+/// the first, which forces `prod (gamma - a_i) = prod (gamma - p_i)`.
+///
+/// A `relaxed` AIR has the same shape but no constraints, so that it can prove traces which the
+/// strict AIR doesn't accept.
+struct FibonacciPermutationAir {
+    relaxed: bool,
+}
+
+const STRICT: FibonacciPermutationAir = FibonacciPermutationAir { relaxed: false };
+
+impl<F> BaseAir<F> for FibonacciPermutationAir {
+    fn width(&self) -> usize {
+        3
+    }
+
+    fn later_stages(&self) -> Vec<StageShape> {
+        vec![StageShape {
+            num_challenges: 1,
+            width: 1,
+        }]
+    }
+}
+
+impl<AB: MultiStageAirBuilder> Air<AB> for FibonacciPermutationAir {
+    fn eval(&self, builder: &mut AB) {
+        if self.relaxed {
+            return;
+        }
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let (a, b, p): (AB::Expr, AB::Expr, AB::Expr) =
+            (local[0].into(), local[1].into(), local[2].into());
+
+        builder.when_first_row().assert_zero(a.clone());
+        builder.when_first_row().assert_one(b.clone());
+        builder.when_transition().assert_eq(next[0], b.clone());
+        builder.when_transition().assert_eq(next[1], a.clone() + b);
+
+        let z = builder.later_stage(0);
+        let z_local: AB::ExprEF = z.row_slice(0)[0].into();
+        let z_next: AB::ExprEF = z.row_slice(1)[0].into();
+        let gamma = builder.later_stage_challenges(0)[0].clone();
+
+        builder.when_first_row().assert_one_ext(z_local.clone());
+        builder.assert_eq_ext(z_next * (gamma.clone() - p), z_local * (gamma - a));
+    }
+}
+
+/// A Fibonacci trace, with the value of `a` in row `permuted(i)` in the third column of row `i`.
+fn main_trace(height: usize, permuted: impl Fn(usize) -> usize) -> RowMajorMatrix<Val> {
+    let mut fib = vec![Val::zero(), Val::one()];
+    while fib.len() <= height {
+        fib.push(fib[fib.len() - 2] + fib[fib.len() - 1]);
+    }
+    let values = (0..height)
+        .flat_map(|i| [fib[i], fib[i + 1], fib[permuted(i)]])
+        .collect();
+    RowMajorMatrix::new(values, 3)
+}
+
+fn running_product(main: &RowMajorMatrix<Val>, gamma: Challenge) -> RowMajorMatrix<Challenge> {
+    let mut z = Vec::with_capacity(main.height());
+    let mut acc = Challenge::one();
+    for row in main.rows() {
+        z.push(acc);
+        acc *= (gamma - row[0]) * (gamma - row[2]).inverse();
+    }
+    RowMajorMatrix::new(z, 1)
+}
+
+fn prove_fibonacci_permutation(
+    config: &MyConfig,
+    pk: &ProvingKey<MyConfig>,
+    challenger: &Challenger,
+    relaxed: bool,
+    main: RowMajorMatrix<Val>,
+) -> Proof<MyConfig> {
+    let main_for_stage = main.clone();
+    prove_multi_stage(
+        config,
+        pk,
+        &FibonacciPermutationAir { relaxed },
+        &mut challenger.clone(),
+        main,
+        |stage, challenges| {
+            assert_eq!(stage, 0);
+            running_product(&main_for_stage, challenges[0])
+        },
+    )
+}
+
+#[test]
+fn test_fibonacci_permutation() {
+    const HEIGHT: usize = 1 << 5;
+    let (config, challenger) = config_and_challenger();
+    let (pk, vk) = setup(&config, &STRICT);
+    let main = main_trace(HEIGHT, |i| HEIGHT - 1 - i);
+    let proof = prove_fibonacci_permutation(&config, &pk, &challenger, false, main);
+    verify(&config, &vk, &STRICT, &mut challenger.clone(), &proof).expect("verification failed");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "constraints had nonzero value on row 31")]
+fn test_not_a_permutation_debug() {
+    const HEIGHT: usize = 1 << 5;
+    let (config, challenger) = config_and_challenger();
+    let (pk, _) = setup(&config, &STRICT);
+    let main = main_trace(HEIGHT, |i| i / 2);
+    prove_fibonacci_permutation(&config, &pk, &challenger, false, main);
+}
+
+#[test]
+fn test_not_a_permutation_rejected() {
+    const HEIGHT: usize = 1 << 5;
+    let (config, challenger) = config_and_challenger();
+    let (pk, vk) = setup(&config, &STRICT);
+    let main = main_trace(HEIGHT, |i| i / 2);
+    let proof = prove_fibonacci_permutation(&config, &pk, &challenger, true, main);
+    assert!(verify(&config, &vk, &STRICT, &mut challenger.clone(), &proof).is_err());
+}
+
+#[test]
+#[should_panic(expected = "this AIR has later stages; use prove_multi_stage")]
+fn test_prove_rejects_later_stages() {
+    const HEIGHT: usize = 1 << 5;
+    let (config, challenger) = config_and_challenger();
+    let (pk, _) = setup(&config, &STRICT);
+    let main = main_trace(HEIGHT, |i| HEIGHT - 1 - i);
+    prove(&config, &pk, &STRICT, &mut challenger.clone(), main);
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use p3_air::{Air, BaseAir, PeriodicAirBuilder};
+use p3_field::AbstractField;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_uni_stark::{prove, setup, verify, Proof, VerificationError};
+
+use crate::common::{config_and_challenger, MyConfig, Val};
+
+const HEIGHT: usize = 1 << 5;
+const PERIOD: usize = 4;
+
+/// Asserts that the first column is zero on the rows `r` with `r % period == offset`.
+///
+/// A `relaxed` AIR has the same shape but no constraints, so that it can prove traces which the
+/// strict AIR doesn't accept.
+struct PeriodicAir {
+    period: usize,
+    offset: usize,
+    relaxed: bool,
+}
+
+impl PeriodicAir {
+    fn new(period: usize, offset: usize) -> Self {
+        Self {
+            period,
+            offset,
+            relaxed: false,
+        }
+    }
+}
+
+impl<F> BaseAir<F> for PeriodicAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: PeriodicAirBuilder> Air<AB> for PeriodicAir {
+    fn eval(&self, builder: &mut AB) {
+        if self.relaxed {
+            return;
+        }
+        let main = builder.main();
+        let x = main.row_slice(0)[0];
+        builder
+            .when_periodic(self.period, self.offset)
+            .assert_zero(x);
+    }
+}
+
+/// A trace which is zero on the rows in `zero_rows`, and nonzero on all others.
+fn trace(zero_rows: impl Fn(usize) -> bool) -> RowMajorMatrix<Val> {
+    let values = (0..HEIGHT)
+        .map(|i| {
+            if zero_rows(i) {
+                Val::zero()
+            } else {
+                Val::from_canonical_usize(i + 1)
+            }
+        })
+        .collect();
+    RowMajorMatrix::new(values, 1)
+}
+
+/// Proves `trace` with `air`, or with its relaxed version if `relaxed` is set, and checks whether
+/// the proof verifies against `air`.
+fn prove_and_verify(air: &PeriodicAir, trace: RowMajorMatrix<Val>, relaxed: bool) -> bool {
+    let (config, challenger) = config_and_challenger();
+    let (pk, vk) = setup(&config, air);
+    let prover_air = PeriodicAir { relaxed, ..*air };
+    let proof: Proof<MyConfig> = prove(&config, &pk, &prover_air, &mut challenger.clone(), trace);
+    verify(&config, &vk, air, &mut challenger.clone(), &proof).is_ok()
+}
+
+#[test]
+fn test_enforced_only_on_periodic_rows() {
+    // The trace is nonzero on every other row, which the constraint must not see.
+    for offset in 0..PERIOD {
+        let air = PeriodicAir::new(PERIOD, offset);
+        assert!(prove_and_verify(
+            &air,
+            trace(|i| i % PERIOD == offset),
+            false
+        ));
+    }
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "constraints had nonzero value on row 8")]
+fn test_violated_on_a_periodic_row_debug() {
+    let air = PeriodicAir::new(PERIOD, 0);
+    prove_and_verify(&air, trace(|i| i % PERIOD == 0 && i != 8), false);
+}
+
+#[test]
+fn test_violated_on_each_periodic_row_rejected() {
+    // Breaking the constraint on any single row 0, 4, 8, ... is caught.
+    let air = PeriodicAir::new(PERIOD, 0);
+    for bad_row in (0..HEIGHT).step_by(PERIOD) {
+        let trace = trace(|i| i % PERIOD == 0 && i != bad_row);
+        assert!(
+            !prove_and_verify(&air, trace, true),
+            "row {bad_row} wasn't enforced"
+        );
+    }
+}
+
+#[test]
+fn test_invalid_periodic_selectors_rejected() {
+    let (config, challenger) = config_and_challenger();
+    let air = PeriodicAir::new(PERIOD, 0);
+    let (pk, _) = setup(&config, &air);
+    let proof = prove(
+        &config,
+        &pk,
+        &air,
+        &mut challenger.clone(),
+        trace(|i| i % PERIOD == 0),
+    );
+    // A period which isn't a power of two, one exceeding the trace height, and an offset which
+    // isn't less than its period.
+    for (period, offset) in [(3, 0), (2 * HEIGHT, 0), (PERIOD, PERIOD)] {
+        let bad_air = PeriodicAir::new(period, offset);
+        let (_, vk) = setup(&config, &bad_air);
+        let result = verify(&config, &vk, &bad_air, &mut challenger.clone(), &proof);
+        assert!(
+            matches!(result, Err(VerificationError::InvalidPeriodicSelector)),
+            "period {period}, offset {offset} wasn't rejected"
+        );
+    }
+}
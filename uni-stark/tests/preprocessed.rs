@@ -0,0 +1,126 @@
+mod common;
+
+use p3_air::{Air, AirBuilder, BaseAir, PairBuilder};
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_uni_stark::{prove, setup, verify};
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::common::{config_and_challenger, Val};
+
+const HEIGHT: usize = 1 << 5;
+
+/// Asserts that `c = a * b + d` on each row, where `d` and `c` are in the main trace, and `a` and
+/// `b` are fixed columns of a preprocessed trace derived from `seed`.
+struct PreprocessedMulAir {
+    seed: u64,
+}
+
+impl<F: Field> BaseAir<F> for PreprocessedMulAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        let mut rng = ChaCha20Rng::seed_from_u64(self.seed);
+        let values = (0..HEIGHT * 2)
+            .map(|_| F::from_wrapped_u32(rng.gen()))
+            .collect();
+        Some(RowMajorMatrix::new(values, 2))
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: PairBuilder> Air<AB> for PreprocessedMulAir {
+    fn eval(&self, builder: &mut AB) {
+        let preprocessed = builder.preprocessed();
+        let main = builder.main();
+        let (a, b) = (preprocessed.row_slice(0)[0], preprocessed.row_slice(0)[1]);
+        let (d, c) = (main.row_slice(0)[0], main.row_slice(0)[1]);
+        builder.assert_eq(c, a * b + d);
+    }
+}
+
+/// Like `PreprocessedMulAir`, but with a `preprocessed_width` which doesn't match its trace.
+struct MisdeclaredWidthAir(PreprocessedMulAir);
+
+impl<F: Field> BaseAir<F> for MisdeclaredWidthAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        self.0.preprocessed_trace()
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: PairBuilder> Air<AB> for MisdeclaredWidthAir {
+    fn eval(&self, builder: &mut AB) {
+        self.0.eval(builder);
+    }
+}
+
+/// A random trace satisfying `air`'s constraints.
+fn trace(air: &PreprocessedMulAir) -> RowMajorMatrix<Val> {
+    let preprocessed: RowMajorMatrix<Val> = air.preprocessed_trace().unwrap();
+    let mut rng = thread_rng();
+    let values = preprocessed
+        .rows()
+        .flat_map(|ab| {
+            let d: Val = rng.gen();
+            [d, ab[0] * ab[1] + d]
+        })
+        .collect();
+    RowMajorMatrix::new(values, 2)
+}
+
+#[test]
+fn test_vk_verifies_many_proofs() {
+    let (config, challenger) = config_and_challenger();
+    let air = PreprocessedMulAir { seed: 1 };
+    let (pk, vk) = setup(&config, &air);
+    for _ in 0..3 {
+        let proof = prove(&config, &pk, &air, &mut challenger.clone(), trace(&air));
+        verify(&config, &vk, &air, &mut challenger.clone(), &proof).expect("verification failed");
+    }
+}
+
+#[test]
+fn test_vk_for_other_air_rejects() {
+    let (config, challenger) = config_and_challenger();
+    let air = PreprocessedMulAir { seed: 1 };
+    let other_air = PreprocessedMulAir { seed: 2 };
+    let (pk, _) = setup(&config, &air);
+    let (_, other_vk) = setup(&config, &other_air);
+    let proof = prove(&config, &pk, &air, &mut challenger.clone(), trace(&air));
+    assert!(verify(&config, &other_vk, &air, &mut challenger.clone(), &proof).is_err());
+    assert!(verify(
+        &config,
+        &other_vk,
+        &other_air,
+        &mut challenger.clone(),
+        &proof
+    )
+    .is_err());
+}
+
+#[test]
+#[should_panic(
+    expected = "the preprocessed trace's width doesn't match the AIR's preprocessed_width"
+)]
+fn test_misdeclared_preprocessed_width_rejected() {
+    let (config, _) = config_and_challenger();
+    setup(
+        &config,
+        &MisdeclaredWidthAir(PreprocessedMulAir { seed: 1 }),
+    );
+}
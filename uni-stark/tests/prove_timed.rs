@@ -0,0 +1,43 @@
+mod common;
+
+use p3_uni_stark::{prove, prove_timed, setup, verify};
+
+use crate::common::{config_and_challenger, random_mul_trace, MulAir};
+
+#[test]
+fn test_prove_timed() {
+    const HEIGHT: usize = 1 << 10;
+
+    let (config, challenger) = config_and_challenger();
+    let trace = random_mul_trace(HEIGHT);
+
+    let (pk, vk) = setup(&config, &MulAir);
+    let (proof, timings) = prove_timed(
+        &config,
+        &pk,
+        &MulAir,
+        &mut challenger.clone(),
+        trace.clone(),
+    );
+
+    // Durations can be zero on a coarse clock, so check that each phase's spans were recorded.
+    for (phase, timing) in [
+        ("trace_commit", timings.trace_commit),
+        ("lde", timings.lde),
+        ("quotient", timings.quotient),
+        ("quotient_commit", timings.quotient_commit),
+        ("fri_commit", timings.fri_commit),
+        ("fri_query", timings.fri_query),
+    ] {
+        assert!(timing.spans > 0, "{phase} wasn't timed");
+    }
+
+    // Timing doesn't change the proof, which is deterministic given the challenger's state as long
+    // as grinding is serial, i.e. without `p3-maybe-rayon/parallel`.
+    let untimed_proof = prove(&config, &pk, &MulAir, &mut challenger.clone(), trace);
+    assert_eq!(
+        postcard::to_allocvec(&proof).unwrap(),
+        postcard::to_allocvec(&untimed_proof).unwrap()
+    );
+    verify(&config, &vk, &MulAir, &mut challenger.clone(), &proof).expect("verification failed");
+}
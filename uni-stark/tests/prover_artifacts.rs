@@ -0,0 +1,42 @@
+mod common;
+
+use p3_commit::UnivariatePcsWithLde;
+use p3_field::{AbstractExtensionField, AbstractField, TwoAdicField};
+use p3_matrix::{Matrix, MatrixRowSlices};
+use p3_uni_stark::{prove_with_artifacts, setup, verify, StarkGenericConfig};
+
+use crate::common::{config_and_challenger, random_mul_trace, Challenge, MulAir, MyConfig, Val};
+
+#[test]
+fn test_quotient_chunks_match_constraints() {
+    const LOG_HEIGHT: usize = 4;
+    const HEIGHT: usize = 1 << LOG_HEIGHT;
+
+    let (config, challenger) = config_and_challenger();
+    let trace = random_mul_trace(HEIGHT);
+
+    let (pk, vk) = setup(&config, &MulAir);
+    let (proof, artifacts) =
+        prove_with_artifacts::<MyConfig, _>(&config, &pk, &MulAir, &mut challenger.clone(), trace);
+
+    // With a single constraint, the folded constraints are just `a * b - c`, independent of the
+    // folding challenge. It has degree 2, so the quotient has degree less than `HEIGHT`, fits in a
+    // single chunk, and is evaluated over `shift H`, i.e. every other point of the LDE domain.
+    let d = <Challenge as AbstractExtensionField<Val>>::D;
+    assert_eq!(artifacts.quotient_chunks.width(), d);
+    assert_eq!(artifacts.quotient_chunks.height(), HEIGHT);
+    assert_eq!(artifacts.trace_lde.height(), 2 * HEIGHT);
+
+    let shift = config.pcs().coset_shift();
+    let g_subgroup = Val::two_adic_generator(LOG_HEIGHT);
+    for (r, x) in g_subgroup.shifted_powers(shift).take(HEIGHT).enumerate() {
+        let quotient = Challenge::from_base_slice(artifacts.quotient_chunks.row_slice(r));
+
+        let lde_row = artifacts.trace_lde.row_slice(2 * r);
+        let constraints = lde_row[0] * lde_row[1] - lde_row[2];
+        let z_h = x.exp_power_of_2(LOG_HEIGHT) - Val::one();
+        assert_eq!(quotient * z_h, Challenge::from_base(constraints));
+    }
+
+    verify(&config, &vk, &MulAir, &mut challenger.clone(), &proof).expect("verification failed");
+}
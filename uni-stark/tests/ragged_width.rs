@@ -0,0 +1,129 @@
+mod common;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{AbstractField, Field, PackedField};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_uni_stark::{prove, setup, verify};
+use rand::{thread_rng, Rng};
+
+use crate::common::{config_and_challenger, Val};
+
+const LANES: usize = <<Val as Field>::Packing as PackedField>::WIDTH;
+
+/// Widths one more and one less than a multiple of the packing width, which is one on targets
+/// without SIMD.
+const WIDTHS: [usize; 2] = [LANES + 1, 2 * LANES - 1];
+
+/// Asserts `next[i] = local[i] * local[i + 1] + 1` on each transition, with the column index
+/// wrapping around, so that every column's constraint reads its neighbour.
+///
+/// A `relaxed` AIR has the same shape but no constraints, so that it can prove traces which the
+/// strict AIR doesn't accept.
+struct ChainAir {
+    width: usize,
+    relaxed: bool,
+}
+
+impl<F> BaseAir<F> for ChainAir {
+    fn width(&self) -> usize {
+        self.width
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for ChainAir {
+    fn eval(&self, builder: &mut AB) {
+        if self.relaxed {
+            return;
+        }
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        for i in 0..self.width {
+            let neighbour = local[(i + 1) % self.width];
+            builder
+                .when_transition()
+                .assert_eq(next[i], local[i] * neighbour + AB::Expr::one());
+        }
+    }
+}
+
+/// A trace for `ChainAir` with a random first row, and with one added to the last column of
+/// `corrupt_row`, if given.
+fn chain_trace(width: usize, height: usize, corrupt_row: Option<usize>) -> RowMajorMatrix<Val> {
+    let mut rng = thread_rng();
+    let mut values: Vec<Val> = (0..width).map(|_| rng.gen()).collect();
+    for r in 1..height {
+        let local = &values[(r - 1) * width..r * width];
+        let next = (0..width)
+            .map(|i| local[i] * local[(i + 1) % width] + Val::one())
+            .collect::<Vec<_>>();
+        values.extend(next);
+    }
+    if let Some(row) = corrupt_row {
+        values[row * width + width - 1] += Val::one();
+    }
+    RowMajorMatrix::new(values, width)
+}
+
+/// The verifier evaluates the constraints at a single point over the extension field, so it
+/// accepts only if the prover's packed quotient agrees with that scalar evaluation.
+#[test]
+fn test_ragged_widths() {
+    let (config, challenger) = config_and_challenger();
+    // The smaller height gives a quotient domain narrower than the widest packings.
+    for height in [1 << 2, 1 << 5] {
+        for width in WIDTHS {
+            let air = ChainAir {
+                width,
+                relaxed: false,
+            };
+            let (pk, vk) = setup(&config, &air);
+            let proof = prove(
+                &config,
+                &pk,
+                &air,
+                &mut challenger.clone(),
+                chain_trace(width, height, None),
+            );
+            verify(&config, &vk, &air, &mut challenger.clone(), &proof)
+                .unwrap_or_else(|e| panic!("verification failed for width {width}: {e:?}"));
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "constraints had nonzero value on row 6")]
+fn test_ragged_width_violated_debug() {
+    let (config, challenger) = config_and_challenger();
+    let width = WIDTHS[0];
+    let trace = chain_trace(width, 1 << 5, Some(7));
+    let air = ChainAir {
+        width,
+        relaxed: false,
+    };
+    let (pk, _) = setup(&config, &air);
+    prove(&config, &pk, &air, &mut challenger.clone(), trace);
+}
+
+#[test]
+fn test_ragged_width_violated_rejected() {
+    let (config, challenger) = config_and_challenger();
+    for width in WIDTHS {
+        let air = ChainAir {
+            width,
+            relaxed: false,
+        };
+        let relaxed = ChainAir {
+            width,
+            relaxed: true,
+        };
+        let trace = chain_trace(width, 1 << 5, Some(7));
+        let (pk, vk) = setup(&config, &air);
+        let proof = prove(&config, &pk, &relaxed, &mut challenger.clone(), trace);
+        assert!(
+            verify(&config, &vk, &air, &mut challenger.clone(), &proof).is_err(),
+            "width {width} wasn't enforced"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use p3_air::{
+    Air, AirBuilder, BaseAir, PairBuilder, RowContextBuilder, RowPointAirBuilder, WithRowContext,
+};
+use p3_field::{AbstractField, Field, TwoAdicField};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_uni_stark::{prove, setup, verify, Proof, ProvingKey};
+
+use crate::common::{config_and_challenger, Challenger, MyConfig, Val};
+
+const LOG_HEIGHT: usize = 5;
+const HEIGHT: usize = 1 << LOG_HEIGHT;
+
+/// Asserts that the first column holds the row index `i`, and that the second holds `x^2 + p` on
+/// row `i`, where `x = g^i` is the row as a point of the trace's subgroup and `p` is a
+/// preprocessed column.
+///
+/// A `relaxed` AIR has the same shape but no constraints on its main trace, so that it can prove
+/// traces which the strict AIR doesn't accept.
+struct SquareIndexAir {
+    relaxed: bool,
+}
+
+impl<F: Field> BaseAir<F> for SquareIndexAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        let values = (0..HEIGHT)
+            .map(|i| F::from_canonical_usize(7 * i + 1))
+            .collect();
+        Some(RowMajorMatrix::new_col(values))
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        1
+    }
+}
+
+impl<'a, AB: PairBuilder + RowPointAirBuilder> Air<RowContextBuilder<'a, AB>> for SquareIndexAir {
+    fn eval(&self, builder: &mut RowContextBuilder<'a, AB>) {
+        if self.relaxed {
+            return;
+        }
+        let p = builder.preprocessed().row_slice(0)[0];
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let (index, y) = (local[0], local[1]);
+
+        builder.when_first_row().assert_zero(index);
+        builder
+            .when_transition()
+            .assert_eq(next[0], index + AB::Expr::one());
+
+        let x = builder.row_point();
+        builder.assert_eq(y, x.clone() * x + p);
+    }
+}
+
+/// A trace for `SquareIndexAir`, with one added to the cell `corrupt`, as a row and column, if
+/// given.
+fn square_index_trace(corrupt: Option<(usize, usize)>) -> RowMajorMatrix<Val> {
+    let g = Val::two_adic_generator(LOG_HEIGHT);
+    let mut values: Vec<Val> = (0..HEIGHT)
+        .flat_map(|i| {
+            [
+                Val::from_canonical_usize(i),
+                g.exp_u64(2 * i as u64) + Val::from_canonical_usize(7 * i + 1),
+            ]
+        })
+        .collect();
+    if let Some((row, col)) = corrupt {
+        values[2 * row + col] += Val::one();
+    }
+    RowMajorMatrix::new(values, 2)
+}
+
+fn prove_square_index(
+    config: &MyConfig,
+    pk: &ProvingKey<MyConfig>,
+    challenger: &Challenger,
+    relaxed: bool,
+    corrupt: Option<(usize, usize)>,
+) -> Proof<MyConfig> {
+    let air = WithRowContext::new(SquareIndexAir { relaxed });
+    prove(
+        config,
+        pk,
+        &air,
+        &mut challenger.clone(),
+        square_index_trace(corrupt),
+    )
+}
+
+#[test]
+fn test_row_context() {
+    let air = WithRowContext::new(SquareIndexAir { relaxed: false });
+    assert_eq!(BaseAir::<Val>::width(&air), 2);
+    assert_eq!(BaseAir::<Val>::preprocessed_width(&air), 1);
+
+    let (config, challenger) = config_and_challenger();
+    let (pk, vk) = setup(&config, &air);
+    let proof = prove_square_index(&config, &pk, &challenger, false, None);
+    verify(&config, &vk, &air, &mut challenger.clone(), &proof).expect("verification failed");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "constraints had nonzero value on row 9")]
+fn test_row_context_off_by_one_index_debug() {
+    let (config, challenger) = config_and_challenger();
+    let (pk, _) = setup(
+        &config,
+        &WithRowContext::new(SquareIndexAir { relaxed: false }),
+    );
+    prove_square_index(&config, &pk, &challenger, false, Some((9, 0)));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "constraints had nonzero value on row 9")]
+fn test_row_context_off_by_one_square_debug() {
+    let (config, challenger) = config_and_challenger();
+    let (pk, _) = setup(
+        &config,
+        &WithRowContext::new(SquareIndexAir { relaxed: false }),
+    );
+    prove_square_index(&config, &pk, &challenger, false, Some((9, 1)));
+}
+
+#[test]
+fn test_row_context_off_by_one_rejected() {
+    let air = WithRowContext::new(SquareIndexAir { relaxed: false });
+    let (config, challenger) = config_and_challenger();
+    let (pk, vk) = setup(&config, &air);
+    for row in [0, 9, HEIGHT - 1] {
+        for col in 0..2 {
+            let proof = prove_square_index(&config, &pk, &challenger, true, Some((row, col)));
+            assert!(
+                verify(&config, &vk, &air, &mut challenger.clone(), &proof).is_err(),
+                "row {row}, column {col} wasn't enforced"
+            );
+        }
+    }
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::AbstractField;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_uni_stark::{prove, setup, verify, Proof, ProvingKey};
+
+use crate::common::{config_and_challenger, Challenger, MyConfig, Val};
+
+const COND: usize = 0;
+const A: usize = 1;
+const B: usize = 2;
+const OUT: usize = 3;
+const XS: usize = 4;
+const YS: usize = 6;
+const ZS: usize = 8;
+const WIDTH: usize = 10;
+
+/// Asserts `out = cond ? a * b : a + b` and `zs = cond ? xs : ys` on each row.
+///
+/// A `relaxed` AIR has the same shape but no constraints, so that it can prove traces which the
+/// strict AIR doesn't accept.
+struct MuxAir {
+    relaxed: bool,
+}
+
+impl<F> BaseAir<F> for MuxAir {
+    fn width(&self) -> usize {
+        WIDTH
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for MuxAir {
+    fn eval(&self, builder: &mut AB) {
+        if self.relaxed {
+            return;
+        }
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let (cond, a, b) = (local[COND], local[A], local[B]);
+        builder.assert_bool(cond);
+        builder.assert_eq(local[OUT], builder.select(cond, a * b, a + b));
+
+        let zs = builder.select_slices(cond, &local[XS..YS], &local[YS..ZS]);
+        for (z, expected) in local[ZS..WIDTH].iter().zip(zs) {
+            builder.assert_eq(*z, expected);
+        }
+    }
+}
+
+/// A trace for `MuxAir` whose selected values are taken from the branch `cond` picks, unless
+/// `swap_branches` is set.
+fn mux_trace(swap_branches: bool) -> RowMajorMatrix<Val> {
+    let height = 1 << 5;
+    let mut values = Vec::with_capacity(height * WIDTH);
+    for i in 0..height {
+        let cond = i % 3 == 0;
+        let pick = cond != swap_branches;
+        let a = Val::from_canonical_usize(i + 2);
+        let b = Val::from_canonical_usize(3 * i + 5);
+        let xs = [a.square(), b.double()];
+        let ys = [b.square(), a.double()];
+        values.push(Val::from_bool(cond));
+        values.extend([a, b, if pick { a * b } else { a + b }]);
+        values.extend(xs);
+        values.extend(ys);
+        values.extend(if pick { xs } else { ys });
+    }
+    RowMajorMatrix::new(values, WIDTH)
+}
+
+fn prove_mux(
+    config: &MyConfig,
+    pk: &ProvingKey<MyConfig>,
+    challenger: &Challenger,
+    relaxed: bool,
+    swap_branches: bool,
+) -> Proof<MyConfig> {
+    prove(
+        config,
+        pk,
+        &MuxAir { relaxed },
+        &mut challenger.clone(),
+        mux_trace(swap_branches),
+    )
+}
+
+#[test]
+fn test_select() {
+    let (config, challenger) = config_and_challenger();
+    let air = MuxAir { relaxed: false };
+    let (pk, vk) = setup(&config, &air);
+    let proof = prove_mux(&config, &pk, &challenger, false, false);
+    verify(&config, &vk, &air, &mut challenger.clone(), &proof).expect("verification failed");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "values didn't match on row 0")]
+fn test_select_swapped_branches_debug() {
+    let (config, challenger) = config_and_challenger();
+    let (pk, _) = setup(&config, &MuxAir { relaxed: false });
+    prove_mux(&config, &pk, &challenger, false, true);
+}
+
+#[test]
+fn test_select_swapped_branches_rejected() {
+    let (config, challenger) = config_and_challenger();
+    let air = MuxAir { relaxed: false };
+    let (pk, vk) = setup(&config, &air);
+    let proof = prove_mux(&config, &pk, &challenger, true, true);
+    assert!(verify(&config, &vk, &air, &mut challenger.clone(), &proof).is_err());
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use p3_field::AbstractExtensionField;
+use p3_fri::{TwoAdicFriPcs, TwoAdicFriPcsConfig};
+use p3_uni_stark::{prove, setup, verify, StarkConfig};
+use p3_util::allocator::TraceAllocator;
+
+use crate::common::{
+    fri_config, random_mul_trace, random_perm, val_mmcs, Challenge, ChallengeMmcs, Challenger, Dft,
+    MulAir, Val, ValMmcs,
+};
+
+type Pcs = TwoAdicFriPcs<
+    TwoAdicFriPcsConfig<
+        Val,
+        Challenge,
+        Challenger,
+        Dft,
+        ValMmcs,
+        ChallengeMmcs,
+        RecordingAllocator,
+    >,
+>;
+type MyConfig = StarkConfig<Val, Challenge, Pcs, Challenger>;
+
+const HEIGHT: usize = 1 << 6;
+const WIDTH: usize = 3;
+
+/// A `TraceAllocator` which records the capacity of each buffer it's asked for.
+#[derive(Clone, Default)]
+struct RecordingAllocator {
+    capacities: Arc<Mutex<Vec<usize>>>,
+}
+
+impl TraceAllocator for RecordingAllocator {
+    fn allocate<T>(&self, capacity: usize) -> Vec<T> {
+        self.capacities.lock().unwrap().push(capacity);
+        Vec::with_capacity(capacity)
+    }
+}
+
+#[test]
+fn test_trace_allocator() {
+    let allocator = RecordingAllocator::default();
+    let perm = random_perm();
+    let val_mmcs = val_mmcs(&perm);
+    let pcs = Pcs::new_with_allocator(fri_config(&val_mmcs), Dft {}, val_mmcs, allocator.clone());
+    let config = MyConfig::new(pcs);
+    let challenger = Challenger::new(perm);
+
+    let (pk, vk) = setup(&config, &MulAir);
+    let proof = prove(
+        &config,
+        &pk,
+        &MulAir,
+        &mut challenger.clone(),
+        random_mul_trace(HEIGHT),
+    );
+    verify(&config, &vk, &MulAir, &mut challenger.clone(), &proof).expect("verification failed");
+
+    let capacities = allocator.capacities.lock().unwrap();
+    // Only trace-sized buffers are asked for, not small scratch space.
+    assert!(
+        capacities.iter().all(|&capacity| capacity >= HEIGHT),
+        "a buffer smaller than the trace came from the allocator: {capacities:?}"
+    );
+    // The constraints have degree 2, so the quotient has a single chunk, and both LDEs double the
+    // height of what they extend.
+    let d = <Challenge as AbstractExtensionField<Val>>::D;
+    for (buffer, capacity) in [
+        ("trace LDE", (HEIGHT << 1) * WIDTH),
+        ("quotient LDE", (HEIGHT << 1) * d),
+    ] {
+        assert!(
+            capacities.contains(&capacity),
+            "the {buffer} didn't come from the allocator: {capacities:?}"
+        );
+    }
+}
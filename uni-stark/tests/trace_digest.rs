@@ -0,0 +1,228 @@
+mod common;
+
+use p3_air::{Air, AirBuilderWithPublicValues, BaseAir};
+use p3_field::AbstractField;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_uni_stark::{
+    prove, prove_with_public_values, setup, verify, verify_with_trace_digest, Proof,
+    VerificationError,
+};
+
+use crate::common::{config_and_challenger, Challenger, MyConfig, Val};
+
+const HEIGHT: usize = 1 << 5;
+
+/// Asserts `y = x^2` on each row, and that the first row is `(x, y)` for public values `[x, y]`, as
+/// a continuation would assert that its first row is where the previous proof's trace ended.
+struct SquareAir;
+
+impl<F> BaseAir<F> for SquareAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn num_public_values(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for SquareAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let (x, y) = (local[0], local[1]);
+        builder.assert_zero(y - x * x);
+        builder
+            .when_first_row()
+            .assert_eq_public_values(local.iter().copied());
+    }
+}
+
+/// `SquareAir`'s shape without its constraints, so that it can prove traces and public values which
+/// `SquareAir` doesn't accept.
+struct RelaxedSquareAir;
+
+impl<F> BaseAir<F> for RelaxedSquareAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn num_public_values(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for RelaxedSquareAir {
+    fn eval(&self, _builder: &mut AB) {}
+}
+
+/// A trace for `SquareAir` with `x = 3 i + first_x` on row `i`.
+fn square_trace(first_x: usize) -> RowMajorMatrix<Val> {
+    let values = (0..HEIGHT)
+        .flat_map(|i| {
+            let x = Val::from_canonical_usize(3 * i + first_x);
+            [x, x * x]
+        })
+        .collect();
+    RowMajorMatrix::new(values, 2)
+}
+
+/// The public values which `square_trace(first_x)` satisfies.
+fn square_public_values(first_x: usize) -> Vec<Val> {
+    let x = Val::from_canonical_usize(first_x);
+    vec![x, x * x]
+}
+
+fn prove_square(config: &MyConfig, challenger: &Challenger, first_x: usize) -> Proof<MyConfig> {
+    let (pk, _) = setup(config, &SquareAir);
+    prove_with_public_values(
+        config,
+        &pk,
+        &SquareAir,
+        &mut challenger.clone(),
+        square_trace(first_x),
+        square_public_values(first_x),
+    )
+}
+
+#[test]
+fn test_trace_digest_round_trip() {
+    let (config, challenger) = config_and_challenger();
+    let (_, vk) = setup(&config, &SquareAir);
+    let proof = prove_square(&config, &challenger, 1);
+    assert_eq!(proof.public_values(), square_public_values(1));
+
+    let serialized_proof = postcard::to_allocvec(&proof).expect("unable to serialize proof");
+    let proof: Proof<MyConfig> =
+        postcard::from_bytes(&serialized_proof).expect("unable to deserialize proof");
+    let digest = *proof.trace_digest();
+
+    verify(&config, &vk, &SquareAir, &mut challenger.clone(), &proof).expect("verification failed");
+    verify_with_trace_digest(
+        &config,
+        &vk,
+        &SquareAir,
+        &mut challenger.clone(),
+        &proof,
+        &digest,
+    )
+    .expect("verification failed");
+
+    let other_digest = *prove_square(&config, &challenger, 2).trace_digest();
+    assert!(matches!(
+        verify_with_trace_digest(
+            &config,
+            &vk,
+            &SquareAir,
+            &mut challenger.clone(),
+            &proof,
+            &other_digest,
+        ),
+        Err(VerificationError::TraceDigestMismatch)
+    ));
+}
+
+/// Splices the encoding of `other`'s trace digest, at the start of a proof, into `proof`.
+fn with_trace_digest_of(proof: &Proof<MyConfig>, other: &Proof<MyConfig>) -> Proof<MyConfig> {
+    let digest_len = postcard::to_allocvec(proof.trace_digest())
+        .expect("unable to serialize digest")
+        .len();
+    let mut bytes =
+        postcard::to_allocvec(other.trace_digest()).expect("unable to serialize digest");
+    bytes.extend_from_slice(
+        &postcard::to_allocvec(proof).expect("unable to serialize proof")[digest_len..],
+    );
+    postcard::from_bytes(&bytes).expect("unable to deserialize proof")
+}
+
+#[test]
+fn test_trace_digest_is_bound_to_trace() {
+    let (config, challenger) = config_and_challenger();
+    let (_, vk) = setup(&config, &SquareAir);
+    let proof = prove_square(&config, &challenger, 1);
+    let other = prove_square(&config, &challenger, 2);
+
+    // Claiming another trace's digest fails, since the openings don't match it.
+    let tampered = with_trace_digest_of(&proof, &other);
+    assert_eq!(tampered.trace_digest(), other.trace_digest());
+    assert!(verify_with_trace_digest(
+        &config,
+        &vk,
+        &SquareAir,
+        &mut challenger.clone(),
+        &tampered,
+        other.trace_digest(),
+    )
+    .is_err());
+}
+
+#[test]
+fn test_proof_is_bound_to_public_values() {
+    let (config, challenger) = config_and_challenger();
+    let (_, vk) = setup(&config, &SquareAir);
+    let proof = prove_square(&config, &challenger, 1);
+    let other = prove_square(&config, &challenger, 2);
+
+    // The public values are the last field of a proof, so replace the encoding at the end of one
+    // proof with that at the end of the other.
+    let encoded_len = |proof: &Proof<MyConfig>| {
+        postcard::to_allocvec(proof.public_values())
+            .expect("unable to serialize public values")
+            .len()
+    };
+    let mut bytes = postcard::to_allocvec(&proof).expect("unable to serialize proof");
+    let other_bytes = postcard::to_allocvec(&other).expect("unable to serialize proof");
+    bytes.truncate(bytes.len() - encoded_len(&proof));
+    bytes.extend_from_slice(&other_bytes[other_bytes.len() - encoded_len(&other)..]);
+    let tampered: Proof<MyConfig> =
+        postcard::from_bytes(&bytes).expect("unable to deserialize proof");
+    assert_eq!(tampered.public_values(), other.public_values());
+
+    assert!(verify(&config, &vk, &SquareAir, &mut challenger.clone(), &tampered).is_err());
+}
+
+#[test]
+#[should_panic(expected = "wrong number of public values for this AIR")]
+fn test_prove_without_public_values_panics() {
+    let (config, challenger) = config_and_challenger();
+    let (pk, _) = setup(&config, &SquareAir);
+    prove(
+        &config,
+        &pk,
+        &SquareAir,
+        &mut challenger.clone(),
+        square_trace(1),
+    );
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "constraints had nonzero value on row 0")]
+fn test_public_values_violated_debug() {
+    let (config, challenger) = config_and_challenger();
+    let (pk, _) = setup(&config, &SquareAir);
+    prove_with_public_values(
+        &config,
+        &pk,
+        &SquareAir,
+        &mut challenger.clone(),
+        square_trace(1),
+        square_public_values(2),
+    );
+}
+
+#[test]
+fn test_public_values_violated_rejected() {
+    let (config, challenger) = config_and_challenger();
+    let (pk, vk) = setup(&config, &SquareAir);
+    let proof = prove_with_public_values(
+        &config,
+        &pk,
+        &RelaxedSquareAir,
+        &mut challenger.clone(),
+        square_trace(1),
+        square_public_values(2),
+    );
+    assert!(verify(&config, &vk, &SquareAir, &mut challenger.clone(), &proof).is_err());
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use p3_field::{AbstractField, TwoAdicField};
+use p3_uni_stark::{prove, setup, verify, Proof, StarkGenericConfig, VerificationError};
+
+use crate::common::{fib_trace, pcs, random_perm, Challenger, FibAir, MyConfig, Perm, Val};
+
+const HEIGHT: usize = 1 << 5;
+
+fn shifted_config(perm: &Perm, trace_domain_shift: Val) -> MyConfig {
+    MyConfig::new_with_trace_domain_shift(pcs(perm), trace_domain_shift)
+}
+
+/// A shift whose ratio with the PCS's coset shift (the generator) lies outside every two-adic
+/// subgroup, so that the trace domain is disjoint from the LDE domain.
+fn nontrivial_shift() -> Val {
+    Val::generator().square()
+}
+
+fn prove_fib(config: &MyConfig, perm: &Perm) -> Proof<MyConfig> {
+    let mut challenger = Challenger::new(perm.clone());
+    let (pk, _) = setup(config, &FibAir);
+    prove::<MyConfig, _>(config, &pk, &FibAir, &mut challenger, fib_trace(HEIGHT))
+}
+
+fn verify_fib(
+    config: &MyConfig,
+    perm: &Perm,
+    proof: &Proof<MyConfig>,
+) -> Result<(), VerificationError> {
+    let mut challenger = Challenger::new(perm.clone());
+    let (_, vk) = setup(config, &FibAir);
+    verify(config, &vk, &FibAir, &mut challenger, proof)
+}
+
+#[test]
+fn test_prove_over_coset() -> Result<(), VerificationError> {
+    let perm = random_perm();
+    let config = shifted_config(&perm, nontrivial_shift());
+    let proof = prove_fib(&config, &perm);
+
+    let serialized_proof = postcard::to_allocvec(&proof).expect("unable to serialize proof");
+    let deserialized_proof =
+        postcard::from_bytes(&serialized_proof).expect("unable to deserialize proof");
+    verify_fib(&config, &perm, &deserialized_proof)
+}
+
+#[test]
+fn test_unit_shift_matches_subgroup() -> Result<(), VerificationError> {
+    let perm = random_perm();
+    let unit_shift_config = shifted_config(&perm, Val::one());
+    let subgroup_config = MyConfig::new(pcs(&perm));
+    assert_eq!(subgroup_config.trace_domain_shift(), Val::one());
+
+    let proof = prove_fib(&unit_shift_config, &perm);
+    verify_fib(&subgroup_config, &perm, &proof)?;
+    let proof = prove_fib(&subgroup_config, &perm);
+    verify_fib(&unit_shift_config, &perm, &proof)
+}
+
+#[test]
+fn test_mismatched_shift_fails() {
+    let perm = random_perm();
+    let proof = prove_fib(&shifted_config(&perm, nontrivial_shift()), &perm);
+    let subgroup_config = MyConfig::new(pcs(&perm));
+    assert!(verify_fib(&subgroup_config, &perm, &proof).is_err());
+}
+
+#[test]
+#[should_panic(expected = "makes the trace domain overlap the PCS's LDE coset")]
+fn test_coset_shift_rejected() {
+    shifted_config(&random_perm(), Val::generator());
+}
+
+#[test]
+#[should_panic(expected = "makes the trace domain overlap the PCS's LDE coset")]
+fn test_overlapping_shift_rejected() {
+    // For a two-adic root of unity `w`, the trace domain `g w H` lies within the LDE coset `g K`.
+    shifted_config(
+        &random_perm(),
+        Val::generator() * Val::two_adic_generator(3),
+    );
+}
+
+#[test]
+#[should_panic(expected = "the trace domain shift must be nonzero")]
+fn test_zero_shift_rejected() {
+    shifted_config(&random_perm(), Val::zero());
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use p3_air::{Air, AirBuilder, BaseAir, PairBuilder};
+use p3_field::{AbstractField, Field};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_uni_stark::{prove, setup, verify, Proof, ProvingKey};
+
+use crate::common::{config_and_challenger, Challenger, MyConfig, Val};
+
+const HEIGHT: usize = 1 << 5;
+
+const FLAG: usize = 0;
+const X: usize = 1;
+const Y: usize = 2;
+const WIDTH: usize = 3;
+
+/// Whether the preprocessed selector is set on row `i`.
+fn preprocessed_selected(i: usize) -> bool {
+    i % 5 == 2
+}
+
+/// Whether the main trace's `FLAG` is set on row `i`.
+fn flag_selected(i: usize) -> bool {
+    i % 7 == 0 || i == 12
+}
+
+/// Asserts `x = 0` on the rows where `flag` is set, and `y = 0` on the rows where the preprocessed
+/// selector is set.
+///
+/// A `relaxed` AIR has the same shape but no constraints, so that it can prove traces which the
+/// strict AIR doesn't accept.
+struct SegmentAir {
+    relaxed: bool,
+}
+
+impl<F: Field> BaseAir<F> for SegmentAir {
+    fn width(&self) -> usize {
+        WIDTH
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        let values = (0..HEIGHT)
+            .map(|i| F::from_bool(preprocessed_selected(i)))
+            .collect();
+        Some(RowMajorMatrix::new_col(values))
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: PairBuilder> Air<AB> for SegmentAir {
+    fn eval(&self, builder: &mut AB) {
+        if self.relaxed {
+            return;
+        }
+        let main = builder.main();
+        let local = main.row_slice(0);
+        builder.assert_bool(local[FLAG]);
+        builder.when_rows(FLAG).assert_zero(local[X]);
+        builder.when_preprocessed_rows(0).assert_zero(local[Y]);
+    }
+}
+
+/// A trace for `SegmentAir` in which `x` and `y` are zero exactly where they're constrained to be,
+/// with one added to the entry at `(row, column)` of `corrupt`, if given.
+fn segment_trace(corrupt: Option<(usize, usize)>) -> RowMajorMatrix<Val> {
+    let mut values = Vec::with_capacity(HEIGHT * WIDTH);
+    for i in 0..HEIGHT {
+        let flag = flag_selected(i);
+        let x = if flag { 0 } else { 3 * i + 1 };
+        let y = if preprocessed_selected(i) { 0 } else { i + 4 };
+        values.extend([
+            Val::from_bool(flag),
+            Val::from_canonical_usize(x),
+            Val::from_canonical_usize(y),
+        ]);
+    }
+    if let Some((row, col)) = corrupt {
+        values[row * WIDTH + col] += Val::one();
+    }
+    RowMajorMatrix::new(values, WIDTH)
+}
+
+fn prove_segment(
+    config: &MyConfig,
+    pk: &ProvingKey<MyConfig>,
+    challenger: &Challenger,
+    relaxed: bool,
+    corrupt: Option<(usize, usize)>,
+) -> Proof<MyConfig> {
+    prove(
+        config,
+        pk,
+        &SegmentAir { relaxed },
+        &mut challenger.clone(),
+        segment_trace(corrupt),
+    )
+}
+
+#[test]
+fn test_when_rows() {
+    let (config, challenger) = config_and_challenger();
+    let air = SegmentAir { relaxed: false };
+    let (pk, vk) = setup(&config, &air);
+    // Changing `x` or `y` on a row where its selector is zero is unconstrained, so still verifies.
+    for corrupt in [
+        None,
+        Some((1, X)),
+        Some((13, Y)),
+        Some((2, X)),
+        Some((8, Y)),
+    ] {
+        let proof = prove_segment(&config, &pk, &challenger, false, corrupt);
+        verify(&config, &vk, &air, &mut challenger.clone(), &proof).expect("verification failed");
+    }
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "constraints had nonzero value on row 12")]
+fn test_when_rows_violated_debug() {
+    let (config, challenger) = config_and_challenger();
+    let (pk, _) = setup(&config, &SegmentAir { relaxed: false });
+    prove_segment(&config, &pk, &challenger, false, Some((12, X)));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "constraints had nonzero value on row 17")]
+fn test_when_preprocessed_rows_violated_debug() {
+    let (config, challenger) = config_and_challenger();
+    let (pk, _) = setup(&config, &SegmentAir { relaxed: false });
+    prove_segment(&config, &pk, &challenger, false, Some((17, Y)));
+}
+
+#[test]
+fn test_when_rows_violated_rejected() {
+    let (config, challenger) = config_and_challenger();
+    let air = SegmentAir { relaxed: false };
+    let (pk, vk) = setup(&config, &air);
+    for corrupt in [(12, X), (21, X), (17, Y), (2, Y)] {
+        let proof = prove_segment(&config, &pk, &challenger, true, Some(corrupt));
+        assert!(
+            verify(&config, &vk, &air, &mut challenger.clone(), &proof).is_err(),
+            "{corrupt:?} wasn't enforced"
+        );
+    }
+}
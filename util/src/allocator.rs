@@ -0,0 +1,23 @@
+use alloc::vec::Vec;
+
+/// Provides the buffers the prover allocates for large, trace-sized data, such as LDEs and
+/// quotient evaluations. Smaller, short-lived allocations don't go through it.
+///
+/// The returned vectors are ordinary `Vec`s, which the global allocator frees when they are
+/// dropped, so their memory must come from the global allocator. Within that constraint an
+/// implementation can, for example, advise the kernel to back large buffers with huge pages, or
+/// touch their pages from threads pinned to a particular NUMA node before returning them.
+pub trait TraceAllocator: Sync {
+    /// Return an empty vector with room for at least `capacity` elements.
+    fn allocate<T>(&self, capacity: usize) -> Vec<T>;
+}
+
+/// A `TraceAllocator` which allocates directly from the global allocator.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GlobalTraceAllocator;
+
+impl TraceAllocator for GlobalTraceAllocator {
+    fn allocate<T>(&self, capacity: usize) -> Vec<T> {
+        Vec::with_capacity(capacity)
+    }
+}
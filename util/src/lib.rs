@@ -6,6 +6,7 @@ extern crate alloc;
 
 use core::hint::unreachable_unchecked;
 
+pub mod allocator;
 pub mod array_serialization;
 pub mod linear_map;
 
@@ -0,0 +1,174 @@
+//! A verifier for the standard BabyBear + Poseidon2 + FRI configuration, with a byte-oriented API
+//! free of generics, so that it can be exposed directly through FFI layers like `wasm-bindgen`.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::BabyBear;
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field, PrimeField32};
+use p3_fri::{FriConfig, TwoAdicFriPcs, TwoAdicFriPcsConfig};
+use p3_matrix::MatrixRowSlices;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{DiffusionMatrixBabybear, Poseidon2};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    setup, verify, Proof, ProvingKey, StarkConfig, SymbolicAirBuilder, VerifierConstraintFolder,
+};
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+
+pub type Val = BabyBear;
+pub type Challenge = BinomialExtensionField<Val, 4>;
+pub type Perm = Poseidon2<Val, DiffusionMatrixBabybear, 16, 7>;
+pub type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+pub type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+pub type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+pub type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+pub type Dft = Radix2DitParallel;
+pub type Challenger = DuplexChallenger<Val, Perm, 16>;
+pub type Pcs =
+    TwoAdicFriPcs<TwoAdicFriPcsConfig<Val, Challenge, Challenger, Dft, ValMmcs, ChallengeMmcs>>;
+pub type MyConfig = StarkConfig<Val, Challenge, Pcs, Challenger>;
+
+const LOG_BLOWUP: usize = 1;
+const NUM_QUERIES: usize = 40;
+const PROOF_OF_WORK_BITS: usize = 8;
+
+/// Everything the verifier needs besides the proof, encoded with `postcard`.
+#[derive(Serialize, Deserialize)]
+pub struct VerifyingKey {
+    /// The seed from which the Poseidon2 round constants are sampled.
+    pub poseidon2_seed: u64,
+    /// The STARK's verifying key for the AIR, so that verifying doesn't run `setup` again.
+    pub stark_vk: p3_uni_stark::VerifyingKey<MyConfig>,
+}
+
+impl VerifyingKey {
+    pub fn perm(&self) -> Perm {
+        perm(self.poseidon2_seed)
+    }
+
+    /// The STARK configuration, along with the permutation used to initialize challengers.
+    pub fn config(&self) -> (MyConfig, Perm) {
+        config(self.poseidon2_seed)
+    }
+}
+
+/// Run `setup` for `air` once, in the configuration given by `poseidon2_seed`, giving the proving
+/// key and the `VerifyingKey` to encode for `verify_babybear_poseidon2_air`.
+pub fn setup_babybear_poseidon2<A>(
+    poseidon2_seed: u64,
+    air: &A,
+) -> (ProvingKey<MyConfig>, VerifyingKey)
+where
+    A: Air<SymbolicAirBuilder<Val>>,
+{
+    let (config, _) = config(poseidon2_seed);
+    let (pk, stark_vk) = setup(&config, air);
+    let vk = VerifyingKey {
+        poseidon2_seed,
+        stark_vk,
+    };
+    (pk, vk)
+}
+
+fn perm(poseidon2_seed: u64) -> Perm {
+    let mut rng = ChaCha20Rng::seed_from_u64(poseidon2_seed);
+    Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng)
+}
+
+fn config(poseidon2_seed: u64) -> (MyConfig, Perm) {
+    let perm = perm(poseidon2_seed);
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let fri_config = FriConfig {
+        log_blowup: LOG_BLOWUP,
+        num_queries: NUM_QUERIES,
+        proof_of_work_bits: PROOF_OF_WORK_BITS,
+        mmcs: ChallengeMmcs::new(val_mmcs.clone()),
+    };
+    let pcs = Pcs::new(fri_config, Dft {}, val_mmcs);
+    (MyConfig::new(pcs), perm)
+}
+
+/// The AIR checked by `verify_babybear_poseidon2`, which asserts `a * b = c` on each row.
+pub struct MulAir;
+
+impl<F> BaseAir<F> for MulAir {
+    fn width(&self) -> usize {
+        3
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for MulAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        builder.assert_zero(local[0] * local[1] - local[2]);
+    }
+}
+
+/// Verify a `postcard`-encoded proof of `MulAir`, given a `postcard`-encoded `VerifyingKey` which
+/// `setup_babybear_poseidon2` computed for `MulAir`.
+///
+/// `MulAir` has no public values, so `public_values` must be empty.
+pub fn verify_babybear_poseidon2(
+    proof_bytes: &[u8],
+    vk_bytes: &[u8],
+    public_values: &[u32],
+) -> Result<(), String> {
+    verify_babybear_poseidon2_air(&MulAir, proof_bytes, vk_bytes, public_values)
+}
+
+/// Like `verify_babybear_poseidon2`, but for an arbitrary AIR, whose `VerifyingKey` must also come
+/// from `setup_babybear_poseidon2`. A wasm wrapper instantiates this with its own AIR to get a
+/// non-generic entry point.
+///
+/// `public_values` are the AIR's public inputs, as canonical BabyBear elements. The proof is only
+/// accepted if it was made with exactly these public values.
+pub fn verify_babybear_poseidon2_air<A>(
+    air: &A,
+    proof_bytes: &[u8],
+    vk_bytes: &[u8],
+    public_values: &[u32],
+) -> Result<(), String>
+where
+    A: BaseAir<Val> + for<'a> Air<VerifierConstraintFolder<'a, Challenge>>,
+{
+    let public_values = public_values
+        .iter()
+        .map(|&value| {
+            if value < Val::ORDER_U32 {
+                Ok(Val::from_canonical_u32(value))
+            } else {
+                Err(format!(
+                    "public value {value} is not a canonical BabyBear element"
+                ))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let vk: VerifyingKey =
+        postcard::from_bytes(vk_bytes).map_err(|e| format!("invalid verifying key: {e}"))?;
+    let proof: Proof<MyConfig> =
+        postcard::from_bytes(proof_bytes).map_err(|e| format!("invalid proof encoding: {e}"))?;
+    if proof.public_values() != public_values {
+        return Err(String::from(
+            "the proof was made with different public values",
+        ));
+    }
+
+    let (config, perm) = vk.config();
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &vk.stark_vk, air, &mut challenger, &proof).map_err(|e| format!("{e:?}"))
+}
@@ -0,0 +1,134 @@
+use p3_air::{Air, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_uni_stark::{prove, prove_with_public_values, ProvingKey};
+use p3_wasm_verifier::{
+    setup_babybear_poseidon2, verify_babybear_poseidon2, verify_babybear_poseidon2_air, Challenger,
+    MulAir, MyConfig, Val, VerifyingKey,
+};
+use rand::{thread_rng, Rng};
+
+/// Asserts that each row is `x, x + 1, ...`, starting from the public value `x`.
+struct CounterAir;
+
+impl<F> BaseAir<F> for CounterAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn num_public_values(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for CounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0)[0], main.row_slice(1)[0]);
+        let start = builder.public_values()[0].clone();
+        builder.when_first_row().assert_eq(local, start);
+        builder
+            .when_transition()
+            .assert_eq(next, local + AB::Expr::one());
+    }
+}
+
+fn prove_mul_air(pk: &ProvingKey<MyConfig>, vk: &VerifyingKey) -> Vec<u8> {
+    let mut rng = thread_rng();
+    let height = 1 << 6;
+    let mut values = Vec::with_capacity(height * 3);
+    for _ in 0..height {
+        let a = rng.gen::<Val>();
+        let b = rng.gen::<Val>();
+        values.extend([a, b, a * b]);
+    }
+    let trace = RowMajorMatrix::new(values, 3);
+
+    let (config, perm) = vk.config();
+    let mut challenger = Challenger::new(perm);
+    let proof = prove::<MyConfig, _>(&config, pk, &MulAir, &mut challenger, trace);
+    postcard::to_allocvec(&proof).expect("unable to serialize proof")
+}
+
+fn prove_counter_air(pk: &ProvingKey<MyConfig>, vk: &VerifyingKey, start: u32) -> Vec<u8> {
+    let start = Val::from_canonical_u32(start);
+    let values = (0..1 << 6)
+        .map(|i| start + Val::from_canonical_usize(i))
+        .collect();
+    let trace = RowMajorMatrix::new(values, 1);
+
+    let (config, perm) = vk.config();
+    let mut challenger = Challenger::new(perm);
+    let proof = prove_with_public_values::<MyConfig, _>(
+        &config,
+        pk,
+        &CounterAir,
+        &mut challenger,
+        trace,
+        vec![start],
+    );
+    postcard::to_allocvec(&proof).expect("unable to serialize proof")
+}
+
+fn vk_bytes(vk: &VerifyingKey) -> Vec<u8> {
+    postcard::to_allocvec(vk).expect("unable to serialize verifying key")
+}
+
+#[test]
+fn test_accepts_valid_proof() {
+    let (pk, vk) = setup_babybear_poseidon2(1, &MulAir);
+    let proof_bytes = prove_mul_air(&pk, &vk);
+    assert_eq!(
+        verify_babybear_poseidon2(&proof_bytes, &vk_bytes(&vk), &[]),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_rejects_corrupted_proof() {
+    let (pk, vk) = setup_babybear_poseidon2(1, &MulAir);
+    let mut proof_bytes = prove_mul_air(&pk, &vk);
+    let mid = proof_bytes.len() / 2;
+    proof_bytes[mid] ^= 1;
+    assert!(verify_babybear_poseidon2(&proof_bytes, &vk_bytes(&vk), &[]).is_err());
+}
+
+#[test]
+fn test_rejects_wrong_verifying_key() {
+    let (pk, vk) = setup_babybear_poseidon2(1, &MulAir);
+    let proof_bytes = prove_mul_air(&pk, &vk);
+    let (_, other_vk) = setup_babybear_poseidon2(2, &MulAir);
+    assert!(verify_babybear_poseidon2(&proof_bytes, &vk_bytes(&other_vk), &[]).is_err());
+}
+
+#[test]
+fn test_rejects_public_values() {
+    let (pk, vk) = setup_babybear_poseidon2(1, &MulAir);
+    let proof_bytes = prove_mul_air(&pk, &vk);
+    assert!(verify_babybear_poseidon2(&proof_bytes, &vk_bytes(&vk), &[1]).is_err());
+}
+
+#[test]
+fn test_accepts_matching_public_values() {
+    let (pk, vk) = setup_babybear_poseidon2(1, &CounterAir);
+    let proof_bytes = prove_counter_air(&pk, &vk, 5);
+    assert_eq!(
+        verify_babybear_poseidon2_air(&CounterAir, &proof_bytes, &vk_bytes(&vk), &[5]),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_rejects_mismatched_public_values() {
+    let (pk, vk) = setup_babybear_poseidon2(1, &CounterAir);
+    let proof_bytes = prove_counter_air(&pk, &vk, 5);
+    let vk_bytes = vk_bytes(&vk);
+    for public_values in [&[][..], &[6], &[5, 5], &[5 + Val::ORDER_U32]] {
+        assert!(
+            verify_babybear_poseidon2_air(&CounterAir, &proof_bytes, &vk_bytes, public_values)
+                .is_err(),
+            "public values {public_values:?} weren't rejected"
+        );
+    }
+}